@@ -3,7 +3,8 @@
 //! Build script for Hongdown.
 //!
 //! This script reads the proper-nouns.tsv file and generates Rust constants
-//! for use in the sentence case converter.
+//! for use in the sentence case converter, keeping the category each proper
+//! noun belongs to.
 
 use std::env;
 use std::fs;
@@ -19,10 +20,21 @@ fn main() {
 
     // Parse the TSV file
     let mut proper_nouns = Vec::new();
+    let mut categories: Vec<String> = Vec::new();
 
     for line in content.lines() {
         let line = line.trim();
 
+        // A comment of the form `# [tag] Title` starts a category
+        if let Some(tag) = line
+            .strip_prefix("# [")
+            .and_then(|rest| rest.split_once(']'))
+            .map(|(tag, _)| tag)
+        {
+            categories.push(tag.to_string());
+            continue;
+        }
+
         // Skip empty lines and comments
         if line.is_empty() || line.starts_with('#') {
             continue;
@@ -33,7 +45,11 @@ fn main() {
         let canonical = line.replace('\'', "\u{2019}");
         let lowercase_key = canonical.to_lowercase();
 
-        proper_nouns.push((canonical, lowercase_key));
+        let category = categories
+            .last()
+            .expect("Proper noun outside of any category")
+            .clone();
+        proper_nouns.push((canonical, lowercase_key, category));
     }
 
     // Generate Rust code
@@ -46,13 +62,27 @@ fn main() {
     writeln!(f, "/// Built-in proper nouns for sentence case conversion.").unwrap();
     writeln!(
         f,
-        "/// Each tuple contains (canonical_form, lowercase_search_key)."
+        "/// Each tuple contains (canonical_form, lowercase_search_key, category)."
     )
     .unwrap();
-    writeln!(f, "pub const PROPER_NOUNS: &[(&str, &str)] = &[").unwrap();
+    writeln!(f, "pub const PROPER_NOUNS: &[(&str, &str, &str)] = &[").unwrap();
+
+    for (canonical, lowercase_key, category) in proper_nouns {
+        writeln!(
+            f,
+            "    (\"{}\", \"{}\", \"{}\"),",
+            canonical, lowercase_key, category
+        )
+        .unwrap();
+    }
+
+    writeln!(f, "];").unwrap();
+    writeln!(f).unwrap();
+    writeln!(f, "/// Tags of the categories of built-in proper nouns.").unwrap();
+    writeln!(f, "pub const PROPER_NOUN_CATEGORIES: &[&str] = &[").unwrap();
 
-    for (canonical, lowercase_key) in proper_nouns {
-        writeln!(f, "    (\"{}\", \"{}\"),", canonical, lowercase_key).unwrap();
+    for category in categories {
+        writeln!(f, "    \"{}\",", category).unwrap();
     }
 
     writeln!(f, "];").unwrap();
@@ -46,6 +46,99 @@ export interface FormatOptions {
    */
   headingSentenceCase?: boolean;
 
+  /**
+   * Instead of converting headings to sentence case, leave them untouched
+   * and warn about each heading sentence case would change, along with the
+   * words it would lowercase.
+   * @default false
+   */
+  headingSentenceCaseReport?: boolean;
+
+  /**
+   * Convert the text of links in headings along with the rest of the
+   * heading; if disabled, it is left as written.  Their URLs and labels are
+   * never converted.
+   * @default true
+   */
+  headingSentenceCaseLinks?: boolean;
+
+  /**
+   * Convert the header cells of tables to sentence case.
+   * @default false
+   */
+  headingSentenceCaseTableHeaders?: boolean;
+
+  /**
+   * Convert the terms of description lists to sentence case.
+   * @default false
+   */
+  headingSentenceCaseTerms?: boolean;
+
+  /**
+   * How the case of headings is converted.  `"sentence"` capitalizes only
+   * the first word and proper nouns, and `"title"` capitalizes every word
+   * except short articles, conjunctions, and prepositions.
+   * Takes precedence over `headingSentenceCase` unless it is `"preserve"`.
+   * @default "preserve"
+   */
+  headingCase?: "sentence" | "title" | "preserve";
+
+  /**
+   * The style guide whose rules title case follows: `"chicago"` lowercases
+   * all prepositions, and `"ap"` only those of up to three letters.
+   * @default "chicago"
+   */
+  headingTitleCaseStyle?: "chicago" | "ap";
+
+  /**
+   * Shift headings so that the shallowest one is at this level, e.g., `2` to
+   * turn h1s into h2s and h2s into h3s.  Levels are kept between 1 and 6.
+   * `0` leaves the levels alone.
+   * @default 0
+   */
+  headingTopLevel?: number;
+
+  /**
+   * Shift headings so that the first one becomes an h1.
+   * Overrides `headingTopLevel`.
+   * @default false
+   */
+  headingPromoteFirst?: boolean;
+
+  /**
+   * Close gaps in the heading hierarchy, so that no heading is more than one
+   * level deeper than the heading it belongs to.
+   * @default false
+   */
+  headingFixSkippedLevels?: boolean;
+
+  /**
+   * Prefix top-level headings with hierarchical section numbers, such as
+   * `2.3.1`, replacing any numbers they already start with.
+   * @default false
+   */
+  headingNumbering?: boolean;
+
+  /**
+   * Highest heading level (h1 being the highest) to number.
+   * @default 2
+   */
+  headingNumberingMinLevel?: number;
+
+  /**
+   * Lowest heading level to number.
+   * @default 6
+   */
+  headingNumberingMaxLevel?: number;
+
+  /**
+   * Also replace plain numbers such as `3` that top-level numbered headings
+   * start with, which are otherwise only replaced when they are the same as
+   * the new number, as they may be part of the heading text.
+   * @default false
+   */
+  headingNumberingReplacePlain?: boolean;
+
   /**
    * Additional proper nouns to preserve in sentence case.
    * These are merged with built-in proper nouns (like "GitHub", "JavaScript").
@@ -62,6 +155,15 @@ export interface FormatOptions {
    */
   headingCommonNouns?: string[];
 
+  /**
+   * Categories of built-in proper nouns to leave out in sentence case,
+   * such as `"countries"` or `"programming-languages"`.  Formatting throws
+   * an error for an unknown category.
+   * @example ["countries"]
+   * @default []
+   */
+  headingDisabledCategories?: string[];
+
   /**
    * Marker character for unordered lists: `"-"`, `"*"`, or `"+"`.
    * @default "-"
@@ -192,6 +294,236 @@ export interface FormatOptions {
    * @default "--"
    */
   emDash?: DashSetting;
+
+  /**
+   * Normalize YAML and TOML front matter: remove trailing whitespace, make
+   * indentation and string quoting consistent, and reorder top-level keys.
+   * Comments are preserved.  Front matter that cannot be parsed, or whose
+   * data would change, is left untouched with a warning.
+   * @default false
+   */
+  frontMatterCanonicalize?: boolean;
+
+  /**
+   * Indentation width for nested YAML mappings and multiline TOML arrays in
+   * canonicalized front matter.
+   * @default 2
+   */
+  frontMatterIndentWidth?: number;
+
+  /**
+   * Quoting style for strings that are already quoted in canonicalized
+   * front matter.
+   * @default "preserve"
+   */
+  frontMatterQuoteStyle?: "preserve" | "double" | "single";
+
+  /**
+   * Top-level front matter keys to move to the beginning, in this order.
+   * @default []
+   */
+  frontMatterKeyOrder?: string[];
+
+  /**
+   * What to do with reference definitions that no link uses: remove them,
+   * keep them, or keep them and emit a warning.
+   * @default "remove"
+   */
+  linkUnusedReferences?: "remove" | "keep" | "warn";
+
+  /**
+   * What to do with different reference labels that point to the same URL:
+   * keep them, emit a warning, or rewrite links to use the first label.
+   * @default "keep"
+   */
+  linkDuplicateUrls?: "keep" | "warn" | "merge";
+
+  /**
+   * Whether to emit a warning when the same reference label is defined
+   * twice with different URLs.
+   * @default "warn"
+   */
+  linkConflictingLabels?: "warn" | "ignore";
+
+  /**
+   * Where reference and footnote definitions are placed: at the end of each
+   * section (before h2 and h3 headings), at the end of each h2 section, all
+   * at the end of the document, or right after the block that uses them.
+   * @default "section"
+   */
+  linkReferencePlacement?: "section" | "section-h2" | "document" | "block";
+
+  /**
+   * Whether inline external links are converted to reference-style links or
+   * kept inline.
+   * @default "reference"
+   */
+  linkExternalLinks?: "reference" | "inline";
+
+  /**
+   * Convert only external links whose URL is wider than this many columns.
+   * @default 0
+   */
+  linkMinUrlWidth?: number;
+
+  /**
+   * Additional URL schemes (e.g., `"mailto"`, `"ftp"`) whose links are
+   * treated as external, besides `http` and `https`.
+   * @default []
+   */
+  linkExternalSchemes?: string[];
+
+  /**
+   * Domains whose links are treated as external whatever their scheme,
+   * including subdomains and protocol-relative URLs.
+   * @default []
+   */
+  linkExternalDomains?: string[];
+
+  /**
+   * Labels of external links converted to reference style: the link text,
+   * a number in order of appearance, or the domain of the URL.
+   * @default "text"
+   */
+  linkReferenceLabel?: "text" | "numeric" | "domain";
+
+  /**
+   * The order of reference definitions.  `"auto"` keeps the order of first
+   * use, but puts numeric labels last in numeric order when there are two or
+   * more of them.  `"numeric-then-alpha"` puts numeric labels first in
+   * numeric order, then sorts the others.
+   * @default "auto"
+   */
+  linkReferenceOrder?:
+    | "auto"
+    | "appearance"
+    | "alphabetical"
+    | "numeric-then-alpha";
+
+  /**
+   * Whether numeric reference labels (`[1]`, `[2]`, ...) are renumbered in
+   * order of first use, rewriting the links that use them.
+   * @default false
+   */
+  linkRenumberReferences?: boolean;
+
+  /**
+   * Recognize `~~strikethrough~~`.
+   * @default false
+   */
+  extensionStrikethrough?: boolean;
+
+  /**
+   * Recognize `^superscript^`.
+   * @default false
+   */
+  extensionSuperscript?: boolean;
+
+  /**
+   * Recognize `~subscript~`.
+   * @default false
+   */
+  extensionSubscript?: boolean;
+
+  /**
+   * Recognize `$inline$` and `$$display$$` math.
+   * @default false
+   */
+  extensionMath?: boolean;
+
+  /**
+   * How display math blocks are delimited: `$$` on lines of their own, or a
+   * fenced code block with the `math` info string, as GitLab uses.
+   * @default "dollar"
+   */
+  extensionMathDelimiters?: "dollar" | "fence";
+
+  /**
+   * Recognize bare URLs, `www.` links, and email addresses.
+   * @default false
+   */
+  extensionAutolink?: boolean;
+
+  /**
+   * Recognize `__underline__` instead of `__strong__`.
+   * @default false
+   */
+  extensionUnderline?: boolean;
+
+  /**
+   * Recognize `||spoiler||`.
+   * @default false
+   */
+  extensionSpoiler?: boolean;
+
+  /**
+   * Recognize `:emoji:` shortcodes.
+   * @default false
+   */
+  extensionShortcodes?: boolean;
+
+  /**
+   * Recognize `[[Page|alias]]` wikilinks and `![[file]]` embeds, which are
+   * kept verbatim.
+   * @default false
+   */
+  extensionWikilinks?: boolean;
+
+  /**
+   * Treat the document as MDX: ESM statements, JSX block elements, and
+   * `{expressions}` are kept verbatim.
+   * @default false
+   */
+  extensionMdx?: boolean;
+
+  /**
+   * Recognize `:::type` containers and Pandoc fenced divs.
+   * @default false
+   */
+  extensionContainers?: boolean;
+
+  /**
+   * Whether containers of the alert types and GitHub alerts are converted to
+   * each other: `"alert"` writes `:::note` containers as `> [!NOTE]` alerts,
+   * and `"container"` does the reverse.
+   * @default "preserve"
+   */
+  extensionContainerStyle?: "preserve" | "alert" | "container";
+
+  /**
+   * Recognize Obsidian-style callouts of any type, e.g., `> [!faq]-`.
+   * Titles and fold markers of alerts are kept either way.
+   * @default false
+   */
+  extensionCallouts?: boolean;
+
+  /**
+   * How the types of alerts and callouts are capitalized.
+   * @default "upper"
+   */
+  extensionCalloutTypeCase?: "upper" | "lower" | "preserve";
+
+  /**
+   * The renderer alerts and callouts are written for.  `"github"` warns
+   * about callout types that GitHub does not support.
+   * @default "any"
+   */
+  extensionCalloutTarget?: "any" | "github";
+
+  /**
+   * Pairs of opening and closing delimiters of templating syntax, such as
+   * `[["{{", "}}"], ["{%", "%}"]]`.  Template tags are kept verbatim.
+   * @default []
+   */
+  templateDelimiters?: [string, string][];
+
+  /**
+   * What to do about known proper nouns written in the wrong case in the
+   * body text, such as `Javascript` or `github`: `"off"`, `"warn"`, or
+   * `"fix"`.  The heading proper nouns and common nouns apply.
+   * @default "off"
+   */
+  lintProperNouns?: "off" | "warn" | "fix";
 }
 
 /**
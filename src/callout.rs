@@ -0,0 +1,177 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Obsidian-style callouts.
+//!
+//! Obsidian and Quartz extend GitHub alerts with arbitrary types, custom
+//! titles, and fold markers:
+//!
+//! ```markdown
+//! > [!faq]- Are callouts foldable?
+//! > Yes.
+//! ```
+//!
+//! Comrak only recognizes the five GitHub alert types, so the type of any
+//! other callout is replaced with `note` before parsing.  The alert it
+//! becomes spans the same lines, and the serializer writes the header back
+//! from the source line.
+
+use crate::scan;
+
+/// The alert types that GitHub supports.
+pub(crate) const GITHUB_TYPES: [&str; 5] = ["note", "tip", "important", "warning", "caution"];
+
+/// The parts of a callout header line such as `> [!tip]- Custom title`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header<'s> {
+    /// The callout type as written, e.g., `tip`.
+    pub kind: &'s str,
+    /// The fold marker: `+`, `-`, or empty.
+    pub fold: &'s str,
+    /// The custom title, or empty.
+    pub title: &'s str,
+}
+
+impl Header<'_> {
+    /// Check if the callout is one that GitHub renders as is: one of its
+    /// alert types, without a fold marker.
+    pub(crate) fn is_github_alert(&self) -> bool {
+        self.fold.is_empty() && is_github_type(self.kind)
+    }
+}
+
+/// Check if a callout type is one of the GitHub alert types, ignoring case.
+pub(crate) fn is_github_type(kind: &str) -> bool {
+    GITHUB_TYPES
+        .iter()
+        .any(|github_type| kind.eq_ignore_ascii_case(github_type))
+}
+
+/// Parse the callout header in a source line, which may be preceded by
+/// block quote markers and list item markers.
+pub(crate) fn parse_header(line: &str) -> Option<Header<'_>> {
+    let start = line.find("> [!")? + 4;
+    let rest = &line[start..];
+    let end = rest.find(']')?;
+    let kind = &rest[..end];
+    if kind.is_empty()
+        || !kind
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+    let rest = &rest[end + 1..];
+    let fold_len = usize::from(rest.starts_with(['+', '-']));
+    Some(Header {
+        kind,
+        fold: &rest[..fold_len],
+        title: rest[fold_len..].trim(),
+    })
+}
+
+/// Replace the types of the callouts in `input` that are not GitHub alert
+/// types with `note`, so that the parser recognizes them as alerts.  Lines
+/// that are written back verbatim are left alone.
+pub(crate) fn mask(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut prev_depth = 0;
+    for line in scan::scan(input) {
+        // A callout starts a block quote, so the line has to be deeper than
+        // the paragraph it would otherwise continue
+        let (depth, text) = quote_depth(line.content);
+        let opens_quote = depth > prev_depth;
+        prev_depth = if text.trim().is_empty() { 0 } else { depth };
+        match parse_header(line.content) {
+            Some(header)
+                if opens_quote
+                    && line.can_start_block
+                    && !line.is_verbatim()
+                    && !is_github_type(header.kind) =>
+            {
+                let start = line.content.find("> [!").unwrap_or(0) + 4;
+                result.push_str(&line.content[..start]);
+                result.push_str("note");
+                result.push_str(&line.text[start + header.kind.len()..]);
+            }
+            _ => result.push_str(line.text),
+        }
+    }
+    result
+}
+
+/// The number of block quote markers that start a line, possibly mixed with
+/// list item markers, and the text after them.
+fn quote_depth(line: &str) -> (usize, &str) {
+    let mut depth = 0;
+    let mut rest = line.trim_start();
+    loop {
+        if let Some(stripped) = rest.strip_prefix('>') {
+            depth += 1;
+            rest = stripped.trim_start();
+            continue;
+        }
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let marker = if digits > 0 {
+            rest[digits..].starts_with(['.', ')']).then_some(digits + 1)
+        } else {
+            rest.starts_with(['-', '*', '+']).then_some(1)
+        };
+        match marker {
+            Some(len) if rest[len..].starts_with(' ') => rest = rest[len..].trim_start(),
+            _ => return (depth, rest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("> [!tip]- Custom *title* "),
+            Some(Header {
+                kind: "tip",
+                fold: "-",
+                title: "Custom *title*",
+            })
+        );
+        assert_eq!(
+            parse_header(" -  > > [!FAQ]+"),
+            Some(Header {
+                kind: "FAQ",
+                fold: "+",
+                title: "",
+            })
+        );
+        assert_eq!(parse_header("> [!not a type]"), None);
+        assert_eq!(parse_header("> Text"), None);
+    }
+
+    #[test]
+    fn test_is_github_alert() {
+        assert!(parse_header("> [!Warning]").unwrap().is_github_alert());
+        assert!(!parse_header("> [!warning]-").unwrap().is_github_alert());
+        assert!(!parse_header("> [!abstract]").unwrap().is_github_alert());
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(
+            mask("> [!abstract]+ Summary\n> Text.\n> [!faq]\n\n> > [!bug]\n"),
+            "> [!note]+ Summary\n> Text.\n> [!faq]\n\n> > [!note]\n"
+        );
+        assert_eq!(mask(" 1. > [!todo]\n"), " 1. > [!note]\n");
+        let input = "```\n> [!abstract]\n```\n\n> [!tip] Title\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_ignores_verbatim_lines() {
+        let input = "Para\n\n    > [!custom]- Title\n\n<div>\n> [!faq]\n</div>\n";
+        assert_eq!(mask(input), input);
+        let input = "> ```\n> x\n> ```\n> [!faq]\n";
+        assert_eq!(mask(input), input);
+    }
+}
@@ -10,6 +10,8 @@ use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::{CodeFormatter, HeadingError, Options, validate_proper_noun_categories};
+
 /// The default configuration file name.
 pub const CONFIG_FILE_NAME: &str = ".hongdown.toml";
 
@@ -58,6 +60,21 @@ pub struct Config {
 
     /// Punctuation transformation options (SmartyPants-style).
     pub punctuation: PunctuationConfig,
+
+    /// Front matter formatting options.
+    pub front_matter: FrontMatterConfig,
+
+    /// Link and reference definition options.
+    pub link: LinkConfig,
+
+    /// Markdown syntax extensions.
+    pub extensions: ExtensionsConfig,
+
+    /// Templating syntax to preserve.
+    pub templates: TemplatesConfig,
+
+    /// Checks of the body text.
+    pub lint: LintConfig,
 }
 
 impl Default for Config {
@@ -74,6 +91,11 @@ impl Default for Config {
             code_block: CodeBlockConfig::default(),
             thematic_break: ThematicBreakConfig::default(),
             punctuation: PunctuationConfig::default(),
+            front_matter: FrontMatterConfig::default(),
+            link: LinkConfig::default(),
+            extensions: ExtensionsConfig::default(),
+            templates: TemplatesConfig::default(),
+            lint: LintConfig::default(),
         }
     }
 }
@@ -119,6 +141,21 @@ pub struct ConfigLayer {
 
     /// Punctuation transformation options (SmartyPants-style).
     pub punctuation: Option<PunctuationConfig>,
+
+    /// Front matter formatting options.
+    pub front_matter: Option<FrontMatterConfig>,
+
+    /// Link and reference definition options.
+    pub link: Option<LinkConfig>,
+
+    /// Markdown syntax extensions.
+    pub extensions: Option<ExtensionsConfig>,
+
+    /// Templating syntax to preserve.
+    pub templates: Option<TemplatesConfig>,
+
+    /// Checks of the body text.
+    pub lint: Option<LintConfig>,
 }
 
 impl ConfigLayer {
@@ -128,7 +165,12 @@ impl ConfigLayer {
     pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
         let content =
             std::fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
-        toml::from_str(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))
+        let mut layer: Self =
+            toml::from_str(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+        if let (Some(heading), Some(dir)) = (&mut layer.heading, path.parent()) {
+            heading.resolve_paths(dir);
+        }
+        Ok(layer)
     }
 
     /// Merge this layer on top of a base Config.
@@ -170,8 +212,76 @@ impl ConfigLayer {
         if let Some(punctuation) = self.punctuation {
             base.punctuation = punctuation;
         }
+        if let Some(front_matter) = self.front_matter {
+            base.front_matter = front_matter;
+        }
+        if let Some(link) = self.link {
+            base.link = link;
+        }
+        if let Some(extensions) = self.extensions {
+            base.extensions = extensions;
+        }
+        if let Some(templates) = self.templates {
+            base.templates = templates;
+        }
+        if let Some(lint) = self.lint {
+            base.lint = lint;
+        }
         base
     }
+
+    /// Apply the formatting options of this layer to `options`.
+    ///
+    /// Unlike [`ConfigLayer::merge_over`], this works at the field level:
+    /// only the keys present in `table` (the table this layer was
+    /// deserialized from) are applied, so a layer that sets only
+    /// `heading.sentence_case` leaves the other heading options untouched.
+    /// File collection settings (`include`, `exclude`, `git_aware`, and
+    /// `no_inherit`) are ignored since they have no meaning for a single
+    /// document.
+    pub(crate) fn apply_to_options(&self, options: &mut Options, table: &toml::Table) {
+        fn section_keys<'t>(table: &'t toml::Table, name: &str) -> impl Fn(&str) -> bool + 't {
+            let section = table.get(name).and_then(|v| v.as_table());
+            move |key| section.is_some_and(|t| t.contains_key(key))
+        }
+
+        if let Some(line_width) = self.line_width {
+            options.line_width = line_width;
+        }
+        if let Some(heading) = &self.heading {
+            heading.apply_to(options, &section_keys(table, "heading"));
+        }
+        if let Some(unordered_list) = &self.unordered_list {
+            unordered_list.apply_to(options, &section_keys(table, "unordered_list"));
+        }
+        if let Some(ordered_list) = &self.ordered_list {
+            ordered_list.apply_to(options, &section_keys(table, "ordered_list"));
+        }
+        if let Some(code_block) = &self.code_block {
+            code_block.apply_to(options, &section_keys(table, "code_block"));
+        }
+        if let Some(thematic_break) = &self.thematic_break {
+            thematic_break.apply_to(options, &section_keys(table, "thematic_break"));
+        }
+        if let Some(punctuation) = &self.punctuation {
+            punctuation.apply_to(options, &section_keys(table, "punctuation"));
+        }
+        if let Some(front_matter) = &self.front_matter {
+            front_matter.apply_to(options, &section_keys(table, "front_matter"));
+        }
+        if let Some(link) = &self.link {
+            link.apply_to(options, &section_keys(table, "link"));
+        }
+        if let Some(extensions) = &self.extensions {
+            extensions.apply_to(options, &section_keys(table, "extensions"));
+        }
+        if let Some(templates) = &self.templates {
+            templates.apply_to(options, &section_keys(table, "templates"));
+        }
+        if let Some(lint) = &self.lint {
+            lint.apply_to(options, &section_keys(table, "lint"));
+        }
+    }
 }
 
 /// Heading formatting options.
@@ -185,7 +295,46 @@ pub struct HeadingConfig {
     pub setext_h2: bool,
 
     /// Convert headings to sentence case (default: false).
-    pub sentence_case: bool,
+    /// `true` is a shorthand for `case = "sentence"`, and `"report"` only
+    /// warns about the headings sentence case would change.
+    pub sentence_case: SentenceCase,
+
+    /// Convert the text of links in headings too (default: true).
+    pub sentence_case_links: bool,
+
+    /// Also convert the header cells of tables (default: false).
+    pub sentence_case_table_headers: bool,
+
+    /// Also convert the terms of description lists (default: false).
+    pub sentence_case_terms: bool,
+
+    /// How the case of headings is converted (default: preserve).
+    pub case: HeadingCase,
+
+    /// The rules title case follows (default: chicago).
+    pub title_case_style: TitleCaseStyle,
+
+    /// Level to shift the shallowest heading to, or 0 to leave the levels
+    /// alone (default: 0).
+    pub top_level: u8,
+
+    /// Shift headings so that the first one becomes an h1 (default: false).
+    pub promote_first: bool,
+
+    /// Close gaps in the heading hierarchy (default: false).
+    pub fix_skipped_levels: bool,
+
+    /// Prefix headings with hierarchical section numbers (default: false).
+    pub numbering: bool,
+
+    /// Highest heading level to number (default: 2).
+    pub numbering_min_level: u8,
+
+    /// Lowest heading level to number (default: 6).
+    pub numbering_max_level: u8,
+
+    /// Also replace plain numbers that headings start with (default: false).
+    pub numbering_replace_plain: bool,
 
     /// Additional proper nouns to preserve (case-sensitive).
     /// These are merged with built-in proper nouns.
@@ -196,6 +345,14 @@ pub struct HeadingConfig {
     /// Useful for words like "Go" which can be either a programming language
     /// or a common verb depending on context.
     pub common_nouns: Vec<String>,
+
+    /// Files of additional proper nouns, one per line, with comments
+    /// starting with `#`.  Relative paths are resolved against the
+    /// directory of the configuration file.
+    pub proper_noun_files: Vec<PathBuf>,
+
+    /// Categories of built-in proper nouns to leave out (default: empty).
+    pub disabled_categories: Vec<String>,
 }
 
 impl Default for HeadingConfig {
@@ -203,13 +360,202 @@ impl Default for HeadingConfig {
         Self {
             setext_h1: true,
             setext_h2: true,
-            sentence_case: false,
+            sentence_case: SentenceCase::Disabled,
+            sentence_case_links: true,
+            sentence_case_table_headers: false,
+            sentence_case_terms: false,
+            case: HeadingCase::Preserve,
+            title_case_style: TitleCaseStyle::Chicago,
+            top_level: 0,
+            promote_first: false,
+            fix_skipped_levels: false,
+            numbering: false,
+            numbering_min_level: 2,
+            numbering_max_level: 6,
+            numbering_replace_plain: false,
             proper_nouns: Vec::new(),
             common_nouns: Vec::new(),
+            proper_noun_files: Vec::new(),
+            disabled_categories: Vec::new(),
+        }
+    }
+}
+
+impl HeadingConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("setext_h1") {
+            options.setext_h1 = self.setext_h1;
+        }
+        if is_set("setext_h2") {
+            options.setext_h2 = self.setext_h2;
+        }
+        if is_set("sentence_case") {
+            options.heading_sentence_case = self.sentence_case == SentenceCase::Enabled;
+            options.heading_sentence_case_report = self.sentence_case == SentenceCase::Report;
+        }
+        if is_set("sentence_case_links") {
+            options.heading_sentence_case_links = self.sentence_case_links;
+        }
+        if is_set("sentence_case_table_headers") {
+            options.heading_sentence_case_table_headers = self.sentence_case_table_headers;
+        }
+        if is_set("sentence_case_terms") {
+            options.heading_sentence_case_terms = self.sentence_case_terms;
+        }
+        if is_set("case") {
+            options.heading_case = self.case;
+        }
+        if is_set("title_case_style") {
+            options.heading_title_case_style = self.title_case_style;
+        }
+        if is_set("top_level") {
+            options.heading_top_level = self.top_level;
         }
+        if is_set("promote_first") {
+            options.heading_promote_first = self.promote_first;
+        }
+        if is_set("fix_skipped_levels") {
+            options.heading_fix_skipped_levels = self.fix_skipped_levels;
+        }
+        if is_set("numbering") {
+            options.heading_numbering = self.numbering;
+        }
+        if is_set("numbering_min_level") {
+            options.heading_numbering_min_level = self.numbering_min_level;
+        }
+        if is_set("numbering_max_level") {
+            options.heading_numbering_max_level = self.numbering_max_level;
+        }
+        if is_set("numbering_replace_plain") {
+            options.heading_numbering_replace_plain = self.numbering_replace_plain;
+        }
+        if is_set("proper_nouns") {
+            options.heading_proper_nouns = self.proper_nouns.clone();
+        }
+        if is_set("common_nouns") {
+            options.heading_common_nouns = self.common_nouns.clone();
+        }
+        if is_set("disabled_categories") {
+            options.heading_disabled_categories = self.disabled_categories.clone();
+        }
+    }
+
+    /// Resolve relative paths of proper noun files against `dir`.
+    fn resolve_paths(&mut self, dir: &Path) {
+        for path in &mut self.proper_noun_files {
+            if path.is_relative() {
+                *path = dir.join(&*path);
+            }
+        }
+    }
+
+    /// Read the proper nouns listed in the proper noun files.
+    pub fn load_proper_noun_files(&self) -> Result<Vec<String>, ConfigError> {
+        let mut nouns = Vec::new();
+        for path in &self.proper_noun_files {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+            nouns.extend(parse_proper_nouns(&content));
+        }
+        Ok(nouns)
     }
 }
 
+/// Parse a list of proper nouns in the format of the built-in list: one per
+/// line, skipping blank lines and comments that start with `#`.
+fn parse_proper_nouns(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether headings are converted to sentence case.
+/// Can be `true`, `false`, or `"report"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SentenceCase {
+    /// Headings are not converted (default).
+    #[default]
+    Disabled,
+    /// Headings are converted to sentence case.
+    Enabled,
+    /// Headings are left untouched, and each heading sentence case would
+    /// change is reported as a warning.
+    Report,
+}
+
+impl<'de> Deserialize<'de> for SentenceCase {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{self, Visitor};
+
+        struct SentenceCaseVisitor;
+
+        impl<'de> Visitor<'de> for SentenceCaseVisitor {
+            type Value = SentenceCase;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("true, false, or \"report\"")
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<SentenceCase, E>
+            where
+                E: de::Error,
+            {
+                Ok(if value {
+                    SentenceCase::Enabled
+                } else {
+                    SentenceCase::Disabled
+                })
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<SentenceCase, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "report" => Ok(SentenceCase::Report),
+                    _ => Err(de::Error::invalid_value(de::Unexpected::Str(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(SentenceCaseVisitor)
+    }
+}
+
+/// How the case of headings is converted.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingCase {
+    /// Capitalize only the first word and proper nouns.
+    Sentence,
+    /// Capitalize every word except short articles, conjunctions, and
+    /// prepositions.
+    Title,
+    /// Keep the case of the source (default).
+    #[default]
+    Preserve,
+}
+
+/// The style guide whose rules title case follows.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleCaseStyle {
+    /// The Chicago Manual of Style: all prepositions are lowercased
+    /// (default).
+    #[default]
+    Chicago,
+    /// The Associated Press Stylebook: only prepositions of up to three
+    /// letters are lowercased.
+    Ap,
+}
+
 /// Marker character for unordered lists.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
 pub enum UnorderedMarker {
@@ -441,6 +787,24 @@ pub struct UnorderedListConfig {
     pub indent_width: IndentWidth,
 }
 
+impl UnorderedListConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("unordered_marker") {
+            options.unordered_marker = self.unordered_marker;
+        }
+        if is_set("leading_spaces") {
+            options.leading_spaces = self.leading_spaces;
+        }
+        if is_set("trailing_spaces") {
+            options.trailing_spaces = self.trailing_spaces;
+        }
+        if is_set("indent_width") {
+            options.indent_width = self.indent_width;
+        }
+    }
+}
+
 /// Marker character for ordered lists.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
 pub enum OrderedMarker {
@@ -502,6 +866,24 @@ impl Default for OrderedListConfig {
     }
 }
 
+impl OrderedListConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("odd_level_marker") {
+            options.odd_level_marker = self.odd_level_marker;
+        }
+        if is_set("even_level_marker") {
+            options.even_level_marker = self.even_level_marker;
+        }
+        if is_set("pad") {
+            options.ordered_list_pad = self.pad;
+        }
+        if is_set("indent_width") {
+            options.ordered_list_indent_width = self.indent_width;
+        }
+    }
+}
+
 /// Default timeout for external formatters in seconds.
 fn default_formatter_timeout() -> u64 {
     5
@@ -659,6 +1041,39 @@ impl Default for CodeBlockConfig {
     }
 }
 
+impl CodeBlockConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("fence_char") {
+            options.fence_char = self.fence_char;
+        }
+        if is_set("min_fence_length") {
+            options.min_fence_length = self.min_fence_length;
+        }
+        if is_set("space_after_fence") {
+            options.space_after_fence = self.space_after_fence;
+        }
+        if is_set("default_language") {
+            options.default_language = self.default_language.clone();
+        }
+        if is_set("formatters") {
+            options.code_formatters = self
+                .formatters
+                .iter()
+                .map(|(lang, cfg)| {
+                    (
+                        lang.clone(),
+                        CodeFormatter {
+                            command: cfg.command().to_vec(),
+                            timeout_secs: cfg.timeout(),
+                        },
+                    )
+                })
+                .collect();
+        }
+    }
+}
+
 /// Thematic break style string (must be a valid CommonMark thematic break pattern).
 ///
 /// A valid thematic break consists of:
@@ -786,6 +1201,18 @@ impl Default for ThematicBreakConfig {
     }
 }
 
+impl ThematicBreakConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("style") {
+            options.thematic_break_style = self.style.clone();
+        }
+        if is_set("leading_spaces") {
+            options.thematic_break_leading_spaces = self.leading_spaces;
+        }
+    }
+}
+
 /// Dash pattern for en-dash or em-dash transformation.
 /// Must be a non-empty string of valid characters.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -934,17 +1361,533 @@ impl Default for PunctuationConfig {
     }
 }
 
+impl PunctuationConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("curly_double_quotes") {
+            options.curly_double_quotes = self.curly_double_quotes;
+        }
+        if is_set("curly_single_quotes") {
+            options.curly_single_quotes = self.curly_single_quotes;
+        }
+        if is_set("curly_apostrophes") {
+            options.curly_apostrophes = self.curly_apostrophes;
+        }
+        if is_set("ellipsis") {
+            options.ellipsis = self.ellipsis;
+        }
+        if is_set("en_dash") {
+            options.en_dash = self.en_dash.clone();
+        }
+        if is_set("em_dash") {
+            options.em_dash = self.em_dash.clone();
+        }
+    }
+}
+
+/// Quoting style for strings in front matter.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterQuoteStyle {
+    /// Keep strings quoted as they are (default).
+    #[default]
+    Preserve,
+    /// Use double quotes for quoted strings where possible.
+    Double,
+    /// Use single quotes for quoted strings where possible.
+    Single,
+}
+
+/// Front matter formatting options.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FrontMatterConfig {
+    /// Normalize YAML and TOML front matter (default: false).
+    /// When false, front matter is preserved verbatim.
+    pub canonicalize: bool,
+
+    /// Indentation width for nested YAML mappings and multiline TOML arrays
+    /// (default: 2).
+    pub indent_width: IndentWidth,
+
+    /// Quoting style for quoted strings (default: `preserve`).
+    pub quote_style: FrontMatterQuoteStyle,
+
+    /// Top-level keys to move to the beginning, in this order (default: empty).
+    /// Other keys keep their original order after these.
+    pub key_order: Vec<String>,
+}
+
+impl Default for FrontMatterConfig {
+    fn default() -> Self {
+        Self {
+            canonicalize: false,
+            indent_width: IndentWidth::new(2).unwrap(),
+            quote_style: FrontMatterQuoteStyle::default(),
+            key_order: Vec::new(),
+        }
+    }
+}
+
+impl FrontMatterConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("canonicalize") {
+            options.front_matter_canonicalize = self.canonicalize;
+        }
+        if is_set("indent_width") {
+            options.front_matter_indent_width = self.indent_width;
+        }
+        if is_set("quote_style") {
+            options.front_matter_quote_style = self.quote_style;
+        }
+        if is_set("key_order") {
+            options.front_matter_key_order = self.key_order.clone();
+        }
+    }
+}
+
+/// What to do with reference definitions that no link uses.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UnusedReferences {
+    /// Remove unused definitions (default).
+    #[default]
+    Remove,
+    /// Keep unused definitions.
+    Keep,
+    /// Keep unused definitions and emit a warning for each.
+    Warn,
+}
+
+/// What to do with different reference labels that point to the same URL.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateUrls {
+    /// Keep all labels (default).
+    #[default]
+    Keep,
+    /// Keep all labels and emit a warning for each duplicate.
+    Warn,
+    /// Rewrite links, including external links converted to references, to
+    /// use the first label defined for the URL.
+    Merge,
+}
+
+/// What to do when the same reference label is defined with different URLs.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictingLabels {
+    /// Emit a warning for each conflicting definition (default).
+    #[default]
+    Warn,
+    /// Silently use the first definition, as CommonMark does.
+    Ignore,
+}
+
+/// Where reference definitions and footnote definitions are placed.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferencePlacement {
+    /// At the end of each section, before every h2 and h3 heading (default).
+    #[default]
+    Section,
+    /// At the end of each section, before every h2 heading only.
+    #[serde(rename = "section-h2")]
+    SectionH2,
+    /// All together at the end of the document.
+    Document,
+    /// Right after the top-level block that uses them.
+    Block,
+}
+
+/// Whether external links are converted to reference style.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalLinks {
+    /// Convert inline external links to reference-style links (default).
+    #[default]
+    Reference,
+    /// Keep inline external links as they are.
+    Inline,
+}
+
+/// How labels are chosen for external links converted to reference style.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceLabel {
+    /// Use the link text as the label (default).
+    #[default]
+    Text,
+    /// Number the URLs in order of appearance.
+    Numeric,
+    /// Use the domain of the URL.
+    Domain,
+}
+
+/// The order of reference definitions within each group of definitions.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceOrder {
+    /// Keep the order of first use, but put numeric labels after the others
+    /// in numeric order when there are two or more of them (default).
+    #[default]
+    Auto,
+    /// Keep the order of first use.
+    Appearance,
+    /// Sort by label, ignoring case.
+    Alphabetical,
+    /// Put numeric labels first in numeric order, then the others sorted by
+    /// label, ignoring case.
+    #[serde(rename = "numeric-then-alpha")]
+    NumericThenAlpha,
+}
+
+/// Link and reference definition options.
+#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct LinkConfig {
+    /// What to do with unused reference definitions (default: `remove`).
+    pub unused_references: UnusedReferences,
+
+    /// What to do with different labels pointing to the same URL
+    /// (default: `keep`).
+    pub duplicate_urls: DuplicateUrls,
+
+    /// What to do when a label is defined twice with different URLs
+    /// (default: `warn`).
+    pub conflicting_labels: ConflictingLabels,
+
+    /// Where reference and footnote definitions are placed
+    /// (default: `section`).
+    pub reference_placement: ReferencePlacement,
+
+    /// Whether external links are converted to reference style
+    /// (default: `reference`).
+    pub external_links: ExternalLinks,
+
+    /// Convert only links whose URL is wider than this many columns
+    /// (default: 0).
+    pub min_url_width: usize,
+
+    /// Additional URL schemes treated as external, e.g., `mailto`
+    /// (default: []).
+    pub external_schemes: Vec<String>,
+
+    /// Domains whose URLs are treated as external regardless of their
+    /// scheme, including their subdomains (default: []).
+    pub external_domains: Vec<String>,
+
+    /// How labels of converted external links are chosen (default: `text`).
+    pub reference_label: ReferenceLabel,
+
+    /// The order of reference definitions (default: `auto`).
+    pub reference_order: ReferenceOrder,
+
+    /// Whether numeric reference labels are renumbered in order of first use
+    /// (default: false).
+    pub renumber_references: bool,
+}
+
+impl LinkConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("unused_references") {
+            options.link_unused_references = self.unused_references;
+        }
+        if is_set("duplicate_urls") {
+            options.link_duplicate_urls = self.duplicate_urls;
+        }
+        if is_set("conflicting_labels") {
+            options.link_conflicting_labels = self.conflicting_labels;
+        }
+        if is_set("reference_placement") {
+            options.link_reference_placement = self.reference_placement;
+        }
+        if is_set("external_links") {
+            options.link_external_links = self.external_links;
+        }
+        if is_set("min_url_width") {
+            options.link_min_url_width = self.min_url_width;
+        }
+        if is_set("external_schemes") {
+            options.link_external_schemes = self.external_schemes.clone();
+        }
+        if is_set("external_domains") {
+            options.link_external_domains = self.external_domains.clone();
+        }
+        if is_set("reference_label") {
+            options.link_reference_label = self.reference_label;
+        }
+        if is_set("reference_order") {
+            options.link_reference_order = self.reference_order;
+        }
+        if is_set("renumber_references") {
+            options.link_renumber_references = self.renumber_references;
+        }
+    }
+}
+
+/// How display math blocks are delimited.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MathDelimiters {
+    /// `$$` on lines of their own (default).
+    #[default]
+    Dollar,
+    /// A fenced code block with the `math` info string, as GitLab uses.
+    Fence,
+}
+
+/// Whether containers and GitHub alerts are converted to each other.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerStyle {
+    /// Keep both as they are (default).
+    #[default]
+    Preserve,
+    /// Write containers of the alert types as GitHub alerts.
+    Alert,
+    /// Write GitHub alerts as containers.
+    Container,
+}
+
+/// How the types of alerts and callouts are capitalized.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CalloutTypeCase {
+    /// `[!NOTE]` (default).
+    #[default]
+    Upper,
+    /// `[!note]`.
+    Lower,
+    /// Keep the case of the source.
+    Preserve,
+}
+
+/// The renderer that alerts and callouts are written for.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CalloutTarget {
+    /// Any renderer (default).
+    #[default]
+    Any,
+    /// GitHub, which supports only its five alert types.
+    Github,
+}
+
+/// Optional Markdown syntax extensions recognized by the parser.
+#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct ExtensionsConfig {
+    /// Recognize `~~strikethrough~~` (default: false).
+    pub strikethrough: bool,
+
+    /// Recognize `^superscript^` (default: false).
+    pub superscript: bool,
+
+    /// Recognize `~subscript~` (default: false).
+    pub subscript: bool,
+
+    /// Recognize `$inline$` and `$$display$$` math (default: false).
+    pub math: bool,
+
+    /// How display math blocks are delimited: `"dollar"` or `"fence"`
+    /// (default: `"dollar"`).
+    pub math_delimiters: MathDelimiters,
+
+    /// Recognize bare URLs, `www.` links, and email addresses as links, as
+    /// GitHub does (default: false).
+    pub autolink: bool,
+
+    /// Recognize `__underline__` instead of `__strong__` (default: false).
+    pub underline: bool,
+
+    /// Recognize `||spoiler||` (default: false).
+    pub spoiler: bool,
+
+    /// Recognize `:emoji:` shortcodes (default: false).
+    pub shortcodes: bool,
+
+    /// Recognize `[[Page|alias]]` wikilinks and `![[file]]` embeds
+    /// (default: false).
+    pub wikilinks: bool,
+
+    /// Recognize `:::type` containers and Pandoc fenced divs (default: false).
+    pub containers: bool,
+
+    /// Convert containers of the `note`, `tip`, `important`, `warning`, and
+    /// `caution` types without a title to GitHub alerts (`"alert"`) or the
+    /// other way around (`"container"`) (default: `"preserve"`).
+    pub container_style: ContainerStyle,
+
+    /// Recognize Obsidian-style callouts of any type, e.g., `> [!faq]-`
+    /// (default: false).  Titles and fold markers are kept either way.
+    pub callouts: bool,
+
+    /// How callout types are capitalized: `"upper"`, `"lower"`, or
+    /// `"preserve"` (default: `"upper"`).
+    pub callout_type_case: CalloutTypeCase,
+
+    /// The renderer callouts are written for: `"any"` or `"github"`, which
+    /// warns about callouts GitHub does not support (default: `"any"`).
+    pub callout_target: CalloutTarget,
+
+    /// Treat every document as MDX, and pick up *.mdx* files when
+    /// collecting files from directories (default: false).  Files with the
+    /// *.mdx* extension are always treated as MDX.
+    pub mdx: bool,
+}
+
+impl ExtensionsConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("strikethrough") {
+            options.extension_strikethrough = self.strikethrough;
+        }
+        if is_set("superscript") {
+            options.extension_superscript = self.superscript;
+        }
+        if is_set("subscript") {
+            options.extension_subscript = self.subscript;
+        }
+        if is_set("math") {
+            options.extension_math = self.math;
+        }
+        if is_set("math_delimiters") {
+            options.extension_math_delimiters = self.math_delimiters;
+        }
+        if is_set("autolink") {
+            options.extension_autolink = self.autolink;
+        }
+        if is_set("underline") {
+            options.extension_underline = self.underline;
+        }
+        if is_set("spoiler") {
+            options.extension_spoiler = self.spoiler;
+        }
+        if is_set("shortcodes") {
+            options.extension_shortcodes = self.shortcodes;
+        }
+        if is_set("wikilinks") {
+            options.extension_wikilinks = self.wikilinks;
+        }
+        if is_set("mdx") {
+            options.extension_mdx = self.mdx;
+        }
+        if is_set("containers") {
+            options.extension_containers = self.containers;
+        }
+        if is_set("container_style") {
+            options.extension_container_style = self.container_style;
+        }
+        if is_set("callouts") {
+            options.extension_callouts = self.callouts;
+        }
+        if is_set("callout_type_case") {
+            options.extension_callout_type_case = self.callout_type_case;
+        }
+        if is_set("callout_target") {
+            options.extension_callout_target = self.callout_target;
+        }
+    }
+}
+
+/// Templating syntax, such as Liquid tags or Hugo shortcodes, that is kept
+/// verbatim.
+#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct TemplatesConfig {
+    /// Pairs of opening and closing delimiters, e.g., `[["{{", "}}"],
+    /// ["{%", "%}"]]` (default: []).
+    pub delimiters: Vec<(String, String)>,
+}
+
+impl TemplatesConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("delimiters") {
+            options.template_delimiters = self.delimiters.clone();
+        }
+    }
+}
+
+/// Checks of the body text.
+#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct LintConfig {
+    /// Check the body text for known proper nouns written in the wrong case,
+    /// such as `Javascript` for `JavaScript` (default: off).
+    pub proper_nouns: ProperNounLint,
+}
+
+impl LintConfig {
+    /// Copy the fields for which `is_set` returns true into `options`.
+    fn apply_to(&self, options: &mut Options, is_set: &dyn Fn(&str) -> bool) {
+        if is_set("proper_nouns") {
+            options.lint_proper_nouns = self.proper_nouns;
+        }
+    }
+}
+
+/// What to do about known proper nouns written in the wrong case in the
+/// body text.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProperNounLint {
+    /// Do not check the body text (default).
+    #[default]
+    Off,
+    /// Warn about each proper noun written in the wrong case.
+    Warn,
+    /// Write each proper noun in its canonical case, and warn about it.
+    Fix,
+}
+
 impl Config {
     /// Parse a configuration from a TOML string.
     pub fn from_toml(toml_str: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(toml_str)
     }
 
+    /// Build the formatter [`Options`] described by this configuration.
+    ///
+    /// This reads the proper noun files, and returns an error if one of
+    /// them cannot be read or a disabled proper noun category is unknown.
+    pub fn to_options(&self) -> Result<Options, ConfigError> {
+        let mut options = Options {
+            line_width: self.line_width,
+            ..Options::default()
+        };
+        let all = |_: &str| true;
+        self.heading.apply_to(&mut options, &all);
+        self.unordered_list.apply_to(&mut options, &all);
+        self.ordered_list.apply_to(&mut options, &all);
+        self.code_block.apply_to(&mut options, &all);
+        self.thematic_break.apply_to(&mut options, &all);
+        self.punctuation.apply_to(&mut options, &all);
+        self.front_matter.apply_to(&mut options, &all);
+        self.link.apply_to(&mut options, &all);
+        self.extensions.apply_to(&mut options, &all);
+        self.templates.apply_to(&mut options, &all);
+        self.lint.apply_to(&mut options, &all);
+        options
+            .heading_proper_nouns
+            .extend(self.heading.load_proper_noun_files()?);
+        validate_proper_noun_categories(&options).map_err(ConfigError::Heading)?;
+        Ok(options)
+    }
+
     /// Load configuration from a file.
     pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
         let content =
             std::fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
-        Self::from_toml(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))
+        let mut config =
+            Self::from_toml(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+        if let Some(dir) = path.parent() {
+            config.heading.resolve_paths(dir);
+        }
+        Ok(config)
     }
 
     /// Discover and load configuration by searching up the directory tree.
@@ -1138,6 +2081,8 @@ pub enum ConfigError {
     GlobIo(glob::GlobError),
     /// Error from ignore crate (file traversal).
     Ignore(ignore::Error),
+    /// Invalid heading options.
+    Heading(HeadingError),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -1158,6 +2103,7 @@ impl std::fmt::Display for ConfigError {
             ConfigError::Ignore(err) => {
                 write!(f, "error during file traversal: {}", err)
             }
+            ConfigError::Heading(err) => write!(f, "{}", err),
         }
     }
 }
@@ -1170,6 +2116,7 @@ impl std::error::Error for ConfigError {
             ConfigError::Glob(_, err) => Some(err),
             ConfigError::GlobIo(err) => Some(err),
             ConfigError::Ignore(err) => Some(err),
+            ConfigError::Heading(err) => Some(err),
         }
     }
 }
@@ -1210,6 +2157,344 @@ mod tests {
         assert_eq!(config.thematic_break.leading_spaces.get(), 3);
     }
 
+    #[test]
+    fn test_config_to_options() {
+        let config = Config::from_toml(
+            r#"
+line_width = 100
+
+[heading]
+setext_h2 = false
+proper_nouns = ["Hongdown"]
+
+[ordered_list]
+pad = "end"
+
+[code_block]
+fence_char = "`"
+formatters = { python = ["black", "-"] }
+
+[punctuation]
+em_dash = false
+"#,
+        )
+        .unwrap();
+        let options = config.to_options().unwrap();
+        assert_eq!(options.line_width.get(), 100);
+        assert!(options.setext_h1);
+        assert!(!options.setext_h2);
+        assert_eq!(options.heading_proper_nouns, vec!["Hongdown"]);
+        assert_eq!(options.ordered_list_pad, OrderedListPad::End);
+        assert_eq!(options.fence_char, FenceChar::Backtick);
+        assert_eq!(
+            options.code_formatters["python"].command,
+            vec!["black", "-"]
+        );
+        assert_eq!(options.em_dash, DashSetting::Disabled);
+    }
+
+    #[test]
+    fn test_config_layer_apply_to_options_only_set_fields() {
+        let table: toml::Table = r#"
+[heading]
+sentence_case = true
+
+[unordered_list]
+unordered_marker = "*"
+"#
+        .parse()
+        .unwrap();
+        let layer: ConfigLayer = toml::Value::Table(table.clone()).try_into().unwrap();
+        let mut options = Options {
+            setext_h1: false,
+            heading_proper_nouns: vec!["Hongdown".to_string()],
+            trailing_spaces: TrailingSpaces::new(1).unwrap(),
+            ..Options::default()
+        };
+        layer.apply_to_options(&mut options, &table);
+        assert!(options.heading_sentence_case);
+        assert!(!options.setext_h1);
+        assert_eq!(options.heading_proper_nouns, vec!["Hongdown"]);
+        assert_eq!(options.unordered_marker, UnorderedMarker::Asterisk);
+        assert_eq!(options.trailing_spaces.get(), 1);
+        assert_eq!(options.line_width.get(), 80);
+    }
+
+    #[test]
+    fn test_parse_front_matter_config() {
+        let config = Config::from_toml(
+            r#"
+[front_matter]
+canonicalize = true
+indent_width = 4
+quote_style = "double"
+key_order = ["title", "date"]
+"#,
+        )
+        .unwrap();
+        assert!(config.front_matter.canonicalize);
+        assert_eq!(config.front_matter.indent_width.get(), 4);
+        assert_eq!(
+            config.front_matter.quote_style,
+            FrontMatterQuoteStyle::Double
+        );
+        assert_eq!(config.front_matter.key_order, vec!["title", "date"]);
+    }
+
+    #[test]
+    fn test_default_front_matter_config() {
+        let config = Config::default();
+        assert!(!config.front_matter.canonicalize);
+        assert_eq!(config.front_matter.indent_width.get(), 2);
+        assert_eq!(
+            config.front_matter.quote_style,
+            FrontMatterQuoteStyle::Preserve
+        );
+        assert!(config.front_matter.key_order.is_empty());
+    }
+
+    #[test]
+    fn test_parse_front_matter_invalid_quote_style() {
+        let result = Config::from_toml("[front_matter]\nquote_style = \"backtick\"\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_link_config() {
+        let config = Config::from_toml(
+            r#"
+[link]
+unused_references = "warn"
+duplicate_urls = "merge"
+conflicting_labels = "ignore"
+reference_placement = "section-h2"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.link.unused_references, UnusedReferences::Warn);
+        assert_eq!(config.link.duplicate_urls, DuplicateUrls::Merge);
+        assert_eq!(config.link.conflicting_labels, ConflictingLabels::Ignore);
+        assert_eq!(
+            config.link.reference_placement,
+            ReferencePlacement::SectionH2
+        );
+
+        let options = config.to_options().unwrap();
+        assert_eq!(options.link_unused_references, UnusedReferences::Warn);
+        assert_eq!(options.link_duplicate_urls, DuplicateUrls::Merge);
+        assert_eq!(options.link_conflicting_labels, ConflictingLabels::Ignore);
+        assert_eq!(
+            options.link_reference_placement,
+            ReferencePlacement::SectionH2
+        );
+    }
+
+    #[test]
+    fn test_default_link_config() {
+        let config = Config::default();
+        assert_eq!(config.link.unused_references, UnusedReferences::Remove);
+        assert_eq!(config.link.duplicate_urls, DuplicateUrls::Keep);
+        assert_eq!(config.link.conflicting_labels, ConflictingLabels::Warn);
+        assert_eq!(config.link.reference_placement, ReferencePlacement::Section);
+        assert_eq!(config.link.external_links, ExternalLinks::Reference);
+        assert_eq!(config.link.min_url_width, 0);
+        assert!(config.link.external_schemes.is_empty());
+        assert!(config.link.external_domains.is_empty());
+        assert_eq!(config.link.reference_label, ReferenceLabel::Text);
+        assert_eq!(config.link.reference_order, ReferenceOrder::Auto);
+        assert!(!config.link.renumber_references);
+    }
+
+    #[test]
+    fn test_parse_link_reference_placement() {
+        for (value, expected) in [
+            ("section", ReferencePlacement::Section),
+            ("section-h2", ReferencePlacement::SectionH2),
+            ("document", ReferencePlacement::Document),
+            ("block", ReferencePlacement::Block),
+        ] {
+            let config =
+                Config::from_toml(&format!("[link]\nreference_placement = \"{}\"\n", value))
+                    .unwrap();
+            assert_eq!(config.link.reference_placement, expected);
+        }
+        assert!(Config::from_toml("[link]\nreference_placement = \"page\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_link_external_config() {
+        let config = Config::from_toml(
+            r#"
+[link]
+external_links = "inline"
+min_url_width = 40
+external_schemes = ["mailto", "ipfs"]
+external_domains = ["example.com"]
+reference_label = "numeric"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.link.external_links, ExternalLinks::Inline);
+        assert_eq!(config.link.min_url_width, 40);
+        assert_eq!(config.link.external_schemes, vec!["mailto", "ipfs"]);
+        assert_eq!(config.link.external_domains, vec!["example.com"]);
+        assert_eq!(config.link.reference_label, ReferenceLabel::Numeric);
+
+        let options = config.to_options().unwrap();
+        assert_eq!(options.link_external_links, ExternalLinks::Inline);
+        assert_eq!(options.link_min_url_width, 40);
+        assert_eq!(options.link_external_schemes, vec!["mailto", "ipfs"]);
+        assert_eq!(options.link_external_domains, vec!["example.com"]);
+        assert_eq!(options.link_reference_label, ReferenceLabel::Numeric);
+    }
+
+    #[test]
+    fn test_parse_link_reference_order() {
+        let config = Config::from_toml(
+            "[link]\nreference_order = \"numeric-then-alpha\"\nrenumber_references = true\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.link.reference_order,
+            ReferenceOrder::NumericThenAlpha
+        );
+        assert!(config.link.renumber_references);
+
+        let options = config.to_options().unwrap();
+        assert_eq!(
+            options.link_reference_order,
+            ReferenceOrder::NumericThenAlpha
+        );
+        assert!(options.link_renumber_references);
+
+        for (value, expected) in [
+            ("auto", ReferenceOrder::Auto),
+            ("appearance", ReferenceOrder::Appearance),
+            ("alphabetical", ReferenceOrder::Alphabetical),
+        ] {
+            let config =
+                Config::from_toml(&format!("[link]\nreference_order = \"{}\"\n", value)).unwrap();
+            assert_eq!(config.link.reference_order, expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_extensions_config() {
+        let config = Config::from_toml(
+            r#"
+[extensions]
+strikethrough = true
+superscript = true
+subscript = true
+math = true
+math_delimiters = "fence"
+autolink = true
+underline = true
+spoiler = true
+shortcodes = true
+wikilinks = true
+mdx = true
+containers = true
+container_style = "alert"
+callouts = true
+callout_type_case = "lower"
+callout_target = "github"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.extensions,
+            ExtensionsConfig {
+                strikethrough: true,
+                superscript: true,
+                subscript: true,
+                math: true,
+                math_delimiters: MathDelimiters::Fence,
+                autolink: true,
+                underline: true,
+                spoiler: true,
+                shortcodes: true,
+                wikilinks: true,
+                mdx: true,
+                containers: true,
+                container_style: ContainerStyle::Alert,
+                callouts: true,
+                callout_type_case: CalloutTypeCase::Lower,
+                callout_target: CalloutTarget::Github,
+            }
+        );
+
+        let options = config.to_options().unwrap();
+        assert!(options.extension_strikethrough);
+        assert!(options.extension_superscript);
+        assert!(options.extension_subscript);
+        assert!(options.extension_math);
+        assert_eq!(options.extension_math_delimiters, MathDelimiters::Fence);
+        assert!(options.extension_autolink);
+        assert!(options.extension_underline);
+        assert!(options.extension_spoiler);
+        assert!(options.extension_shortcodes);
+        assert!(options.extension_wikilinks);
+        assert!(options.extension_mdx);
+        assert!(options.extension_containers);
+        assert_eq!(options.extension_container_style, ContainerStyle::Alert);
+        assert!(options.extension_callouts);
+        assert_eq!(options.extension_callout_type_case, CalloutTypeCase::Lower);
+        assert_eq!(options.extension_callout_target, CalloutTarget::Github);
+    }
+
+    #[test]
+    fn test_parse_lint_config() {
+        let config = Config::from_toml(
+            r#"
+[lint]
+proper_nouns = "fix"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.lint.proper_nouns, ProperNounLint::Fix);
+        assert_eq!(
+            config.to_options().unwrap().lint_proper_nouns,
+            ProperNounLint::Fix
+        );
+        assert_eq!(
+            Config::default().to_options().unwrap().lint_proper_nouns,
+            ProperNounLint::Off
+        );
+    }
+
+    #[test]
+    fn test_parse_templates_config() {
+        let config = Config::from_toml(
+            r#"
+[templates]
+delimiters = [["{{", "}}"], ["{%", "%}"]]
+"#,
+        )
+        .unwrap();
+        let delimiters = vec![
+            ("{{".to_string(), "}}".to_string()),
+            ("{%".to_string(), "%}".to_string()),
+        ];
+        assert_eq!(config.templates.delimiters, delimiters);
+        assert_eq!(config.to_options().unwrap().template_delimiters, delimiters);
+        assert!(
+            Config::default()
+                .to_options()
+                .unwrap()
+                .template_delimiters
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_default_extensions_config() {
+        let options = Config::default().to_options().unwrap();
+        assert!(!options.extension_strikethrough);
+        assert!(!options.extension_math);
+        assert!(!options.extension_autolink);
+    }
+
     #[test]
     fn test_parse_empty_toml() {
         let config = Config::from_toml("").unwrap();
@@ -1245,7 +2530,82 @@ sentence_case = true
 "#,
         )
         .unwrap();
-        assert!(config.heading.sentence_case);
+        assert_eq!(config.heading.sentence_case, SentenceCase::Enabled);
+    }
+
+    #[test]
+    fn test_parse_heading_sentence_case_report() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+sentence_case = "report"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.heading.sentence_case, SentenceCase::Report);
+        let options = config.to_options().unwrap();
+        assert!(!options.heading_sentence_case);
+        assert!(options.heading_sentence_case_report);
+
+        let result = Config::from_toml(
+            r#"
+[heading]
+sentence_case = "always"
+"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_heading_case() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+case = "title"
+title_case_style = "ap"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.heading.case, HeadingCase::Title);
+        assert_eq!(config.heading.title_case_style, TitleCaseStyle::Ap);
+        let options = config.to_options().unwrap();
+        assert_eq!(options.heading_case, HeadingCase::Title);
+        assert_eq!(options.heading_title_case_style, TitleCaseStyle::Ap);
+    }
+
+    #[test]
+    fn test_parse_heading_levels() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+top_level = 2
+promote_first = true
+fix_skipped_levels = true
+"#,
+        )
+        .unwrap();
+        let options = config.to_options().unwrap();
+        assert_eq!(options.heading_top_level, 2);
+        assert!(options.heading_promote_first);
+        assert!(options.heading_fix_skipped_levels);
+    }
+
+    #[test]
+    fn test_parse_heading_numbering() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+numbering = true
+numbering_max_level = 3
+numbering_replace_plain = true
+"#,
+        )
+        .unwrap();
+        let options = config.to_options().unwrap();
+        assert!(options.heading_numbering);
+        assert_eq!(options.heading_numbering_min_level, 2);
+        assert_eq!(options.heading_numbering_max_level, 3);
+        assert!(options.heading_numbering_replace_plain);
     }
 
     #[test]
@@ -1273,7 +2633,7 @@ proper_nouns = ["Hongdown", "MyAPI"]
 "#,
         )
         .unwrap();
-        assert!(config.heading.sentence_case);
+        assert_eq!(config.heading.sentence_case, SentenceCase::Enabled);
         assert_eq!(config.heading.proper_nouns, vec!["Hongdown", "MyAPI"]);
     }
 
@@ -1300,11 +2660,74 @@ common_nouns = ["Go"]
 "#,
         )
         .unwrap();
-        assert!(config.heading.sentence_case);
+        assert_eq!(config.heading.sentence_case, SentenceCase::Enabled);
         assert_eq!(config.heading.proper_nouns, vec!["MyAPI"]);
         assert_eq!(config.heading.common_nouns, vec!["Go"]);
     }
 
+    #[test]
+    fn test_parse_heading_sentence_case_extents() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+sentence_case = true
+sentence_case_links = false
+sentence_case_table_headers = true
+sentence_case_terms = true
+"#,
+        )
+        .unwrap();
+        let options = config.to_options().unwrap();
+        assert!(!options.heading_sentence_case_links);
+        assert!(options.heading_sentence_case_table_headers);
+        assert!(options.heading_sentence_case_terms);
+        let options = Config::default().to_options().unwrap();
+        assert!(options.heading_sentence_case_links);
+        assert!(!options.heading_sentence_case_table_headers);
+        assert!(!options.heading_sentence_case_terms);
+    }
+
+    #[test]
+    fn test_parse_heading_disabled_categories() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+disabled_categories = ["countries", "natural-languages"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.heading.disabled_categories,
+            vec!["countries", "natural-languages"]
+        );
+        assert_eq!(
+            config.to_options().unwrap().heading_disabled_categories,
+            vec!["countries", "natural-languages"]
+        );
+    }
+
+    #[test]
+    fn test_config_to_options_unknown_category() {
+        let config = Config::from_toml(
+            r#"
+[heading]
+disabled_categories = ["contries"]
+"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.to_options(),
+            Err(ConfigError::Heading(HeadingError::UnknownCategory(category)))
+                if category == "contries"
+        ));
+    }
+
+    #[test]
+    fn test_parse_proper_nouns() {
+        let content = "# Products\nFooBar\n\n  ACME Widgets  \n# Done\n";
+        assert_eq!(parse_proper_nouns(content), vec!["FooBar", "ACME Widgets"]);
+    }
+
     #[test]
     fn test_parse_unordered_list_config() {
         let config = Config::from_toml(
@@ -2497,10 +3920,70 @@ line_width = 100
         .unwrap();
 
         let layer = ConfigLayer::from_file(&config_path).unwrap();
-        assert_eq!(layer.no_inherit, true);
+        assert!(layer.no_inherit);
         assert_eq!(layer.line_width, Some(LineWidth::new(100).unwrap()));
     }
 
+    #[test]
+    fn test_config_layer_from_file_proper_noun_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".hongdown.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+[heading]
+proper_noun_files = ["glossary.txt"]
+"#,
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("glossary.txt"), "# Glossary\nFooBar\n").unwrap();
+
+        let layer = ConfigLayer::from_file(&config_path).unwrap();
+        let heading = layer.heading.unwrap();
+        assert_eq!(
+            heading.proper_noun_files,
+            vec![temp_dir.path().join("glossary.txt")]
+        );
+        assert_eq!(heading.load_proper_noun_files().unwrap(), vec!["FooBar"]);
+    }
+
+    #[test]
+    fn test_config_to_options_loads_proper_noun_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let glossary = temp_dir.path().join("glossary.txt");
+        std::fs::write(&glossary, "FooBar\n").unwrap();
+        let config = Config {
+            heading: HeadingConfig {
+                proper_nouns: vec!["BazQux".to_string()],
+                proper_noun_files: vec![glossary],
+                ..HeadingConfig::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(
+            config.to_options().unwrap().heading_proper_nouns,
+            vec!["BazQux", "FooBar"]
+        );
+
+        let config = Config {
+            heading: HeadingConfig {
+                proper_noun_files: vec![PathBuf::from("/nonexistent/glossary.txt")],
+                ..HeadingConfig::default()
+            },
+            ..Config::default()
+        };
+        assert!(matches!(config.to_options(), Err(ConfigError::Io(..))));
+    }
+
+    #[test]
+    fn test_load_proper_noun_files_not_found() {
+        let heading = HeadingConfig {
+            proper_noun_files: vec![PathBuf::from("/nonexistent/glossary.txt")],
+            ..HeadingConfig::default()
+        };
+        assert!(heading.load_proper_noun_files().is_err());
+    }
+
     #[test]
     fn test_config_layer_from_file_not_found() {
         let result = ConfigLayer::from_file(Path::new("/nonexistent/.hongdown.toml"));
@@ -2523,7 +4006,7 @@ line_width = 100
 
         let merged = layer.merge_over(base);
         assert_eq!(merged.line_width.get(), 100);
-        assert_eq!(merged.git_aware, false);
+        assert!(!merged.git_aware);
     }
 
     #[test]
@@ -2543,7 +4026,7 @@ line_width = 100
 
         let merged = layer.merge_over(base);
         assert_eq!(merged.line_width.get(), 100);
-        assert_eq!(merged.git_aware, false); // Preserved
+        assert!(!merged.git_aware); // Preserved
         assert_eq!(merged.include, vec!["*.md".to_string()]); // Preserved
     }
 
@@ -2553,9 +4036,23 @@ line_width = 100
             heading: HeadingConfig {
                 setext_h1: true,
                 setext_h2: true,
-                sentence_case: false,
+                sentence_case: SentenceCase::Disabled,
+                sentence_case_links: false,
+                sentence_case_table_headers: false,
+                sentence_case_terms: false,
+                case: HeadingCase::Preserve,
+                title_case_style: TitleCaseStyle::Chicago,
+                top_level: 0,
+                promote_first: false,
+                fix_skipped_levels: false,
+                numbering: false,
+                numbering_min_level: 2,
+                numbering_max_level: 6,
+                numbering_replace_plain: false,
                 proper_nouns: vec!["Rust".to_string()],
                 common_nouns: Vec::new(),
+                proper_noun_files: Vec::new(),
+                disabled_categories: Vec::new(),
             },
             ..Config::default()
         };
@@ -2564,17 +4061,31 @@ line_width = 100
             heading: Some(HeadingConfig {
                 setext_h1: false,
                 setext_h2: false,
-                sentence_case: true,
+                sentence_case: SentenceCase::Enabled,
+                sentence_case_links: false,
+                sentence_case_table_headers: false,
+                sentence_case_terms: false,
+                case: HeadingCase::Preserve,
+                title_case_style: TitleCaseStyle::Chicago,
+                top_level: 0,
+                promote_first: false,
+                fix_skipped_levels: false,
+                numbering: false,
+                numbering_min_level: 2,
+                numbering_max_level: 6,
+                numbering_replace_plain: false,
                 proper_nouns: vec!["Python".to_string()],
                 common_nouns: Vec::new(),
+                proper_noun_files: Vec::new(),
+                disabled_categories: Vec::new(),
             }),
             ..ConfigLayer::default()
         };
 
         let merged = layer.merge_over(base);
-        assert_eq!(merged.heading.setext_h1, false);
-        assert_eq!(merged.heading.setext_h2, false);
-        assert_eq!(merged.heading.sentence_case, true);
+        assert!(!merged.heading.setext_h1);
+        assert!(!merged.heading.setext_h2);
+        assert_eq!(merged.heading.sentence_case, SentenceCase::Enabled);
         assert_eq!(merged.heading.proper_nouns, vec!["Python".to_string()]);
     }
 
@@ -2691,7 +4202,7 @@ line_width = 100
 
         // Should use config's values, ignoring any system/user configs
         assert_eq!(config.line_width.get(), 100);
-        assert_eq!(config.no_inherit, true);
+        assert!(config.no_inherit);
     }
 
     #[test]
@@ -2735,7 +4246,7 @@ git_aware = false
 
         // Should find parent's config when searching from child
         assert_eq!(config.line_width.get(), 120);
-        assert_eq!(config.git_aware, false);
+        assert!(!config.git_aware);
         assert_eq!(path, Some(parent.join(".hongdown.toml")));
     }
 }
@@ -0,0 +1,173 @@
+//! Container directives and fenced divs.
+//!
+//! Docusaurus, VitePress, and Pandoc put blocks in containers fenced by runs
+//! of colons:
+//!
+//! ```markdown
+//! :::note Title
+//! Content.
+//! :::
+//! ```
+//!
+//! Comrak does not recognize them, so each fence line is replaced with an
+//! HTML comment before parsing, keeping the source positions of the content
+//! intact.  The comment records the canonical fence length of its container:
+//! three colons for the innermost containers, and one more for each level of
+//! containers nested inside.  The serializer writes the fence back from the
+//! source line with that many colons.
+
+use comrak::nodes::AlertType;
+
+use crate::scan;
+
+/// The start of the HTML comment that stands for a container fence.
+const MARKER: &str = "<!--container:";
+
+/// The canonical fence length recorded in an HTML block literal that stands
+/// for a container fence, or `None` if the literal is not one.  In list
+/// items, the literal keeps the indentation of the fence past the content
+/// of the item.
+pub(crate) fn masked_fence_length(literal: &str) -> Option<usize> {
+    literal
+        .trim_start_matches(' ')
+        .strip_prefix(MARKER)?
+        .split_once("-->")?
+        .0
+        .parse()
+        .ok()
+}
+
+/// The types of GitHub alerts, with the names of the containers that map to
+/// them.
+pub(crate) const ALERT_CONTAINERS: [(AlertType, &str); 5] = [
+    (AlertType::Note, "note"),
+    (AlertType::Tip, "tip"),
+    (AlertType::Important, "important"),
+    (AlertType::Warning, "warning"),
+    (AlertType::Caution, "caution"),
+];
+
+/// The alert type of a container whose info string is exactly the name of
+/// one, ignoring case.
+pub(crate) fn alert_type(info: &str) -> Option<AlertType> {
+    let info = info.trim();
+    ALERT_CONTAINERS
+        .iter()
+        .find(|(_, name)| info.eq_ignore_ascii_case(name))
+        .map(|(alert_type, _)| *alert_type)
+}
+
+/// Split a container fence line into its number of colons and the info
+/// string that follows them (with its leading whitespace).  The info string
+/// is empty for closing fences.
+pub(crate) fn parse_fence(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    let colons = trimmed.chars().take_while(|&c| c == ':').count();
+    (colons >= 3).then(|| (colons, trimmed[colons..].trim_end()))
+}
+
+/// Mask the fences of the containers in `input`.  Only lines where a block
+/// can start and that are not written back verbatim are fences, and opening
+/// fences that are never closed are left alone.
+pub(crate) fn mask(input: &str) -> String {
+    let lines = scan::scan(input);
+    let mut lengths: Vec<Option<usize>> = vec![None; lines.len()];
+    // Open containers: the line of the opening fence and the height of the
+    // tallest stack of containers nested inside so far
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_verbatim() || !line.can_start_block {
+            continue;
+        }
+        let Some((_, info)) = parse_fence(line.content) else {
+            continue;
+        };
+        if !info.is_empty() {
+            stack.push((i, 0));
+        } else if let Some((open, height)) = stack.pop() {
+            lengths[open] = Some(3 + height);
+            lengths[i] = Some(3 + height);
+            if let Some((_, parent_height)) = stack.last_mut() {
+                *parent_height = (*parent_height).max(height + 1);
+            }
+        }
+    }
+
+    let mut result = String::with_capacity(input.len());
+    for (line, length) in lines.iter().zip(lengths) {
+        match length {
+            Some(length) => {
+                let indent = line.content.len() - line.content.trim_start().len();
+                result.push_str(&line.content[..indent]);
+                result.push_str(&format!("{}{}-->", MARKER, length));
+                result.push_str(&line.text[line.content.len()..]);
+            }
+            None => result.push_str(line.text),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let input = "::::: note Title\nText\n::: tip\nTip\n:::\n:::::\n";
+        assert_eq!(
+            mask(input),
+            "<!--container:4-->\nText\n<!--container:3-->\nTip\n<!--container:3-->\n\
+             <!--container:4-->\n"
+        );
+    }
+
+    #[test]
+    fn test_mask_ignores_unclosed_and_code() {
+        let input = ":::note\n\n```\n:::\n```\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_ignores_indented_and_html() {
+        let input = "Para\n\n    :::tip\n    x\n    :::\n";
+        assert_eq!(mask(input), input);
+        let input = "Para\n    :::tip\n    x\n    :::\n";
+        assert_eq!(mask(input), input);
+        let input = "<div>\n:::tip\nx\n:::\n</div>\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_nested_alert_container() {
+        // Only top-level containers become alerts, so a nested one counts
+        // towards the height of its parent
+        let input = ":::: details\n:::tip\nTip\n:::\n::::\n";
+        assert_eq!(
+            mask(input),
+            "<!--container:4-->\n<!--container:3-->\nTip\n<!--container:3-->\n\
+             <!--container:4-->\n"
+        );
+    }
+
+    #[test]
+    fn test_alert_type() {
+        assert_eq!(alert_type(" Warning"), Some(AlertType::Warning));
+        assert_eq!(alert_type("warning Title"), None);
+    }
+
+    #[test]
+    fn test_masked_fence_length() {
+        assert_eq!(masked_fence_length("<!--container:4-->\n"), Some(4));
+        assert_eq!(masked_fence_length("  <!--container:3-->\n"), Some(3));
+        assert_eq!(masked_fence_length("<!-- comment -->\n"), None);
+    }
+
+    #[test]
+    fn test_parse_fence() {
+        assert_eq!(parse_fence(":::note Title "), Some((3, "note Title")));
+        assert_eq!(parse_fence("  :::: {.warning}"), Some((4, " {.warning}")));
+        assert_eq!(parse_fence(":::"), Some((3, "")));
+        assert_eq!(parse_fence(":: no"), None);
+    }
+}
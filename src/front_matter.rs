@@ -0,0 +1,496 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Front matter detection and per-document option overrides.
+//!
+//! A document can override formatting options for itself through a
+//! `hongdown` key in its front matter, whose contents mirror
+//! *.hongdown.toml*:
+//!
+//! ```markdown
+//! ---
+//! title: Release notes
+//! hongdown:
+//!   line_width: 72
+//!   heading:
+//!     proper_nouns: [Hongdown]
+//! ---
+//! ```
+//!
+//! TOML front matter (delimited by `+++`) uses a `[hongdown]` table instead,
+//! and JSON front matter (a leading `{ ... }` object) a `"hongdown"` member.
+
+use std::borrow::Cow;
+
+use serde::Deserialize;
+
+use crate::config::ConfigLayer;
+use crate::serializer::Warning;
+use crate::{Options, validate_dash_settings, validate_proper_noun_categories};
+
+/// The key that holds per-document options in front matter.
+const OPTIONS_KEY: &str = "hongdown";
+
+/// Top-level configuration keys that have no meaning for a single document.
+const IGNORED_KEYS: &[&str] = &["no_inherit", "include", "exclude", "git_aware"];
+
+/// The syntax of a front matter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrontMatterKind {
+    /// YAML front matter delimited by `---`.
+    Yaml,
+    /// TOML front matter delimited by `+++`.
+    Toml,
+    /// JSON front matter: an object whose braces are on lines of their own.
+    Json,
+}
+
+impl FrontMatterKind {
+    /// The delimiter line that opens and closes this kind of front matter,
+    /// or `None` for JSON front matter, which has no delimiters.
+    pub(crate) fn delimiter(self) -> Option<&'static str> {
+        match self {
+            Self::Yaml => Some("---"),
+            Self::Toml => Some("+++"),
+            Self::Json => None,
+        }
+    }
+}
+
+/// A front matter block found at the start of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FrontMatter<'a> {
+    /// The syntax of the block.
+    pub kind: FrontMatterKind,
+    /// The content between the delimiter lines, or the whole object
+    /// (braces included) for JSON front matter.
+    pub content: &'a str,
+    /// The 1-indexed line on which the block ends.
+    pub end_line: usize,
+}
+
+/// Detect a front matter block at the start of `input`.
+///
+/// The block must start on the first line (after an optional byte order
+/// mark).  YAML and TOML blocks are closed by a line consisting solely of
+/// the opening delimiter; a JSON block opens with a line consisting solely
+/// of `{` and is closed by the line holding its matching `}`.
+pub(crate) fn detect(input: &str) -> Option<FrontMatter<'_>> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let (first_line, rest) = input.split_once('\n')?;
+    let first_line = first_line.trim_end_matches('\r');
+    if first_line.trim_end() == "{" {
+        return detect_json(input);
+    }
+    let kind = [FrontMatterKind::Yaml, FrontMatterKind::Toml]
+        .into_iter()
+        .find(|kind| kind.delimiter() == Some(first_line))?;
+
+    let mut offset = 0;
+    for (index, line) in rest.split_inclusive('\n').enumerate() {
+        if kind.delimiter() == Some(line.trim_end_matches(['\r', '\n'])) {
+            return Some(FrontMatter {
+                kind,
+                content: &rest[..offset],
+                end_line: index + 2,
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Detect a JSON front matter object that starts at the beginning of `input`.
+fn detect_json(input: &str) -> Option<FrontMatter<'_>> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    let end = i + 1;
+                    let line_end = input[end..].find('\n').map_or(input.len(), |n| end + n);
+                    if !input[end..line_end].trim().is_empty() {
+                        return None;
+                    }
+                    return Some(FrontMatter {
+                        kind: FrontMatterKind::Json,
+                        content: &input[..end],
+                        end_line: input[..end].lines().count(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Replace the lines of a JSON front matter block with empty lines.
+///
+/// Comrak has no notion of JSON front matter, so the object is hidden from
+/// the parser this way while keeping the source positions of the following
+/// blocks intact.  The serializer emits the object verbatim instead.
+pub(crate) fn mask_json(input: &str, front_matter: &FrontMatter<'_>) -> String {
+    input
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            if index < front_matter.end_line {
+                &line[line.trim_end_matches(['\r', '\n']).len()..]
+            } else {
+                line
+            }
+        })
+        .collect()
+}
+
+/// Apply the `hongdown` options from the front matter of `input`, if any.
+///
+/// Returns the effective options for the document along with warnings for
+/// overrides that could not be applied.  Only the keys that are present in
+/// the front matter are overridden; every other option keeps the value given
+/// in `options`.  When the overrides are invalid they are ignored entirely.
+pub(crate) fn apply_overrides<'o>(
+    input: &str,
+    options: &'o Options,
+) -> (Cow<'o, Options>, Vec<Warning>) {
+    let mut warnings = Vec::new();
+    let Some(front_matter) = detect(input) else {
+        return (Cow::Borrowed(options), warnings);
+    };
+    let line = find_options_line(&front_matter);
+
+    let table = match extract_options_table(&front_matter) {
+        Ok(Some(table)) => table,
+        Ok(None) => return (Cow::Borrowed(options), warnings),
+        Err(message) => {
+            warnings.push(Warning {
+                line,
+                message: format!("ignoring invalid front matter options: {}", message),
+            });
+            return (Cow::Borrowed(options), warnings);
+        }
+    };
+
+    let mut table = table;
+    for key in IGNORED_KEYS {
+        if table.remove(*key).is_some() {
+            warnings.push(Warning {
+                line,
+                message: format!("`{}` has no effect in front matter options", key),
+            });
+        }
+    }
+    // Running arbitrary commands named by the document being formatted
+    // would be a security hole, so external formatters stay config-only.
+    if let Some(code_block) = table.get_mut("code_block").and_then(|v| v.as_table_mut())
+        && code_block.remove("formatters").is_some()
+    {
+        warnings.push(Warning {
+            line,
+            message: "`code_block.formatters` cannot be set in front matter options".to_string(),
+        });
+    }
+    // Likewise, the document should not make the formatter read other files.
+    if let Some(heading) = table.get_mut("heading").and_then(|v| v.as_table_mut())
+        && heading.remove("proper_noun_files").is_some()
+    {
+        warnings.push(Warning {
+            line,
+            message: "`heading.proper_noun_files` cannot be set in front matter options"
+                .to_string(),
+        });
+    }
+
+    let layer: ConfigLayer = match toml::Value::Table(table.clone()).try_into() {
+        Ok(layer) => layer,
+        Err(e) => {
+            warnings.push(Warning {
+                line,
+                message: format!(
+                    "ignoring invalid front matter options: {}",
+                    e.to_string().trim_end()
+                ),
+            });
+            return (Cow::Borrowed(options), warnings);
+        }
+    };
+
+    let mut overridden = options.clone();
+    layer.apply_to_options(&mut overridden, &table);
+    if let Err(e) = validate_dash_settings(&overridden) {
+        warnings.push(Warning {
+            line,
+            message: format!("ignoring invalid front matter options: {}", e),
+        });
+        return (Cow::Borrowed(options), warnings);
+    }
+    if let Err(e) = validate_proper_noun_categories(&overridden) {
+        warnings.push(Warning {
+            line,
+            message: format!("ignoring invalid front matter options: {}", e),
+        });
+        return (Cow::Borrowed(options), warnings);
+    }
+
+    (Cow::Owned(overridden), warnings)
+}
+
+/// Extract the `hongdown` table from the front matter.
+///
+/// Returns `Ok(None)` if the front matter has no `hongdown` key, or if the
+/// front matter itself cannot be parsed (it is not ours to complain about).
+fn extract_options_table(front_matter: &FrontMatter<'_>) -> Result<Option<toml::Table>, String> {
+    match front_matter.kind {
+        // JSON is parsed with the YAML parser, as YAML is a superset of it.
+        FrontMatterKind::Yaml | FrontMatterKind::Json => {
+            let Ok(serde_yaml_ng::Value::Mapping(mapping)) =
+                serde_yaml_ng::from_str::<serde_yaml_ng::Value>(front_matter.content)
+            else {
+                return Ok(None);
+            };
+            let Some(value) = mapping.get(OPTIONS_KEY) else {
+                return Ok(None);
+            };
+            toml::Table::deserialize(value.clone())
+                .map(Some)
+                .map_err(|e| format!("`{}` must be a mapping of options ({})", OPTIONS_KEY, e))
+        }
+        FrontMatterKind::Toml => {
+            let Ok(mut table) = front_matter.content.parse::<toml::Table>() else {
+                return Ok(None);
+            };
+            match table.remove(OPTIONS_KEY) {
+                None => Ok(None),
+                Some(toml::Value::Table(options)) => Ok(Some(options)),
+                Some(_) => Err(format!("`{}` must be a table of options", OPTIONS_KEY)),
+            }
+        }
+    }
+}
+
+/// Find the 1-indexed document line that introduces the `hongdown` key,
+/// falling back to the opening delimiter line.
+fn find_options_line(front_matter: &FrontMatter<'_>) -> usize {
+    front_matter
+        .content
+        .lines()
+        .position(|line| match front_matter.kind {
+            FrontMatterKind::Yaml => line
+                .strip_prefix(OPTIONS_KEY)
+                .is_some_and(|rest| rest.trim_start().starts_with(':')),
+            FrontMatterKind::Json => line
+                .trim_start()
+                .starts_with(&format!("\"{}\"", OPTIONS_KEY)),
+            FrontMatterKind::Toml => {
+                let line = line.trim();
+                line == format!("[{}]", OPTIONS_KEY)
+                    || line.starts_with(&format!("[{}.", OPTIONS_KEY))
+                    || line
+                        .strip_prefix(OPTIONS_KEY)
+                        .is_some_and(|rest| rest.trim_start().starts_with(['=', '.']))
+            }
+        })
+        .map(|index| match front_matter.kind {
+            FrontMatterKind::Json => index + 1,
+            _ => index + 2,
+        })
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_yaml_front_matter() {
+        let input = "---\ntitle: Test\n---\n\n# Hello\n";
+        let front_matter = detect(input).unwrap();
+        assert_eq!(front_matter.kind, FrontMatterKind::Yaml);
+        assert_eq!(front_matter.content, "title: Test\n");
+    }
+
+    #[test]
+    fn test_detect_toml_front_matter() {
+        let input = "+++\ntitle = \"Test\"\n+++\n\n# Hello\n";
+        let front_matter = detect(input).unwrap();
+        assert_eq!(front_matter.kind, FrontMatterKind::Toml);
+        assert_eq!(front_matter.content, "title = \"Test\"\n");
+    }
+
+    #[test]
+    fn test_detect_no_front_matter() {
+        assert_eq!(detect("# Hello\n"), None);
+        assert_eq!(detect("---\nunclosed\n"), None);
+        assert_eq!(detect("--- \ntitle: Test\n---\n"), None);
+        assert_eq!(detect("{% include header.html %}\n"), None);
+        assert_eq!(detect("{\n\"title\": \"Test\"\n"), None);
+    }
+
+    #[test]
+    fn test_detect_json_front_matter() {
+        let input = "{\n  \"title\": \"Test }\",\n  \"tags\": [\"a\"]\n}\n\n# Hello\n";
+        let front_matter = detect(input).unwrap();
+        assert_eq!(front_matter.kind, FrontMatterKind::Json);
+        assert_eq!(
+            front_matter.content,
+            "{\n  \"title\": \"Test }\",\n  \"tags\": [\"a\"]\n}"
+        );
+        assert_eq!(front_matter.end_line, 4);
+    }
+
+    #[test]
+    fn test_detect_end_line() {
+        let front_matter = detect("---\ntitle: Test\ndate: 2025-01-01\n---\n").unwrap();
+        assert_eq!(front_matter.end_line, 4);
+    }
+
+    #[test]
+    fn test_mask_json() {
+        let input = "{\n  \"title\": \"Test\"\n}\n\n# Hello\r\n";
+        let front_matter = detect(input).unwrap();
+        assert_eq!(mask_json(input, &front_matter), "\n\n\n\n# Hello\r\n");
+    }
+
+    #[test]
+    fn test_apply_overrides_json() {
+        let input = "{\n  \"title\": \"Test\",\n  \"hongdown\": {\"line_width\": 4}\n}\n";
+        let options = Options::default();
+        let (_, warnings) = apply_overrides(input, &options);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 3);
+        let input = "{\n  \"hongdown\": {\"line_width\": 60}\n}\n";
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(warnings.is_empty());
+        assert_eq!(result.line_width.get(), 60);
+    }
+
+    #[test]
+    fn test_apply_overrides_without_front_matter() {
+        let options = Options::default();
+        let (result, warnings) = apply_overrides("# Hello\n", &options);
+        assert!(matches!(result, Cow::Borrowed(_)));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_apply_overrides_without_options_key() {
+        let options = Options::default();
+        let (result, warnings) = apply_overrides("---\ntitle: Test\n---\n", &options);
+        assert!(matches!(result, Cow::Borrowed(_)));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_apply_overrides_yaml() {
+        let input = "---\ntitle: Test\nhongdown:\n  line_width: 72\n  heading:\n    sentence_case: true\n---\n";
+        let options = Options {
+            setext_h1: false,
+            ..Options::default()
+        };
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(warnings.is_empty());
+        assert_eq!(result.line_width.get(), 72);
+        assert!(result.heading_sentence_case);
+        // Keys not mentioned in the front matter keep their values.
+        assert!(!result.setext_h1);
+    }
+
+    #[test]
+    fn test_apply_overrides_toml() {
+        let input = "+++\ntitle = \"Test\"\n\n[hongdown]\nline_width = 60\n\n[hongdown.heading]\nproper_nouns = [\"Hongdown\"]\n+++\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(warnings.is_empty());
+        assert_eq!(result.line_width.get(), 60);
+        assert_eq!(result.heading_proper_nouns, vec!["Hongdown".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_overrides_invalid_value() {
+        let input = "---\ntitle: Test\nhongdown:\n  line_width: 4\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert_eq!(result.line_width.get(), 80);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 3);
+        assert!(
+            warnings[0]
+                .message
+                .contains("line_width must be at least 8")
+        );
+    }
+
+    #[test]
+    fn test_apply_overrides_not_a_mapping() {
+        let input = "---\nhongdown: yes\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(matches!(result, Cow::Borrowed(_)));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 2);
+    }
+
+    #[test]
+    fn test_apply_overrides_ignores_file_collection_keys() {
+        let input = "---\nhongdown:\n  include: [\"*.md\"]\n  line_width: 100\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert_eq!(result.line_width.get(), 100);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("`include`"));
+    }
+
+    #[test]
+    fn test_apply_overrides_rejects_formatters() {
+        let input = "---\nhongdown:\n  code_block:\n    formatters:\n      python: [\"black\", \"-\"]\n    fence_char: \"`\"\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(result.code_formatters.is_empty());
+        assert_eq!(result.fence_char, crate::FenceChar::Backtick);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("code_block.formatters"));
+    }
+
+    #[test]
+    fn test_apply_overrides_conflicting_dashes() {
+        let input = "---\nhongdown:\n  punctuation:\n    en_dash: \"--\"\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(matches!(result, Cow::Borrowed(_)));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_apply_overrides_rejects_proper_noun_files() {
+        let input = "---\nhongdown:\n  heading:\n    proper_noun_files: [glossary.txt]\n    proper_nouns: [FooBar]\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert_eq!(result.heading_proper_nouns, vec!["FooBar"]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("heading.proper_noun_files"));
+    }
+
+    #[test]
+    fn test_apply_overrides_unknown_category() {
+        let input = "---\nhongdown:\n  heading:\n    disabled_categories: [contries]\n---\n";
+        let options = Options::default();
+        let (result, warnings) = apply_overrides(input, &options);
+        assert!(matches!(result, Cow::Borrowed(_)));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("contries"));
+    }
+}
@@ -13,20 +13,36 @@
 
 use std::collections::HashMap;
 
+mod callout;
 pub mod config;
+mod container;
+mod front_matter;
+pub mod link_check;
+mod mdx;
+mod scan;
 mod serializer;
+mod template;
+mod wikilink;
 
 #[cfg(feature = "wasm")]
 mod wasm;
 
 pub use config::{
-    DashPattern, DashSetting, FenceChar, IndentWidth, LeadingSpaces, LineWidth, MinFenceLength,
-    OrderedListPad, OrderedMarker, ThematicBreakStyle, TrailingSpaces, UnorderedMarker,
+    CalloutTarget, CalloutTypeCase, ConflictingLabels, ContainerStyle, DashPattern, DashSetting,
+    DuplicateUrls, ExternalLinks, FenceChar, FrontMatterQuoteStyle, HeadingCase, IndentWidth,
+    LeadingSpaces, LineWidth, MathDelimiters, MinFenceLength, OrderedListPad, OrderedMarker,
+    ProperNounLint, ReferenceLabel, ReferenceOrder, ReferencePlacement, SentenceCase,
+    ThematicBreakStyle, TitleCaseStyle, TrailingSpaces, UnorderedMarker, UnusedReferences,
 };
 pub use serializer::Warning;
+pub use serializer::heading::{
+    HeadingError, PROPER_NOUN_CATEGORIES, validate_proper_noun_categories,
+};
 pub use serializer::punctuation::{PunctuationError, validate_dash_settings};
 
-use comrak::{Arena, Options as ComrakOptions, parse_document};
+use comrak::nodes::AstNode;
+use comrak::{Arena, Options as ComrakOptions};
+use front_matter::FrontMatterKind;
 
 /// External code formatter configuration.
 #[derive(Debug, Clone)]
@@ -49,9 +65,67 @@ pub struct Options {
     /// Use setext-style (underlined) for h2 headings. Default: true.
     pub setext_h2: bool,
 
-    /// Convert headings to sentence case. Default: false.
+    /// Convert headings to sentence case. This is a shorthand for
+    /// `heading_case` set to `HeadingCase::Sentence`, and only applies while
+    /// `heading_case` is `HeadingCase::Preserve`. Default: false.
     pub heading_sentence_case: bool,
 
+    /// Instead of converting headings to sentence case, leave them untouched
+    /// and warn about each heading that sentence case would change, along
+    /// with the words it would lowercase.  Only applies while `heading_case`
+    /// is `HeadingCase::Preserve`. Default: false.
+    pub heading_sentence_case_report: bool,
+
+    /// Convert the text of links in headings along with the rest of the
+    /// heading; if disabled, it is left as written. Their URLs and labels
+    /// are never converted. Default: true.
+    pub heading_sentence_case_links: bool,
+
+    /// Convert the header cells of tables to sentence case. Default: false.
+    pub heading_sentence_case_table_headers: bool,
+
+    /// Convert the terms of description lists to sentence case.
+    /// Default: false.
+    pub heading_sentence_case_terms: bool,
+
+    /// How the case of headings is converted. Default: `HeadingCase::Preserve`.
+    pub heading_case: HeadingCase,
+
+    /// The style guide whose rules title case follows.
+    /// Default: `TitleCaseStyle::Chicago`.
+    pub heading_title_case_style: TitleCaseStyle,
+
+    /// Level to shift headings to, so that the shallowest one is at this
+    /// level, e.g., 2 to turn h1s into h2s and h2s into h3s. Levels are kept
+    /// between 1 and 6. 0 leaves the levels alone. Default: 0.
+    pub heading_top_level: u8,
+
+    /// Shift headings so that the first one becomes an h1.
+    /// Overrides `heading_top_level`. Default: false.
+    pub heading_promote_first: bool,
+
+    /// Close gaps in the heading hierarchy, so that no heading is more than
+    /// one level deeper than the heading it belongs to.
+    /// Default: false.
+    pub heading_fix_skipped_levels: bool,
+
+    /// Prefix top-level headings with hierarchical section numbers, such as
+    /// `2.3.1`, replacing any numbers they already start with.
+    /// Default: false.
+    pub heading_numbering: bool,
+
+    /// Highest heading level (h1 being the highest) to number. Default: 2.
+    pub heading_numbering_min_level: u8,
+
+    /// Lowest heading level to number. Default: 6.
+    pub heading_numbering_max_level: u8,
+
+    /// Also replace plain numbers such as `3` that top-level numbered
+    /// headings start with, which are otherwise only replaced when they are
+    /// the same as the new number, as they may be part of the heading text,
+    /// as in `3 ways to win`. Default: false.
+    pub heading_numbering_replace_plain: bool,
+
     /// Additional proper nouns to preserve (case-sensitive).
     /// These are merged with built-in proper nouns.
     pub heading_proper_nouns: Vec<String>,
@@ -60,6 +134,11 @@ pub struct Options {
     /// These are excluded from built-in proper nouns.
     pub heading_common_nouns: Vec<String>,
 
+    /// Categories of built-in proper nouns to leave out, such as
+    /// `"countries"` or `"programming-languages"`; see
+    /// [`PROPER_NOUN_CATEGORIES`] for all of them.  Default: empty.
+    pub heading_disabled_categories: Vec<String>,
+
     /// Marker character for unordered lists: `-`, `*`, or `+`. Default: `-`.
     pub unordered_marker: UnorderedMarker,
 
@@ -133,6 +212,138 @@ pub struct Options {
     /// The pattern is replaced with `—` (U+2014).
     pub em_dash: DashSetting,
 
+    /// Normalize YAML and TOML front matter. Default: false.
+    /// When false, front matter is preserved verbatim.  When true, trailing
+    /// whitespace is removed, indentation and string quoting are made
+    /// consistent, and top-level keys are reordered; comments are preserved.
+    /// If the front matter cannot be parsed, or normalizing would change its
+    /// data, it is left untouched and a warning is emitted.
+    pub front_matter_canonicalize: bool,
+
+    /// Indentation width for nested YAML mappings and multiline TOML arrays
+    /// in canonicalized front matter. Default: 2.
+    pub front_matter_indent_width: IndentWidth,
+
+    /// Quoting style for quoted strings in canonicalized front matter.
+    /// Default: `Preserve`.
+    /// Only strings that are already quoted are affected.
+    pub front_matter_quote_style: FrontMatterQuoteStyle,
+
+    /// Top-level front matter keys to move to the beginning, in this order.
+    /// Default: empty.
+    pub front_matter_key_order: Vec<String>,
+
+    /// What to do with reference definitions that no link uses.
+    /// Default: `Remove`.
+    pub link_unused_references: UnusedReferences,
+
+    /// What to do with different reference labels that point to the same URL.
+    /// Default: `Keep`.
+    pub link_duplicate_urls: DuplicateUrls,
+
+    /// What to do when a reference label is defined twice with different
+    /// URLs. Default: `Warn`.
+    pub link_conflicting_labels: ConflictingLabels,
+
+    /// Where reference definitions and footnote definitions are placed.
+    /// Default: `Section`.
+    pub link_reference_placement: ReferencePlacement,
+
+    /// Whether external links are converted to reference-style links.
+    /// Default: `Reference`.
+    pub link_external_links: ExternalLinks,
+
+    /// Convert only external links whose URL is wider than this many
+    /// columns. Default: `0`.
+    pub link_min_url_width: usize,
+
+    /// Additional URL schemes (e.g., `mailto`, `ftp`) whose links are treated
+    /// as external, besides `http` and `https`. Default: empty.
+    pub link_external_schemes: Vec<String>,
+
+    /// Domains whose links are treated as external whatever their scheme,
+    /// including protocol-relative URLs and subdomains. Default: empty.
+    pub link_external_domains: Vec<String>,
+
+    /// How labels are chosen for external links converted to reference
+    /// style. Default: `Text`.
+    pub link_reference_label: ReferenceLabel,
+
+    /// The order of reference definitions within each group of definitions.
+    /// Default: `Auto`.
+    pub link_reference_order: ReferenceOrder,
+
+    /// Whether numeric reference labels (`[1]`, `[2]`, ...) are renumbered
+    /// in order of first use, rewriting the links that use them.
+    /// Default: `false`.
+    pub link_renumber_references: bool,
+
+    /// Recognize `~~strikethrough~~`. Default: `false`.
+    pub extension_strikethrough: bool,
+
+    /// Recognize `^superscript^`. Default: `false`.
+    pub extension_superscript: bool,
+
+    /// Recognize `~subscript~`. Default: `false`.
+    pub extension_subscript: bool,
+
+    /// Recognize `$inline$` and `$$display$$` math. Default: `false`.
+    pub extension_math: bool,
+
+    /// How display math blocks are delimited. Default: `Dollar`.
+    pub extension_math_delimiters: MathDelimiters,
+
+    /// Recognize bare URLs, `www.` links, and email addresses as links, as
+    /// GitHub does. Default: `false`.
+    pub extension_autolink: bool,
+
+    /// Recognize `__underline__` instead of `__strong__`. Default: `false`.
+    pub extension_underline: bool,
+
+    /// Recognize `||spoiler||`. Default: `false`.
+    pub extension_spoiler: bool,
+
+    /// Recognize `:emoji:` shortcodes. Default: `false`.
+    pub extension_shortcodes: bool,
+
+    /// Recognize `[[Page|alias]]` wikilinks and `![[file]]` embeds, which
+    /// are kept verbatim. Default: `false`.
+    pub extension_wikilinks: bool,
+
+    /// Treat the document as MDX: ESM statements, JSX block elements, and
+    /// `{expressions}` are kept verbatim. Default: `false`.
+    pub extension_mdx: bool,
+
+    /// Recognize `:::type` containers and Pandoc fenced divs. Default: `false`.
+    pub extension_containers: bool,
+
+    /// Whether containers of the alert types and GitHub alerts are converted
+    /// to each other. Default: `Preserve`.
+    pub extension_container_style: ContainerStyle,
+
+    /// Recognize Obsidian-style callouts of any type. Default: `false`.
+    pub extension_callouts: bool,
+
+    /// How the types of alerts and callouts are capitalized. Default:
+    /// `Upper`.
+    pub extension_callout_type_case: CalloutTypeCase,
+
+    /// The renderer alerts and callouts are written for; `Github` warns
+    /// about callout types it does not support. Default: `Any`.
+    pub extension_callout_target: CalloutTarget,
+
+    /// Pairs of opening and closing delimiters of templating syntax, such as
+    /// `{{`/`}}` and `{%`/`%}`. Template tags are kept verbatim: inline ones
+    /// are neither escaped, curly-quoted, nor broken across lines, and lines
+    /// of tags only are left as they are. Default: empty.
+    pub template_delimiters: Vec<(String, String)>,
+
+    /// What to do about known proper nouns written in the wrong case in the
+    /// body text, such as `Javascript` or `github`: nothing, warn about them,
+    /// or fix them. The proper nouns and common nouns of headings apply.
+    /// Default: `ProperNounLint::Off`.
+    pub lint_proper_nouns: ProperNounLint,
+
     /// External code formatters by language.
     ///
     /// Key: language identifier (exact match only).
@@ -154,8 +365,22 @@ impl Default for Options {
             setext_h1: true,
             setext_h2: true,
             heading_sentence_case: false,
+            heading_sentence_case_report: false,
+            heading_sentence_case_links: true,
+            heading_sentence_case_table_headers: false,
+            heading_sentence_case_terms: false,
+            heading_case: HeadingCase::Preserve,
+            heading_title_case_style: TitleCaseStyle::Chicago,
+            heading_top_level: 0,
+            heading_promote_first: false,
+            heading_fix_skipped_levels: false,
+            heading_numbering: false,
+            heading_numbering_min_level: 2,
+            heading_numbering_max_level: 6,
+            heading_numbering_replace_plain: false,
             heading_proper_nouns: Vec::new(),
             heading_common_nouns: Vec::new(),
+            heading_disabled_categories: Vec::new(),
             unordered_marker: UnorderedMarker::default(),
             leading_spaces: LeadingSpaces::default(),
             trailing_spaces: TrailingSpaces::default(),
@@ -176,6 +401,39 @@ impl Default for Options {
             ellipsis: true,
             en_dash: DashSetting::Disabled,
             em_dash: DashSetting::Pattern(DashPattern::new("--".to_string()).unwrap()),
+            front_matter_canonicalize: false,
+            front_matter_indent_width: IndentWidth::new(2).unwrap(),
+            front_matter_quote_style: FrontMatterQuoteStyle::default(),
+            front_matter_key_order: Vec::new(),
+            link_unused_references: UnusedReferences::default(),
+            link_duplicate_urls: DuplicateUrls::default(),
+            link_conflicting_labels: ConflictingLabels::default(),
+            link_reference_placement: ReferencePlacement::default(),
+            link_external_links: ExternalLinks::default(),
+            link_min_url_width: 0,
+            link_external_schemes: Vec::new(),
+            link_external_domains: Vec::new(),
+            link_reference_label: ReferenceLabel::default(),
+            link_reference_order: ReferenceOrder::default(),
+            link_renumber_references: false,
+            extension_strikethrough: false,
+            extension_superscript: false,
+            extension_subscript: false,
+            extension_math: false,
+            extension_math_delimiters: MathDelimiters::Dollar,
+            extension_autolink: false,
+            extension_underline: false,
+            extension_spoiler: false,
+            extension_shortcodes: false,
+            extension_wikilinks: false,
+            extension_mdx: false,
+            extension_containers: false,
+            extension_container_style: ContainerStyle::Preserve,
+            extension_callouts: false,
+            extension_callout_type_case: CalloutTypeCase::Upper,
+            extension_callout_target: CalloutTarget::Any,
+            template_delimiters: Vec::new(),
+            lint_proper_nouns: ProperNounLint::Off,
             code_formatters: HashMap::new(),
         }
     }
@@ -192,6 +450,9 @@ impl Default for Options {
 /// - `<!-- hongdown-disable -->` - Disable formatting from this point.
 /// - `<!-- hongdown-enable -->` - Re-enable formatting.
 ///
+/// Options can also be overridden per document through a `hongdown` key in
+/// the document's front matter, whose contents mirror *.hongdown.toml*.
+///
 /// # Arguments
 ///
 /// * `input` - The Markdown source to format.
@@ -209,17 +470,11 @@ pub fn format(input: &str, options: &Options) -> Result<String, FormatError> {
         return Ok(String::new());
     }
 
-    let arena = Arena::new();
-    let mut comrak_options = ComrakOptions::default();
-    comrak_options.extension.front_matter_delimiter = Some("---".to_string());
-    comrak_options.extension.table = true;
-    comrak_options.extension.description_lists = true;
-    comrak_options.extension.alerts = true;
-    comrak_options.extension.footnotes = true;
-    comrak_options.extension.tasklist = true;
+    let (options, _) = front_matter::apply_overrides(input, options);
 
-    let root = parse_document(&arena, input, &comrak_options);
-    let output = serializer::serialize_with_source(root, options, Some(input));
+    let arena = Arena::new();
+    let root = parse_document(&arena, input, &options);
+    let output = serializer::serialize_with_source(root, &options, Some(input));
 
     Ok(output)
 }
@@ -231,6 +486,12 @@ pub struct FormatResult {
     pub output: String,
     /// Warnings generated during formatting.
     pub warnings: Vec<Warning>,
+    /// Capitalized words in headings that are not known proper nouns, and
+    /// that sentence case would therefore lowercase, in the order they
+    /// appear.  Articles, prepositions, and other words too common to be
+    /// proper nouns are left out.  Only collected when
+    /// [`Options::heading_sentence_case_report`] is enabled.
+    pub unknown_words: Vec<String>,
 }
 
 /// Formats a Markdown document and returns both output and warnings.
@@ -251,10 +512,92 @@ pub fn format_with_warnings(input: &str, options: &Options) -> Result<FormatResu
         return Ok(FormatResult {
             output: String::new(),
             warnings: Vec::new(),
+            unknown_words: Vec::new(),
         });
     }
 
+    let (options, mut warnings) = front_matter::apply_overrides(input, options);
+
     let arena = Arena::new();
+    let root = parse_document(&arena, input, &options);
+    let result = serializer::serialize_with_source_and_warnings(root, &options, Some(input));
+    warnings.extend(result.warnings);
+
+    Ok(FormatResult {
+        output: result.output,
+        warnings,
+        unknown_words: result.unknown_words,
+    })
+}
+
+/// Parse a Markdown document, taking its front matter syntax into account.
+///
+/// YAML (`---`) and TOML (`+++`) front matter become a front matter node.
+/// JSON front matter is hidden from the parser (see
+/// [`front_matter::mask_json`]) and emitted verbatim by the serializer.
+/// So are template tags (see [`template::mask`]), the MDX-specific parts of
+/// the document in MDX mode (see [`mdx::mask`]), and container fences (see
+/// [`container::mask`]), while callouts of types unknown to the parser are
+/// masked as notes (see [`callout::mask`]) and wikilink embeds as wikilinks
+/// (see [`wikilink::mask`]).
+pub(crate) fn parse_document<'a>(
+    arena: &'a Arena<'a>,
+    input: &str,
+    options: &Options,
+) -> &'a AstNode<'a> {
+    let masked_templates;
+    let input = if options.template_delimiters.is_empty() {
+        input
+    } else {
+        masked_templates = template::mask(input, &options.template_delimiters);
+        masked_templates.as_str()
+    };
+    let masked_mdx;
+    let input = if options.extension_mdx {
+        masked_mdx = mdx::mask(input);
+        masked_mdx.as_str()
+    } else {
+        input
+    };
+    let masked_containers;
+    let input = if options.extension_containers {
+        masked_containers = container::mask(input);
+        masked_containers.as_str()
+    } else {
+        input
+    };
+    let masked_wikilinks;
+    let input = if options.extension_wikilinks {
+        masked_wikilinks = wikilink::mask(input);
+        masked_wikilinks.as_str()
+    } else {
+        input
+    };
+    let masked_callouts;
+    let input = if options.extension_callouts {
+        masked_callouts = callout::mask(input);
+        masked_callouts.as_str()
+    } else {
+        input
+    };
+    let mut comrak_options = comrak_options(options);
+    match front_matter::detect(input) {
+        Some(front_matter) if front_matter.kind == FrontMatterKind::Json => {
+            let masked = front_matter::mask_json(input, &front_matter);
+            return comrak::parse_document(arena, &masked, &comrak_options);
+        }
+        Some(front_matter) => {
+            comrak_options.extension.front_matter_delimiter =
+                front_matter.kind.delimiter().map(str::to_string);
+        }
+        None => {}
+    }
+    comrak::parse_document(arena, input, &comrak_options)
+}
+
+/// Build the comrak options shared by every formatting entry point, with
+/// the optional extensions enabled in `options`.
+pub(crate) fn comrak_options(options: &Options) -> ComrakOptions<'static> {
     let mut comrak_options = ComrakOptions::default();
     comrak_options.extension.front_matter_delimiter = Some("---".to_string());
     comrak_options.extension.table = true;
@@ -262,14 +605,16 @@ pub fn format_with_warnings(input: &str, options: &Options) -> Result<FormatResu
     comrak_options.extension.alerts = true;
     comrak_options.extension.footnotes = true;
     comrak_options.extension.tasklist = true;
-
-    let root = parse_document(&arena, input, &comrak_options);
-    let result = serializer::serialize_with_source_and_warnings(root, options, Some(input));
-
-    Ok(FormatResult {
-        output: result.output,
-        warnings: result.warnings,
-    })
+    comrak_options.extension.strikethrough = options.extension_strikethrough;
+    comrak_options.extension.superscript = options.extension_superscript;
+    comrak_options.extension.subscript = options.extension_subscript;
+    comrak_options.extension.math_dollars = options.extension_math;
+    comrak_options.extension.autolink = options.extension_autolink;
+    comrak_options.extension.underline = options.extension_underline;
+    comrak_options.extension.spoiler = options.extension_spoiler;
+    comrak_options.extension.shortcodes = options.extension_shortcodes;
+    comrak_options.extension.wikilinks_title_after_pipe = options.extension_wikilinks;
+    comrak_options
 }
 
 /// Errors that can occur during formatting.
@@ -0,0 +1,232 @@
+//! Checking that relative links point to existing files and headings.
+//!
+//! The [`LinkChecker`] resolves every relative link and image in a document
+//! against the document's directory.  Links with a `#fragment` into another
+//! Markdown file are additionally checked against the anchors of that file,
+//! which are computed once and cached, so a single checker can be shared by
+//! all files being processed, including from multiple threads.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use comrak::Arena;
+use comrak::nodes::NodeValue;
+
+use crate::serializer::anchor::percent_decode;
+use crate::{Options, Warning, front_matter, serializer};
+
+/// Checks relative links against the file system.
+#[derive(Debug)]
+pub struct LinkChecker {
+    options: Options,
+    /// Anchors of the Markdown files linked to, keyed by path.  `None` means
+    /// the file could not be read.
+    anchors: Mutex<HashMap<PathBuf, Option<Arc<HashSet<String>>>>>,
+}
+
+impl LinkChecker {
+    /// Create a link checker.  The options are used to compute the heading
+    /// anchors of linked Markdown files.
+    pub fn new(options: &Options) -> Self {
+        Self {
+            options: options.clone(),
+            anchors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check the relative links and images of a Markdown document.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The Markdown source of the document.
+    /// * `base_dir` - The directory relative links are resolved against,
+    ///   usually the directory containing the document.
+    ///
+    /// # Returns
+    ///
+    /// A warning for each link whose target file does not exist, or whose
+    /// `#fragment` matches no heading in the target Markdown file.
+    pub fn check(&self, input: &str, base_dir: &Path) -> Vec<Warning> {
+        let arena = Arena::new();
+        let root = crate::parse_document(&arena, input, &self.options);
+
+        let mut warnings = Vec::new();
+        for node in root.descendants() {
+            let url = match &node.data.borrow().value {
+                NodeValue::Link(link) | NodeValue::Image(link) => link.url.clone(),
+                _ => continue,
+            };
+            let Some((path, fragment)) = split_relative_url(&url) else {
+                continue;
+            };
+            let line = node.data.borrow().sourcepos.start.line;
+
+            let target = base_dir.join(percent_decode(path));
+            if !target.exists() {
+                warnings.push(Warning {
+                    line,
+                    message: format!("broken link: {} (file not found)", url),
+                });
+                continue;
+            }
+
+            if let Some(fragment) = fragment
+                && is_markdown_file(&target)
+                && let Some(anchors) = self.anchors_of(&target)
+                && !anchors.contains(&percent_decode(fragment))
+            {
+                warnings.push(Warning {
+                    line,
+                    message: format!(
+                        "broken link: {} (no heading with anchor #{} in {})",
+                        url,
+                        fragment,
+                        target.display()
+                    ),
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Get the anchors of a Markdown file, parsing it on first use.
+    fn anchors_of(&self, path: &Path) -> Option<Arc<HashSet<String>>> {
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if let Some(anchors) = self.anchors.lock().unwrap().get(&key) {
+            return anchors.clone();
+        }
+
+        // Parse outside the lock so other files can be checked meanwhile
+        let anchors = fs::read_to_string(&key).ok().map(|content| {
+            let (options, _) = front_matter::apply_overrides(&content, &self.options);
+            let arena = Arena::new();
+            let root = crate::parse_document(&arena, &content, &options);
+            Arc::new(serializer::collect_anchors(root, &options))
+        });
+        self.anchors
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(anchors)
+            .clone()
+    }
+}
+
+/// Split a relative URL into its path and fragment.  Returns `None` for
+/// URLs with a scheme, protocol-relative and absolute URLs, and fragment-only
+/// links, which are not resolved against the file system.
+fn split_relative_url(url: &str) -> Option<(&str, Option<&str>)> {
+    let (path, fragment) = match url.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment).filter(|f| !f.is_empty())),
+        None => (url, None),
+    };
+    let path = path.split('?').next().unwrap_or(path);
+    if path.is_empty() || path.starts_with('/') {
+        return None;
+    }
+    // A colon before the first slash means a scheme (e.g., `https:`, `mailto:`)
+    if path
+        .split('/')
+        .next()
+        .is_some_and(|first| first.contains(':'))
+    {
+        return None;
+    }
+    Some((path, fragment))
+}
+
+fn is_markdown_file(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().is_some_and(|ext| {
+            ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_relative_url() {
+        assert_eq!(split_relative_url("docs/a.md"), Some(("docs/a.md", None)));
+        assert_eq!(
+            split_relative_url("../a.md#usage"),
+            Some(("../a.md", Some("usage")))
+        );
+        assert_eq!(split_relative_url("a.md?raw=1#"), Some(("a.md", None)));
+        assert_eq!(split_relative_url("https://example.com/a.md"), None);
+        assert_eq!(split_relative_url("mailto:a@example.com"), None);
+        assert_eq!(split_relative_url("//example.com/a"), None);
+        assert_eq!(split_relative_url("/absolute/a.md"), None);
+        assert_eq!(split_relative_url("#usage"), None);
+    }
+
+    #[test]
+    fn test_check_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("exists.md"), "# Exists\n").unwrap();
+        let checker = LinkChecker::new(&Options::default());
+        let warnings = checker.check(
+            "See [a](exists.md) and [b](missing.md).\n\n![img](img/missing.png)\n",
+            dir.path(),
+        );
+        let messages: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.line, w.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (1, "broken link: missing.md (file not found)"),
+                (3, "broken link: img/missing.png (file not found)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_fragment_in_other_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(
+            dir.path().join("docs/guide.md"),
+            "Guide\n=====\n\nInstallation\n------------\n\n<a id=\"custom\"></a>\n",
+        )
+        .unwrap();
+        let checker = LinkChecker::new(&Options::default());
+        let warnings = checker.check(
+            "[a](docs/guide.md#installation) [b](docs/guide.md#custom) [c](docs/guide.md#usage)\n",
+            dir.path(),
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0]
+                .message
+                .starts_with("broken link: docs/guide.md#usage (no heading with anchor #usage"),
+            "got: {}",
+            warnings[0].message
+        );
+    }
+
+    #[test]
+    fn test_check_caches_anchors() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target.md");
+        fs::write(&target, "## Usage\n").unwrap();
+        let checker = LinkChecker::new(&Options::default());
+        assert!(
+            checker
+                .check("[a](target.md#usage)\n", dir.path())
+                .is_empty()
+        );
+
+        // The anchors parsed for the first document are reused
+        fs::write(&target, "## Other\n").unwrap();
+        assert!(
+            checker
+                .check("[b](target.md#usage)\n", dir.path())
+                .is_empty()
+        );
+    }
+}
@@ -1,14 +1,19 @@
 //! Hongdown CLI - A Markdown formatter for Hong Minhee's style conventions.
 
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use clap::Parser;
 use hongdown::config::Config;
-use hongdown::{CodeFormatter, LineWidth, Options, format_with_warnings, validate_dash_settings};
+use hongdown::link_check::LinkChecker;
+use hongdown::{
+    FormatError, FormatResult, LineWidth, Options, format_with_warnings, validate_dash_settings,
+};
 use rayon::prelude::*;
 use similar::{ChangeTag, TextDiff};
 use walkdir::WalkDir;
@@ -45,6 +50,11 @@ struct Args {
     /// Path to configuration file.
     #[arg(long, value_name = "FILE")]
     config: Option<PathBuf>,
+
+    /// Warn about relative links and images whose target file or heading
+    /// does not exist.
+    #[arg(long)]
+    check_links: bool,
 }
 
 fn main() -> ExitCode {
@@ -54,51 +64,16 @@ fn main() -> ExitCode {
     let (config, config_dir) = load_config(&args);
 
     // Build options, with CLI args overriding config file
-    let options = Options {
-        line_width: args
-            .line_width
-            .map(|w| LineWidth::new(w).expect("Invalid line width"))
-            .unwrap_or(config.line_width),
-        setext_h1: config.heading.setext_h1,
-        setext_h2: config.heading.setext_h2,
-        heading_sentence_case: config.heading.sentence_case,
-        heading_proper_nouns: config.heading.proper_nouns.clone(),
-        heading_common_nouns: config.heading.common_nouns.clone(),
-        unordered_marker: config.unordered_list.unordered_marker,
-        leading_spaces: config.unordered_list.leading_spaces,
-        trailing_spaces: config.unordered_list.trailing_spaces,
-        indent_width: config.unordered_list.indent_width,
-        odd_level_marker: config.ordered_list.odd_level_marker,
-        even_level_marker: config.ordered_list.even_level_marker,
-        ordered_list_pad: config.ordered_list.pad,
-        ordered_list_indent_width: config.ordered_list.indent_width,
-        fence_char: config.code_block.fence_char,
-        min_fence_length: config.code_block.min_fence_length,
-        space_after_fence: config.code_block.space_after_fence,
-        default_language: config.code_block.default_language.clone(),
-        thematic_break_style: config.thematic_break.style.clone(),
-        thematic_break_leading_spaces: config.thematic_break.leading_spaces,
-        curly_double_quotes: config.punctuation.curly_double_quotes,
-        curly_single_quotes: config.punctuation.curly_single_quotes,
-        curly_apostrophes: config.punctuation.curly_apostrophes,
-        ellipsis: config.punctuation.ellipsis,
-        en_dash: config.punctuation.en_dash.clone(),
-        em_dash: config.punctuation.em_dash.clone(),
-        code_formatters: config
-            .code_block
-            .formatters
-            .iter()
-            .map(|(lang, cfg)| {
-                (
-                    lang.clone(),
-                    CodeFormatter {
-                        command: cfg.command().to_vec(),
-                        timeout_secs: cfg.timeout(),
-                    },
-                )
-            })
-            .collect(),
+    let mut options = match config.to_options() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
     };
+    if let Some(line_width) = args.line_width {
+        options.line_width = LineWidth::new(line_width).expect("Invalid line width");
+    }
 
     // Validate formatter configurations
     for (lang, cfg) in &config.code_block.formatters {
@@ -114,6 +89,11 @@ fn main() -> ExitCode {
         return ExitCode::FAILURE;
     }
 
+    // Linked files are parsed once and shared by all input files
+    let link_checker = args.check_links.then(|| LinkChecker::new(&options));
+    let link_checker = link_checker.as_ref();
+    let unknown_words = &UnknownWords::default();
+
     // Check if stdin is explicitly requested via --stdin or `-` as filename
     let stdin_requested = args.stdin || args.files.iter().any(|f| f.to_str() == Some("-"));
 
@@ -143,12 +123,12 @@ fn main() -> ExitCode {
             .filter(|f| f.to_str() != Some("-"))
             .cloned()
             .collect();
-        expand_paths(&filtered)
+        expand_paths(&filtered, options.extension_mdx)
     } else {
-        expand_paths(&args.files)
+        expand_paths(&args.files, options.extension_mdx)
     };
 
-    if stdin_requested {
+    let exit_code = if stdin_requested {
         // Read from stdin
         let mut input = String::new();
         if let Err(e) = io::stdin().read_to_string(&mut input) {
@@ -156,7 +136,7 @@ fn main() -> ExitCode {
             return ExitCode::FAILURE;
         }
 
-        match format_with_warnings(&input, &options) {
+        match format_document(&input, None, &options, link_checker, unknown_words) {
             Ok(result) => {
                 // Print warnings to stderr
                 for warning in &result.warnings {
@@ -176,13 +156,89 @@ fn main() -> ExitCode {
         }
     } else if args.write || args.check {
         // Parallel processing for --write and --check modes
-        process_files_parallel(&files, &options, args.write, args.check)
+        process_files_parallel(
+            &files,
+            &options,
+            link_checker,
+            unknown_words,
+            args.write,
+            args.check,
+        )
     } else if args.diff {
         // Diff mode for files
-        process_files_diff(&files, &options)
+        process_files_diff(&files, &options, link_checker, unknown_words)
     } else {
         // Sequential processing for stdout mode (order matters)
-        process_files_sequential(&files, &options)
+        process_files_sequential(&files, &options, link_checker, unknown_words)
+    };
+
+    if options.heading_sentence_case_report {
+        unknown_words.print_summary();
+    }
+    exit_code
+}
+
+/// Format a document, adding the warnings of the link checker if enabled.
+///
+/// Relative links are resolved against the directory of `path`, or against
+/// the current directory for stdin.  Files with the *.mdx* extension are
+/// formatted as MDX.
+fn format_document(
+    input: &str,
+    path: Option<&Path>,
+    options: &Options,
+    link_checker: Option<&LinkChecker>,
+    unknown_words: &UnknownWords,
+) -> Result<FormatResult, FormatError> {
+    let mut result = if !options.extension_mdx && path.is_some_and(is_mdx_file) {
+        let options = Options {
+            extension_mdx: true,
+            ..options.clone()
+        };
+        format_with_warnings(input, &options)?
+    } else {
+        format_with_warnings(input, options)?
+    };
+    if let Some(checker) = link_checker {
+        let base_dir = path
+            .and_then(Path::parent)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        result.warnings.extend(checker.check(input, base_dir));
+        result.warnings.sort_by_key(|warning| warning.line);
+    }
+    unknown_words.record(&result.unknown_words);
+    Ok(result)
+}
+
+/// Capitalized heading words that sentence case would lowercase, counted
+/// across all files for the summary of sentence case report mode.
+#[derive(Default)]
+struct UnknownWords(Mutex<HashMap<String, usize>>);
+
+impl UnknownWords {
+    fn record(&self, words: &[String]) {
+        let mut counts = self.0.lock().unwrap();
+        for word in words {
+            *counts.entry(word.clone()).or_default() += 1;
+        }
+    }
+
+    /// Print the words to stderr, the most frequent first.
+    fn print_summary(&self) {
+        let counts = self.0.lock().unwrap();
+        if counts.is_empty() {
+            return;
+        }
+        let mut words: Vec<_> = counts.iter().collect();
+        words.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        eprintln!(
+            "Capitalized words that sentence case would lowercase \
+             (add the proper nouns among them to heading.proper_nouns):"
+        );
+        for (word, count) in words {
+            eprintln!("  {} ({})", word, count);
+        }
     }
 }
 
@@ -190,6 +246,8 @@ fn main() -> ExitCode {
 fn process_files_parallel(
     files: &[PathBuf],
     options: &Options,
+    link_checker: Option<&LinkChecker>,
+    unknown_words: &UnknownWords,
     write: bool,
     check: bool,
 ) -> ExitCode {
@@ -206,7 +264,7 @@ fn process_files_parallel(
             }
         };
 
-        match format_with_warnings(&input, options) {
+        match format_document(&input, Some(file), options, link_checker, unknown_words) {
             Ok(result) => {
                 // Print warnings to stderr
                 for warning in &result.warnings {
@@ -250,7 +308,12 @@ fn process_files_parallel(
 }
 
 /// Process files sequentially (for stdout mode where order matters).
-fn process_files_sequential(files: &[PathBuf], options: &Options) -> ExitCode {
+fn process_files_sequential(
+    files: &[PathBuf],
+    options: &Options,
+    link_checker: Option<&LinkChecker>,
+    unknown_words: &UnknownWords,
+) -> ExitCode {
     for file in files {
         let input = match fs::read_to_string(file) {
             Ok(content) => content,
@@ -260,7 +323,7 @@ fn process_files_sequential(files: &[PathBuf], options: &Options) -> ExitCode {
             }
         };
 
-        match format_with_warnings(&input, options) {
+        match format_document(&input, Some(file), options, link_checker, unknown_words) {
             Ok(result) => {
                 // Print warnings to stderr
                 for warning in &result.warnings {
@@ -284,7 +347,12 @@ fn process_files_sequential(files: &[PathBuf], options: &Options) -> ExitCode {
 }
 
 /// Process files in diff mode.
-fn process_files_diff(files: &[PathBuf], options: &Options) -> ExitCode {
+fn process_files_diff(
+    files: &[PathBuf],
+    options: &Options,
+    link_checker: Option<&LinkChecker>,
+    unknown_words: &UnknownWords,
+) -> ExitCode {
     for file in files {
         let input = match fs::read_to_string(file) {
             Ok(content) => content,
@@ -294,7 +362,7 @@ fn process_files_diff(files: &[PathBuf], options: &Options) -> ExitCode {
             }
         };
 
-        match format_with_warnings(&input, options) {
+        match format_document(&input, Some(file), options, link_checker, unknown_words) {
             Ok(result) => {
                 // Print warnings to stderr
                 for warning in &result.warnings {
@@ -346,13 +414,14 @@ fn print_diff(filename: &str, original: &str, formatted: &str) {
 
 /// Expand paths, converting directories to their contained `.md` files.
 ///
-/// If a path is a directory, recursively finds all `.md` files within it.
+/// If a path is a directory, recursively finds all `.md` files within it,
+/// and also `.mdx` files if `mdx` is true.
 /// If a path is a file, it is included as-is.
-fn expand_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
+fn expand_paths(paths: &[PathBuf], mdx: bool) -> Vec<PathBuf> {
     let mut result = Vec::new();
     for path in paths {
         if path.is_dir() {
-            result.extend(collect_md_files(path));
+            result.extend(collect_md_files(path, mdx));
         } else {
             result.push(path.clone());
         }
@@ -360,8 +429,9 @@ fn expand_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
     result
 }
 
-/// Recursively collect all Markdown files (`.md` and `.markdown`) from a directory.
-fn collect_md_files(dir: &Path) -> Vec<PathBuf> {
+/// Recursively collect all Markdown files (`.md` and `.markdown`) from a directory,
+/// and also MDX files (`.mdx`) if `mdx` is true.
+fn collect_md_files(dir: &Path, mdx: bool) -> Vec<PathBuf> {
     let mut files = Vec::new();
     for entry in WalkDir::new(dir).follow_links(true) {
         let Ok(entry) = entry else { continue };
@@ -369,7 +439,7 @@ fn collect_md_files(dir: &Path) -> Vec<PathBuf> {
         if path.is_file() {
             let is_markdown = path.extension().is_some_and(|ext| {
                 ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
-            });
+            }) || (mdx && is_mdx_file(path));
             if is_markdown {
                 files.push(path.to_path_buf());
             }
@@ -379,6 +449,12 @@ fn collect_md_files(dir: &Path) -> Vec<PathBuf> {
     files
 }
 
+/// Check if a path has the `.mdx` extension.
+fn is_mdx_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mdx"))
+}
+
 /// Load configuration from file or use defaults.
 ///
 /// Returns the configuration and the base directory for glob patterns.
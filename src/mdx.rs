@@ -0,0 +1,261 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! MDX support.
+//!
+//! Comrak knows nothing about MDX, so the MDX-specific parts of a document
+//! are masked before parsing, in a way that keeps the source positions of
+//! everything else intact:
+//!
+//!  -  ESM statements (`import`/`export`), JSX block elements, and
+//!     `{expression}` blocks at the top level become HTML comments that span
+//!     the same lines.  The serializer recognizes them by [`MARKER`] and
+//!     emits the original lines verbatim.
+//!  -  Inline `{expressions}` become code spans of the same length, so that
+//!     they are neither escaped nor parsed as Markdown.  The serializer
+//!     emits their original text instead of a code span.
+
+use crate::scan::{self, Block, Line};
+
+/// The start of the HTML comment that stands for an MDX block.
+pub(crate) const MARKER: &str = "<!--mdx";
+
+/// Check if an HTML block literal stands for a masked MDX block.
+pub(crate) fn is_masked_block(literal: &str) -> bool {
+    literal.starts_with(MARKER)
+}
+
+/// Mask the MDX blocks and inline expressions of `input`, leaving front
+/// matter and code alone.  Inline expressions in HTML blocks are left alone
+/// too, as the blocks are written back verbatim.
+pub(crate) fn mask(input: &str) -> String {
+    let lines = scan::scan(input);
+    let mut result = String::with_capacity(input.len());
+    let mut at_block_start = true;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        if line.is_code() {
+            // Nothing continues code, so a block can start right after it
+            at_block_start = true;
+            result.push_str(line.text);
+            i += 1;
+            continue;
+        }
+        if at_block_start
+            && line.can_start_block
+            && starts_block(line.content)
+            && let Some(end) = block_end(&lines, i)
+        {
+            for (j, line) in lines.iter().enumerate().take(end + 1).skip(i) {
+                let newline = &line.text[line.content.len()..];
+                if j == i {
+                    result.push_str(MARKER);
+                }
+                if j == end {
+                    result.push_str("-->");
+                }
+                result.push_str(newline);
+            }
+            i = end + 1;
+            continue;
+        }
+        at_block_start = line.content.trim().is_empty();
+        if line.block == Block::Html {
+            result.push_str(line.text);
+        } else {
+            result.push_str(&mask_expressions(line.content));
+            result.push_str(&line.text[line.content.len()..]);
+        }
+        i += 1;
+    }
+    result
+}
+
+/// Check if a line starts an ESM statement, a JSX block element, or an
+/// expression block.  ESM statements must not be indented, while JSX and
+/// expressions may be.
+fn starts_block(line: &str) -> bool {
+    if ["import ", "export ", "import{", "export{"]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+    {
+        return true;
+    }
+    let mut chars = line.trim_start().chars();
+    match chars.next() {
+        Some('{') => true,
+        Some('<') => match chars.next() {
+            Some('>') => true,
+            Some('/') => chars
+                .next()
+                .is_some_and(|c| c == '>' || c.is_ascii_uppercase()),
+            Some(c) => c.is_ascii_uppercase(),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Find the last line of the block that starts at `start`: the first line
+/// after which the brackets are balanced and a blank line (or the end of
+/// the input) follows.  Returns `None` if the brackets never balance.
+fn block_end(lines: &[Line<'_>], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    for (j, line) in lines.iter().enumerate().skip(start) {
+        let mut escaped = false;
+        for c in line.text.chars() {
+            if let Some(q) = quote {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    _ if c == q => quote = None,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                // Quotes only delimit strings inside JavaScript; elsewhere
+                // they are apostrophes in text
+                '"' | '\'' | '`' if depth > 0 => quote = Some(c),
+                '{' | '(' | '[' => depth += 1,
+                '}' | ')' | ']' => depth = depth.checked_sub(1)?,
+                _ => {}
+            }
+        }
+        let next_is_blank = lines
+            .get(j + 1)
+            .is_none_or(|next| next.content.trim().is_empty());
+        if depth == 0 && quote.is_none() && next_is_blank {
+            return Some(j);
+        }
+    }
+    None
+}
+
+/// Turn the inline `{expressions}` of a line into code spans of the same
+/// length, leaving code spans and HTML/JSX tags alone.
+fn mask_expressions(line: &str) -> String {
+    let mut chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '`' => i = skip_code_span(&chars, i),
+            '<' if chars
+                .get(i + 1)
+                .is_some_and(|&c| c == '/' || c.is_ascii_alphabetic()) =>
+            {
+                // Skip the tag; braces in its attributes are part of it
+                let mut depth = 0usize;
+                let mut j = i + 1;
+                while j < chars.len() && !(chars[j] == '>' && depth == 0) {
+                    match chars[j] {
+                        '{' => depth += 1,
+                        '}' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    j += 1;
+                }
+                i = j + 1;
+            }
+            '{' => match expression_end(&chars, i) {
+                Some(end)
+                    if !chars[i..=end].contains(&'`')
+                        && (i == 0 || chars[i - 1] != '`')
+                        && chars.get(end + 1).is_none_or(|&c| c != '`' && c != '{') =>
+                {
+                    chars[i] = '`';
+                    chars[end] = '`';
+                    i = end + 1;
+                }
+                _ => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// The index right after the code span that starts at `start`, or after its
+/// opening backticks if the span is never closed.
+pub(crate) fn skip_code_span(chars: &[char], start: usize) -> usize {
+    let run = chars[start..].iter().take_while(|&&c| c == '`').count();
+    let mut j = start + run;
+    while j < chars.len() {
+        let len = chars[j..].iter().take_while(|&&c| c == '`').count();
+        if len == run {
+            return j + len;
+        }
+        j += len.max(1);
+    }
+    start + run
+}
+
+/// Find the brace closing the expression that opens at `start`.
+fn expression_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (j, &c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(j);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_blocks() {
+        let input = "import {Tabs} from './tabs'\nexport const meta = {\n\n  title: 'A'\n}\n\n\
+                     # Title\n\n<Tabs>\n  <Tab />\n</Tabs>\n\n{1 + 1}\n";
+        assert_eq!(
+            mask(input),
+            "<!--mdx\n\n\n\n-->\n\n# Title\n\n<!--mdx\n\n-->\n\n<!--mdx-->\n"
+        );
+    }
+
+    #[test]
+    fn test_mask_keeps_code_and_paragraphs() {
+        let input = "```js\nimport x from 'y'\n```\n\nWe import\nexport files.\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_expressions() {
+        assert_eq!(
+            mask("Hi {user_name}, see `{x}` and <Badge v={a_b} />.\n"),
+            "Hi `user_name`, see `{x}` and <Badge v={a_b} />.\n"
+        );
+        assert_eq!(mask("Unclosed { brace\n"), "Unclosed { brace\n");
+    }
+
+    #[test]
+    fn test_mask_keeps_code_in_containers() {
+        let input = "- ```\n  {a}\n  ```\n\n> ```js\n> export const a = {b}\n> ```\n";
+        assert_eq!(mask(input), input);
+        let input = "Para\n\n    {expr} and {more}\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_keeps_html_blocks() {
+        let input = "<div>\n  Hi {name}\n</div>\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_unbalanced_block() {
+        let input = "<Note>(unbalanced\n\ntext\n";
+        assert_eq!(mask(input), input);
+    }
+}
@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Block contexts of source lines.
+//!
+//! The masking passes (see [`crate::parse_document`]) rewrite syntax that
+//! the parser does not know, line by line, before the document is parsed.
+//! Front matter, code blocks, and HTML blocks are written back verbatim, so
+//! nothing in them may be rewritten, and a line can only be masked as an
+//! HTML comment where a block can start.  Both depend on block quotes, list
+//! items, and indentation, so instead of tracking them itself, each pass
+//! goes through the lines returned by [`scan`], which parses the document
+//! to find out.
+
+use comrak::Arena;
+use comrak::nodes::NodeValue;
+
+use crate::front_matter;
+
+/// The kind of block a source line belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Block {
+    /// Front matter, including its delimiters.
+    FrontMatter,
+    /// A fenced code block, including its fences.
+    FencedCode,
+    /// An indented code block.
+    IndentedCode,
+    /// An HTML block.
+    Html,
+    /// Anything else, including blank lines.
+    Other,
+}
+
+/// A source line and the block it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Line<'s> {
+    /// The line with its line ending.
+    pub text: &'s str,
+    /// The line without its line ending.
+    pub content: &'s str,
+    /// The kind of block the line belongs to.
+    pub block: Block,
+    /// Whether a block can start on the line: it is indented by at most
+    /// three spaces past the markers of its block quotes and list items, or,
+    /// if it lacks some of them, past the ones it has.
+    pub can_start_block: bool,
+}
+
+impl Line<'_> {
+    /// Check if the line is written back verbatim: front matter, code, or
+    /// HTML.
+    pub(crate) fn is_verbatim(&self) -> bool {
+        self.block != Block::Other
+    }
+
+    /// Check if the line is in front matter or a code block.
+    pub(crate) fn is_code(&self) -> bool {
+        matches!(
+            self.block,
+            Block::FrontMatter | Block::FencedCode | Block::IndentedCode
+        )
+    }
+}
+
+/// A block that contains the lines it spans.
+#[derive(Debug, Clone, Copy)]
+enum Container {
+    /// A block quote, whose lines start with `>`.
+    Quote,
+    /// A list item or a footnote definition, which starts on `line` and
+    /// whose content starts at byte `column` of its lines.
+    Indented { line: usize, column: usize },
+}
+
+/// Split `input` into lines and find out the block each belongs to.
+pub(crate) fn scan(input: &str) -> Vec<Line<'_>> {
+    let texts: Vec<&str> = input.split_inclusive('\n').collect();
+    let mut blocks = vec![Block::Other; texts.len()];
+    let mut containers: Vec<Vec<Container>> = vec![Vec::new(); texts.len()];
+    let front_matter_end =
+        front_matter::detect(input).map_or(0, |front_matter| front_matter.end_line);
+    blocks[..front_matter_end.min(texts.len())].fill(Block::FrontMatter);
+
+    let mut comrak_options = crate::comrak_options(&crate::Options::default());
+    // Task items do not record where their content starts
+    comrak_options.extension.tasklist = false;
+    let arena = Arena::new();
+    let root = comrak::parse_document(&arena, input, &comrak_options);
+    // Containers come before the blocks in them, so that each line gets its
+    // containers from the outermost
+    for node in root.descendants() {
+        let data = node.data.borrow();
+        let start = data.sourcepos.start.line.saturating_sub(1);
+        let end = data.sourcepos.end.line.min(texts.len());
+        if start >= end {
+            continue;
+        }
+        let column = data.sourcepos.start.column.saturating_sub(1);
+        let block = match &data.value {
+            NodeValue::CodeBlock(code_block) if code_block.fenced => Block::FencedCode,
+            NodeValue::CodeBlock(_) => Block::IndentedCode,
+            NodeValue::HtmlBlock(_) => Block::Html,
+            NodeValue::BlockQuote | NodeValue::Alert(_) => {
+                for line in &mut containers[start..end] {
+                    line.push(Container::Quote);
+                }
+                continue;
+            }
+            NodeValue::Item(item) => {
+                let container = Container::Indented {
+                    line: start,
+                    column: column + item.padding,
+                };
+                for line in &mut containers[start..end] {
+                    line.push(container);
+                }
+                continue;
+            }
+            NodeValue::FootnoteDefinition(_) => {
+                let container = Container::Indented {
+                    line: start,
+                    column: column + 4,
+                };
+                for line in &mut containers[start..end] {
+                    line.push(container);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        for line in &mut blocks[start..end] {
+            if *line == Block::Other {
+                *line = block;
+            }
+        }
+    }
+
+    texts
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let content = text.trim_end_matches(['\r', '\n']);
+            Line {
+                text,
+                content,
+                block: blocks[i],
+                can_start_block: indentation(content, i, &containers[i]) <= 3,
+            }
+        })
+        .collect()
+}
+
+/// The indentation of the line at `index` past the markers of its
+/// `containers`, or past the ones it has if it lacks some.
+fn indentation(line: &str, index: usize, containers: &[Container]) -> usize {
+    let bytes = line.as_bytes();
+    let spaces = |pos: usize| {
+        bytes
+            .get(pos..)
+            .map_or(0, |rest| rest.iter().take_while(|&&b| b == b' ').count())
+    };
+    let mut pos = 0;
+    for container in containers {
+        match *container {
+            Container::Quote => {
+                let indent = spaces(pos);
+                if indent > 3 || bytes.get(pos + indent) != Some(&b'>') {
+                    return indent;
+                }
+                pos += indent + 1;
+                if bytes.get(pos) == Some(&b' ') {
+                    pos += 1;
+                }
+            }
+            Container::Indented { line, column } => {
+                // The first line has the marker in place of the indentation
+                if index != line && pos + spaces(pos) < column {
+                    return spaces(pos);
+                }
+                pos = pos.max(column);
+            }
+        }
+    }
+    spaces(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(input: &str) -> Vec<(Block, bool)> {
+        scan(input)
+            .iter()
+            .map(|line| (line.block, line.can_start_block))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_code_and_html() {
+        assert_eq!(
+            blocks("---\na: 1\n---\nPara\n\n    code\n\n<div>\nx\n</div>\n"),
+            vec![
+                (Block::FrontMatter, true),
+                (Block::FrontMatter, true),
+                (Block::FrontMatter, true),
+                (Block::Other, true),
+                (Block::Other, true),
+                (Block::IndentedCode, false),
+                (Block::IndentedCode, true),
+                (Block::Html, true),
+                (Block::Html, true),
+                (Block::Html, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_containers() {
+        assert_eq!(
+            blocks("> ```\n> x\n> ```\n\n - ```\n   y\n   ```\n"),
+            vec![
+                (Block::FencedCode, true),
+                (Block::FencedCode, true),
+                (Block::FencedCode, true),
+                (Block::Other, true),
+                (Block::FencedCode, true),
+                (Block::FencedCode, true),
+                (Block::FencedCode, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_can_start_block() {
+        let lines = scan("Para\n    lazy\n\n- item\n    nested\n> quote\n      lazy\n");
+        let can_start_block: Vec<bool> = lines.iter().map(|line| line.can_start_block).collect();
+        assert_eq!(
+            can_start_block,
+            vec![true, false, true, true, true, true, false]
+        );
+    }
+}
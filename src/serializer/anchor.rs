@@ -0,0 +1,174 @@
+//! Validation of links to headings within the same document.
+
+use std::collections::{HashMap, HashSet};
+
+use comrak::nodes::{AstNode, NodeValue};
+use regex::Regex;
+
+use super::Serializer;
+use super::state::Directive;
+
+impl<'a> Serializer<'a> {
+    /// Warn about `#fragment` links and reference definitions that point to
+    /// no heading and no explicit HTML `id` in the document.
+    ///
+    /// Heading anchors are computed the way GitHub does, after the configured
+    /// heading case is applied.  When a fragment matches the anchor a heading
+    /// had before its text was rewritten, or differs from an anchor only in
+    /// case, the warning suggests the current anchor.
+    pub(super) fn check_anchor_links<'b>(&mut self, document: &'b AstNode<'b>) {
+        if self.source_lines.is_empty() {
+            return;
+        }
+
+        let anchors = self.document_anchors(document);
+        let mut suggestions: HashMap<String, String> = HashMap::new();
+        for (slug, original_slug) in self.heading_anchors(document) {
+            if original_slug != slug {
+                suggestions.insert(original_slug, slug);
+            }
+        }
+
+        // Lines whose links are not checked: disabled regions, and tables of
+        // contents that are about to be regenerated
+        let mut ignored_ranges = Self::collect_disabled_line_ranges(document);
+        ignored_ranges.extend(Self::collect_toc_line_ranges(document));
+
+        let mut links = Vec::new();
+        self.collect_fragment_links(document, &mut links);
+        links.extend(self.collect_fragment_definitions(document));
+
+        for (line, fragment) in links {
+            if Self::is_line_in_disabled_ranges(line, &ignored_ranges) {
+                continue;
+            }
+            let decoded = percent_decode(&fragment);
+            if decoded.is_empty() || decoded == "top" || anchors.contains(&decoded) {
+                continue;
+            }
+            let suggestion = suggestions.get(&decoded).cloned().or_else(|| {
+                let lowercase = decoded.to_lowercase();
+                anchors.contains(&lowercase).then_some(lowercase)
+            });
+            let message = match suggestion {
+                Some(slug) => format!(
+                    "undefined anchor link: #{} (did you mean #{}?)",
+                    fragment, slug
+                ),
+                None => format!("undefined anchor link: #{}", fragment),
+            };
+            self.add_warning(line, message);
+        }
+    }
+
+    /// Collect the anchors the document defines: heading slugs and explicit
+    /// HTML `id` and `name` attributes.
+    pub(super) fn document_anchors<'b>(&self, document: &'b AstNode<'b>) -> HashSet<String> {
+        let mut anchors: HashSet<String> = self
+            .heading_anchors(document)
+            .into_iter()
+            .map(|(slug, _)| slug)
+            .collect();
+        anchors.extend(Self::collect_html_ids(document));
+        anchors
+    }
+
+    /// Collect `#fragment` inline links as (line, fragment) pairs.  Links
+    /// written in reference style are checked at their definitions instead.
+    fn collect_fragment_links<'b>(&self, node: &'b AstNode<'b>, links: &mut Vec<(usize, String)>) {
+        if let NodeValue::Link(link) = &node.data.borrow().value
+            && let Some(fragment) = link.url.strip_prefix('#')
+            && self.get_reference_style_info(node).is_none()
+        {
+            links.push((
+                node.data.borrow().sourcepos.start.line,
+                fragment.to_string(),
+            ));
+        }
+        for child in node.children() {
+            self.collect_fragment_links(child, links);
+        }
+    }
+
+    /// Collect reference definitions whose destination is a `#fragment` as
+    /// (line, fragment) pairs.
+    fn collect_fragment_definitions<'b>(&self, document: &'b AstNode<'b>) -> Vec<(usize, String)> {
+        self.collect_source_definitions(document)
+            .into_iter()
+            .filter_map(|def| Some((def.line, def.url.strip_prefix('#')?.to_string())))
+            .collect()
+    }
+
+    /// Collect the values of `id` and `name` attributes in raw HTML.
+    fn collect_html_ids<'b>(document: &'b AstNode<'b>) -> Vec<String> {
+        let id_pattern =
+            Regex::new(r#"(?i)\b(?:id|name)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+))"#)
+                .unwrap();
+        let mut ids = Vec::new();
+        for node in document.descendants() {
+            let value = &node.data.borrow().value;
+            let html = match value {
+                NodeValue::HtmlBlock(html_block) => html_block.literal.as_str(),
+                NodeValue::HtmlInline(html) => html.as_str(),
+                _ => continue,
+            };
+            for caps in id_pattern.captures_iter(html) {
+                if let Some(id) = caps.get(1).or(caps.get(2)).or(caps.get(3)) {
+                    ids.push(id.as_str().to_string());
+                }
+            }
+        }
+        ids
+    }
+
+    /// Collect the line ranges of tables of contents, from the start marker
+    /// to the end marker.
+    fn collect_toc_line_ranges<'b>(document: &'b AstNode<'b>) -> Vec<(usize, usize)> {
+        let children: Vec<_> = document.children().collect();
+        let mut ranges = Vec::new();
+        for (i, child) in children.iter().enumerate() {
+            if let NodeValue::HtmlBlock(html_block) = &child.data.borrow().value
+                && let Some(Directive::Toc { .. }) = Directive::parse(&html_block.literal)
+                && let Some(end) = Self::find_toc_end(&children, i)
+            {
+                let start_line = child.data.borrow().sourcepos.start.line;
+                let end_line = children[end].data.borrow().sourcepos.end.line;
+                ranges.push((start_line, end_line));
+            }
+        }
+        ranges
+    }
+}
+
+/// Decode `%XX` escapes in a URL component.  Invalid escapes are kept as is.
+pub(crate) fn percent_decode(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = fragment.get(i + 1..i + 3)
+            && hex.bytes().all(|b| b.is_ascii_hexdigit())
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| fragment.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::percent_decode;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("a%zzb"), "a%zzb");
+    }
+}
@@ -0,0 +1,213 @@
+//! Pandoc attribute blocks.
+//!
+//! Pandoc and Quarto let headings and fenced code blocks end with an
+//! attribute block such as `{#install .unnumbered}` or
+//! `{.python .numberLines startFrom="10"}`.  These are identifiers, not
+//! prose, so they are kept out of heading case conversion and written in
+//! a canonical form: the identifier first, then the classes, then the
+//! key-value pairs, separated by single spaces.
+
+use std::fmt;
+
+use comrak::nodes::{AstNode, NodeValue};
+
+/// A parsed attribute block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct Attributes<'s> {
+    /// The identifier, without its `#`.
+    pub id: Option<&'s str>,
+    /// The classes with their `.`, and the `-` shorthand for `.unnumbered`.
+    pub classes: Vec<&'s str>,
+    /// The key-value pairs as written, e.g., `startFrom="10"`.
+    pub pairs: Vec<&'s str>,
+}
+
+impl<'s> Attributes<'s> {
+    /// Parse the inside of an attribute block.  Returns `None` if it has
+    /// anything other than an identifier, classes, and key-value pairs, or
+    /// more than one identifier.
+    pub(super) fn parse(inner: &'s str) -> Option<Self> {
+        let mut attributes = Self::default();
+        for token in split_tokens(inner)? {
+            if let Some(id) = token.strip_prefix('#') {
+                if id.is_empty() || attributes.id.is_some() || !is_name(id) {
+                    return None;
+                }
+                attributes.id = Some(id);
+            } else if token == "-" || token.strip_prefix('.').is_some_and(is_name) {
+                attributes.classes.push(token);
+            } else if is_pair(token) {
+                attributes.pairs.push(token);
+            } else {
+                return None;
+            }
+        }
+        (attributes != Self::default()).then_some(attributes)
+    }
+
+    /// The first class, without its `.`.
+    pub(super) fn first_class(&self) -> Option<&'s str> {
+        self.classes
+            .iter()
+            .find_map(|class| class.strip_prefix('.'))
+    }
+}
+
+impl fmt::Display for Attributes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<String> = self
+            .id
+            .map(|id| format!("#{}", id))
+            .into_iter()
+            .chain(self.classes.iter().map(|class| class.to_string()))
+            .chain(self.pairs.iter().map(|pair| pair.to_string()))
+            .collect();
+        write!(f, "{{{}}}", tokens.join(" "))
+    }
+}
+
+/// Split an attribute block off the end of `text`, returning the text before
+/// it with trailing whitespace removed.
+pub(super) fn split_trailing(text: &str) -> Option<(&str, Attributes<'_>)> {
+    let text = text.trim_end();
+    let inner = text.strip_suffix('}')?;
+    let start = inner.rfind('{')?;
+    let attributes = Attributes::parse(&inner[start + 1..])?;
+    Some((text[..start].trim_end(), attributes))
+}
+
+/// If `node` is the text that ends a heading and `text` (its literal, or
+/// its source) ends with an attribute block, split the block off.  A
+/// heading that consists of an attribute block only has none.
+pub(super) fn split_heading_attributes<'t, 'b>(
+    node: &'b AstNode<'b>,
+    text: &'t str,
+) -> Option<(&'t str, Attributes<'t>)> {
+    let parent = node.parent()?;
+    if node.next_sibling().is_some() || !matches!(parent.data.borrow().value, NodeValue::Heading(_))
+    {
+        return None;
+    }
+    split_trailing(text)
+        .filter(|(before, _)| !before.is_empty() || node.previous_sibling().is_some())
+}
+
+/// The attribute block that ends a heading, in canonical form, if any.
+pub(super) fn heading_attributes<'b>(node: &'b AstNode<'b>) -> Option<String> {
+    map_heading_attributes(node, |attributes| attributes.to_string())
+}
+
+/// The identifier in the attribute block that ends a heading, if any.
+pub(super) fn heading_id<'b>(node: &'b AstNode<'b>) -> Option<String> {
+    map_heading_attributes(node, |attributes| attributes.id.map(str::to_string)).flatten()
+}
+
+/// Check if a heading is marked `.unnumbered`, or `-` for short.
+pub(super) fn is_unnumbered<'b>(node: &'b AstNode<'b>) -> bool {
+    map_heading_attributes(node, |attributes| {
+        attributes
+            .classes
+            .iter()
+            .any(|class| *class == "-" || *class == ".unnumbered")
+    })
+    .unwrap_or(false)
+}
+
+fn map_heading_attributes<'b, T>(
+    node: &'b AstNode<'b>,
+    f: impl FnOnce(&Attributes<'_>) -> T,
+) -> Option<T> {
+    let last = node.last_child()?;
+    match &last.data.borrow().value {
+        NodeValue::Text(text) => {
+            split_heading_attributes(last, text).map(|(_, attributes)| f(&attributes))
+        }
+        _ => None,
+    }
+}
+
+/// Split the inside of an attribute block into whitespace-separated tokens,
+/// keeping quoted values whole.  Returns `None` if a quote is never closed.
+fn split_tokens(inner: &str) -> Option<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    for (i, c) in inner.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c.is_whitespace() => {
+                if let Some(s) = start.take() {
+                    tokens.push(&inner[s..i]);
+                }
+            }
+            None => {
+                start.get_or_insert(i);
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+            }
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    if let Some(s) = start {
+        tokens.push(&inner[s..]);
+    }
+    Some(tokens)
+}
+
+/// Check if a string can be an identifier, a class, or a key.
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+}
+
+/// Check if a token is a key-value pair whose value is either quoted or
+/// a single word.
+fn is_pair(token: &str) -> bool {
+    let Some((key, value)) = token.split_once('=') else {
+        return false;
+    };
+    let quoted = |q: char| value.len() >= 2 && value.starts_with(q) && value.ends_with(q);
+    is_name(key) && (quoted('"') || quoted('\'') || !value.contains(['"', '\'', '{', '}', '=']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let attributes = Attributes::parse(" .python   #code  startFrom=\"1 0\" - ").unwrap();
+        assert_eq!(attributes.id, Some("code"));
+        assert_eq!(attributes.classes, vec![".python", "-"]);
+        assert_eq!(attributes.pairs, vec!["startFrom=\"1 0\""]);
+        assert_eq!(attributes.first_class(), Some("python"));
+        assert_eq!(
+            attributes.to_string(),
+            "{#code .python - startFrom=\"1 0\"}"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_non_attributes() {
+        assert_eq!(Attributes::parse("python"), None);
+        assert_eq!(Attributes::parse("=html"), None);
+        assert_eq!(Attributes::parse("#a #b"), None);
+        assert_eq!(Attributes::parse("key=\"open"), None);
+        assert_eq!(Attributes::parse(""), None);
+    }
+
+    #[test]
+    fn test_split_trailing() {
+        let (before, attributes) = split_trailing("Install {.unnumbered #install} ").unwrap();
+        assert_eq!(before, "Install");
+        assert_eq!(attributes.to_string(), "{#install .unnumbered}");
+        assert_eq!(split_trailing("Set {x}"), None);
+        assert_eq!(split_trailing("Install"), None);
+    }
+}
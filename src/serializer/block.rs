@@ -1,8 +1,11 @@
-//! Block quote and alert serialization logic.
+//! Block quote, alert, and container serialization logic.
 
-use comrak::nodes::{AlertType, AstNode};
+use comrak::nodes::{AlertType, AstNode, NodeValue};
 
 use super::Serializer;
+use crate::callout::{self, Header};
+use crate::container::{ALERT_CONTAINERS, alert_type, masked_fence_length, parse_fence};
+use crate::{CalloutTarget, CalloutTypeCase, ContainerStyle};
 
 /// Saved state for blockquote context, used for restoration after processing.
 struct BlockquoteState {
@@ -87,19 +90,35 @@ impl<'a> Serializer<'a> {
     }
 
     pub(super) fn serialize_alert<'b>(&mut self, node: &'b AstNode<'b>, alert_type: AlertType) {
+        let header = self.callout_header(node);
+        if let Some(header) = header
+            && self.options.extension_callout_target == CalloutTarget::Github
+            && !callout::is_github_type(header.kind)
+        {
+            let line = node.data.borrow().sourcepos.start.line;
+            self.add_warning(
+                line,
+                format!("callout type not supported by GitHub: [!{}]", header.kind),
+            );
+        }
+
+        // Top-level alerts may be written as containers instead, unless they
+        // have a title or fold marker that containers cannot carry
+        let is_top_level = node
+            .parent()
+            .is_some_and(|parent| matches!(parent.data.borrow().value, NodeValue::Document))
+            && !self.is_inside_container(node);
+        if is_top_level
+            && self.options.extension_containers
+            && self.options.extension_container_style == ContainerStyle::Container
+            && header.is_none_or(|header| header.is_github_alert() && header.title.is_empty())
+        {
+            self.serialize_alert_as_container(node, alert_type);
+            return;
+        }
+
         // Output the alert header
-        let type_str = match alert_type {
-            AlertType::Note => "NOTE",
-            AlertType::Tip => "TIP",
-            AlertType::Important => "IMPORTANT",
-            AlertType::Warning => "WARNING",
-            AlertType::Caution => "CAUTION",
-        };
-        self.output.push_str(&self.list_item_indent);
-        self.output.push_str(&self.blockquote_prefix);
-        self.output.push_str("> [!");
-        self.output.push_str(type_str);
-        self.output.push_str("]\n");
+        self.serialize_alert_header(alert_type, header);
 
         // Check if original source has a blank line after the alert header
         let children: Vec<_> = node.children().collect();
@@ -121,4 +140,140 @@ impl<'a> Serializer<'a> {
         self.serialize_blockquote_children(&children, &state);
         self.exit_blockquote_context(state);
     }
+
+    /// The callout header on the source line that opens an alert.
+    fn callout_header<'b>(&self, node: &'b AstNode<'b>) -> Option<Header<'a>> {
+        let line = node.data.borrow().sourcepos.start.line;
+        callout::parse_header(self.source_lines.get(line.checked_sub(1)?)?)
+    }
+
+    /// Output the `> [!TYPE]` line that opens an alert, with the fold marker
+    /// and title of its source `header`, if any.
+    fn serialize_alert_header(&mut self, alert_type: AlertType, header: Option<Header<'_>>) {
+        let type_str = match header {
+            Some(header) => header.kind,
+            None => match alert_type {
+                AlertType::Note => "NOTE",
+                AlertType::Tip => "TIP",
+                AlertType::Important => "IMPORTANT",
+                AlertType::Warning => "WARNING",
+                AlertType::Caution => "CAUTION",
+            },
+        };
+        let type_str = match self.options.extension_callout_type_case {
+            CalloutTypeCase::Upper => type_str.to_uppercase(),
+            CalloutTypeCase::Lower => type_str.to_lowercase(),
+            CalloutTypeCase::Preserve => type_str.to_string(),
+        };
+        self.output.push_str(&self.list_item_indent);
+        self.output.push_str(&self.blockquote_prefix);
+        self.output.push_str("> [!");
+        self.output.push_str(&type_str);
+        self.output.push(']');
+        if let Some(header) = header {
+            self.output.push_str(header.fold);
+            if !header.title.is_empty() {
+                self.output.push(' ');
+                self.output.push_str(header.title);
+            }
+        }
+        self.output.push('\n');
+    }
+
+    /// Output an alert as a container, e.g., `:::note` ... `:::`.
+    fn serialize_alert_as_container<'b>(&mut self, node: &'b AstNode<'b>, alert_type: AlertType) {
+        let name = ALERT_CONTAINERS
+            .iter()
+            .find(|(t, _)| *t == alert_type)
+            .map_or("note", |(_, name)| name);
+        self.output.push_str(":::");
+        self.output.push_str(name);
+        self.output.push('\n');
+        for child in node.children() {
+            self.output.push('\n');
+            self.serialize_node(child);
+        }
+        self.output.push_str("\n:::\n");
+    }
+
+    /// The info string of the container fence that `node` stands for, with
+    /// its leading whitespace.  It is empty for closing fences.
+    fn container_fence_info<'b>(&self, node: &'b AstNode<'b>) -> Option<&'a str> {
+        let NodeValue::HtmlBlock(html_block) = &node.data.borrow().value else {
+            return None;
+        };
+        masked_fence_length(&html_block.literal)?;
+        let line = node.data.borrow().sourcepos.start.line;
+        let source = self.source_lines.get(line.checked_sub(1)?)?;
+        parse_fence(source).map(|(_, info)| info)
+    }
+
+    /// Check if a node comes between the fences of a container.  The fences
+    /// are siblings of the blocks they contain, so the preceding siblings
+    /// tell whether one is still open.
+    fn is_inside_container<'b>(&self, node: &'b AstNode<'b>) -> bool {
+        let mut closed = 0usize;
+        for sibling in node.preceding_siblings().skip(1) {
+            match self.container_fence_info(sibling) {
+                Some("") => closed += 1,
+                Some(_) if closed == 0 => return true,
+                Some(_) => closed -= 1,
+                None => {}
+            }
+        }
+        false
+    }
+
+    /// Output a container fence with its canonical number of colons.
+    pub(super) fn serialize_container_fence<'b>(&mut self, node: &'b AstNode<'b>, literal: &str) {
+        let length = masked_fence_length(literal).unwrap_or(3);
+        let info = self.container_fence_info(node).unwrap_or_default();
+        // In list items, the list adds the prefix
+        if self.in_block_quote && self.list_type.is_none() {
+            self.output.push_str(&self.blockquote_outer_indent);
+            self.output.push_str(&self.blockquote_prefix);
+        }
+        self.output.push_str(&":".repeat(length));
+        self.output.push_str(info);
+        self.output.push('\n');
+    }
+
+    /// If `children[start]` opens a top-level container that is to be written
+    /// as a GitHub alert, output it and its contents as one and return the
+    /// index of its closing fence.
+    pub(super) fn serialize_container_as_alert<'b>(
+        &mut self,
+        children: &[&'b AstNode<'b>],
+        start: usize,
+    ) -> Option<usize> {
+        if self.options.extension_container_style != ContainerStyle::Alert {
+            return None;
+        }
+        let alert_type = alert_type(self.container_fence_info(children[start])?)?;
+        if self.is_inside_container(children[start]) {
+            return None;
+        }
+
+        // Find the matching closing fence
+        let mut depth = 0usize;
+        let mut end = None;
+        for (i, child) in children.iter().enumerate().skip(start + 1) {
+            match self.container_fence_info(child) {
+                Some("") if depth == 0 => {
+                    end = Some(i);
+                    break;
+                }
+                Some("") => depth -= 1,
+                Some(_) => depth += 1,
+                None => {}
+            }
+        }
+        let end = end?;
+
+        self.serialize_alert_header(alert_type, None);
+        let state = self.enter_blockquote_context();
+        self.serialize_blockquote_children(&children[start + 1..end], &state);
+        self.exit_blockquote_context(state);
+        Some(end)
+    }
 }
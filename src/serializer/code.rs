@@ -1,8 +1,11 @@
 //! Code block serialization logic.
 
+use std::borrow::Cow;
+
 use comrak::nodes::NodeCodeBlock;
 
 use super::Serializer;
+use super::attribute;
 
 /// The keyword to skip code formatting for a code block.
 const NO_FORMAT_KEYWORD: &str = "hongdown-no-format";
@@ -11,15 +14,18 @@ const NO_FORMAT_KEYWORD: &str = "hongdown-no-format";
 ///
 /// The info string can contain a language identifier followed by optional
 /// metadata. If `hongdown-no-format` is present, formatting will be skipped.
+/// A trailing Pandoc attribute block such as `{.python .numberLines}` is
+/// written in canonical form, and its first class is the language unless
+/// the info string starts with one.
 ///
 /// Returns `(language, full_info_for_output, skip_format)`:
 /// - `language`: The language identifier for formatter lookup (without metadata)
 /// - `full_info_for_output`: The full info string to output (preserves hongdown-no-format)
 /// - `skip_format`: Whether to skip formatting
-fn parse_code_info(info: &str) -> (&str, &str, bool) {
+fn parse_code_info(info: &str) -> (&str, Cow<'_, str>, bool) {
     let trimmed = info.trim();
     if trimmed.is_empty() {
-        return ("", "", false);
+        return ("", Cow::Borrowed(""), false);
     }
 
     // Check if hongdown-no-format is present
@@ -27,11 +33,24 @@ fn parse_code_info(info: &str) -> (&str, &str, bool) {
         .split_whitespace()
         .any(|word| word == NO_FORMAT_KEYWORD);
 
+    if let Some((before, attributes)) = attribute::split_trailing(trimmed) {
+        let language = match before.split_whitespace().next() {
+            Some(language) => language,
+            None => attributes.first_class().unwrap_or(""),
+        };
+        let output = if before.is_empty() {
+            attributes.to_string()
+        } else {
+            format!("{} {}", before, attributes)
+        };
+        return (language, Cow::Owned(output), has_no_format);
+    }
+
     // Extract just the language (first word)
     let language = trimmed.split_whitespace().next().unwrap_or("");
 
     // Return the full info for output (to preserve hongdown-no-format)
-    (language, trimmed, has_no_format)
+    (language, Cow::Borrowed(trimmed), has_no_format)
 }
 
 impl<'a> Serializer<'a> {
@@ -96,7 +115,7 @@ impl<'a> Serializer<'a> {
         let output_info = if info_output.is_empty() && !self.options.default_language.is_empty() {
             self.options.default_language.as_str()
         } else {
-            info_output
+            &info_output
         };
 
         // Try to format the code if a formatter is configured and not skipped
@@ -156,7 +175,7 @@ impl<'a> Serializer<'a> {
         let output_info = if info_output.is_empty() && !self.options.default_language.is_empty() {
             self.options.default_language.as_str()
         } else {
-            info_output
+            &info_output
         };
 
         // Try to format the code if a formatter is configured and not skipped
@@ -245,7 +264,7 @@ impl<'a> Serializer<'a> {
         let output_info = if info_output.is_empty() && !self.options.default_language.is_empty() {
             self.options.default_language.as_str()
         } else {
-            info_output
+            &info_output
         };
 
         // Try to format the code if a formatter is configured and not skipped
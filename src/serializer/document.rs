@@ -5,16 +5,32 @@ use regex::Regex;
 use unicode_width::UnicodeWidthStr;
 
 use super::Serializer;
+use super::front_matter;
 use super::state::{Directive, FormatSkipMode};
+use super::toc::TOC_END_MARKER;
 use super::wrap;
+use crate::front_matter::FrontMatterKind;
+use crate::{HeadingCase, ReferencePlacement};
 
 impl<'a> Serializer<'a> {
     pub(super) fn serialize_document<'b>(&mut self, node: &'b AstNode<'b>) {
         let children: Vec<_> = node.children().collect();
 
+        // Shift heading levels and close gaps before anything looks at them
+        self.normalize_heading_levels(node);
+        self.heading_numbers = self.number_headings(node);
+
         // Check for undefined reference links using AST
         self.check_undefined_references_ast(node);
 
+        // Check for links to anchors that no heading defines
+        self.check_anchor_links(node);
+
+        // Check for unused, duplicate, and conflicting reference definitions
+        self.analyze_reference_definitions(node);
+        self.reserve_source_labels(node);
+        self.renumber_references(node);
+
         // First pass: collect all footnote reference lines
         // This is needed because FootnoteDefinition nodes come at the end of the AST,
         // but we need to know reference lines before flushing at section boundaries
@@ -28,11 +44,31 @@ impl<'a> Serializer<'a> {
             }
         }
 
+        // JSON front matter is hidden from the parser, so it has no node of
+        // its own; emit it verbatim before everything else
+        if let Some(front_matter) = &self.front_matter
+            && front_matter.kind == FrontMatterKind::Json
+        {
+            self.output.push_str(front_matter.content);
+            self.output.push_str("\n\n");
+        }
+
         // Identify trailing HTML blocks (non-directive comments at the end of document)
         // These should be output after reference definitions to maintain their position
         let trailing_html_start = self.find_trailing_html_blocks(&children);
 
+        // Index of the end marker of the table of contents being regenerated;
+        // the old contents up to it are dropped
+        let mut toc_end: Option<usize> = None;
+
+        // Index of the closing fence of the container being written as an
+        // alert; the contents up to it are written by then
+        let mut container_end: Option<usize> = None;
+
         for (i, child) in children.iter().enumerate() {
+            if toc_end.is_some_and(|end| i <= end) || container_end.is_some_and(|end| i <= end) {
+                continue;
+            }
             // Skip trailing HTML blocks for now - they'll be output after references
             if i >= trailing_html_start
                 && let NodeValue::HtmlBlock(_) = &child.data.borrow().value
@@ -43,6 +79,21 @@ impl<'a> Serializer<'a> {
             if let NodeValue::FootnoteDefinition(_) = &child.data.borrow().value {
                 continue;
             }
+            // A table of contents directive with invalid arguments stays an
+            // ordinary comment, which should not go unnoticed
+            if let NodeValue::HtmlBlock(html_block) = &child.data.borrow().value
+                && self.skip_mode == FormatSkipMode::None
+                && let Some(error) = Directive::toc_args_error(&html_block.literal)
+            {
+                let line = child.data.borrow().sourcepos.start.line;
+                self.add_warning(
+                    line,
+                    format!(
+                        "invalid table of contents directive: {}; leaving it as a comment",
+                        error
+                    ),
+                );
+            }
             // Check for directives in HTML blocks
             if let NodeValue::HtmlBlock(html_block) = &child.data.borrow().value
                 && let Some(directive) = Directive::parse(&html_block.literal)
@@ -52,9 +103,7 @@ impl<'a> Serializer<'a> {
                         // Flush pending footnotes and references BEFORE the disable-file directive.
                         // Definitions that appear before the directive should stay before it.
                         let directive_line = child.data.borrow().sourcepos.start.line;
-                        self.flush_footnotes_before(Some(directive_line));
-                        self.flush_references();
-                        self.flush_footnote_references_before(Some(directive_line));
+                        self.flush_definitions_before(Some(directive_line));
 
                         // Output the directive comment, then output remaining content as-is
                         self.output.push_str(html_block.literal.trim_end());
@@ -72,10 +121,10 @@ impl<'a> Serializer<'a> {
                     Directive::DisableNextLine => {
                         // Flush pending footnotes and references BEFORE the directive.
                         // Definitions that appear before the directive should stay before it.
-                        let directive_line = child.data.borrow().sourcepos.start.line;
-                        self.flush_footnotes_before(Some(directive_line));
-                        self.flush_references();
-                        self.flush_footnote_references_before(Some(directive_line));
+                        if self.options.link_reference_placement != ReferencePlacement::Document {
+                            let directive_line = child.data.borrow().sourcepos.start.line;
+                            self.flush_definitions_before(Some(directive_line));
+                        }
 
                         self.skip_mode = FormatSkipMode::NextBlock;
                         // Output the directive comment
@@ -88,10 +137,10 @@ impl<'a> Serializer<'a> {
                     Directive::DisableNextSection => {
                         // Flush pending footnotes and references BEFORE the directive.
                         // Definitions that appear before the directive should stay before it.
-                        let directive_line = child.data.borrow().sourcepos.start.line;
-                        self.flush_footnotes_before(Some(directive_line));
-                        self.flush_references();
-                        self.flush_footnote_references_before(Some(directive_line));
+                        if self.options.link_reference_placement != ReferencePlacement::Document {
+                            let directive_line = child.data.borrow().sourcepos.start.line;
+                            self.flush_definitions_before(Some(directive_line));
+                        }
 
                         self.skip_mode = FormatSkipMode::UntilSection;
                         // Output the directive comment
@@ -104,10 +153,10 @@ impl<'a> Serializer<'a> {
                     Directive::Disable => {
                         // Flush pending footnotes and references BEFORE the disable directive.
                         // Definitions that appear before the directive should stay before it.
-                        let directive_line = child.data.borrow().sourcepos.start.line;
-                        self.flush_footnotes_before(Some(directive_line));
-                        self.flush_references();
-                        self.flush_footnote_references_before(Some(directive_line));
+                        if self.options.link_reference_placement != ReferencePlacement::Document {
+                            let directive_line = child.data.borrow().sourcepos.start.line;
+                            self.flush_definitions_before(Some(directive_line));
+                        }
 
                         self.skip_mode = FormatSkipMode::Disabled;
                         // Output the directive comment
@@ -129,6 +178,7 @@ impl<'a> Serializer<'a> {
                     Directive::ProperNouns(nouns) => {
                         // Add to directive proper nouns list
                         self.directive_proper_nouns.extend(nouns);
+                        self.proper_noun_index = None;
                         // Output the directive comment
                         if i > 0 {
                             self.output.push('\n');
@@ -139,6 +189,47 @@ impl<'a> Serializer<'a> {
                     Directive::CommonNouns(nouns) => {
                         // Add to directive common nouns list
                         self.directive_common_nouns.extend(nouns);
+                        self.proper_noun_index = None;
+                        // Output the directive comment
+                        if i > 0 {
+                            self.output.push('\n');
+                        }
+                        self.output.push_str(&html_block.literal);
+                        continue;
+                    }
+                    Directive::Toc {
+                        min_level,
+                        max_level,
+                    } if self.skip_mode == FormatSkipMode::None => {
+                        if i > 0 {
+                            self.output.push('\n');
+                        }
+                        self.output.push_str(&html_block.literal);
+                        let end = Self::find_toc_end(&children, i);
+                        if end.is_none() && Self::has_misplaced_toc_end(&children, i) {
+                            // Replacing up to a heading would leave the end
+                            // marker behind, so keep the region as it is
+                            let line = child.data.borrow().sourcepos.start.line;
+                            self.add_warning(
+                                line,
+                                "table of contents end marker comes after a heading; \
+                                 leaving the table of contents alone"
+                                    .to_string(),
+                            );
+                            continue;
+                        }
+                        self.output.push('\n');
+                        let toc = self.generate_toc(node, min_level, max_level);
+                        if !toc.is_empty() {
+                            self.output.push_str(&toc);
+                            self.output.push('\n');
+                        }
+                        self.output.push_str(TOC_END_MARKER);
+                        self.output.push('\n');
+                        toc_end = end;
+                        continue;
+                    }
+                    Directive::Toc { .. } | Directive::TocEnd => {
                         // Output the directive comment
                         if i > 0 {
                             self.output.push('\n');
@@ -149,22 +240,24 @@ impl<'a> Serializer<'a> {
                 }
             }
 
-            // Check if we're about to start a new section (h2 or h3 heading)
+            // Check if we're about to start a new section (h2 or h3 heading,
+            // or only h2 depending on the configured placement)
             // If so, flush any pending references and footnotes first
             let heading_level = match &child.data.borrow().value {
                 NodeValue::Heading(h) => Some(h.level),
                 _ => None,
             };
             let is_h2 = heading_level == Some(2);
-            let is_h2_or_h3 = matches!(heading_level, Some(2) | Some(3));
+            let is_section_start = match self.options.link_reference_placement {
+                ReferencePlacement::Section => matches!(heading_level, Some(2) | Some(3)),
+                ReferencePlacement::SectionH2 => is_h2,
+                ReferencePlacement::Document | ReferencePlacement::Block => false,
+            };
 
-            if is_h2_or_h3 && i > 0 {
+            if is_section_start && i > 0 {
                 // Get the source line of the heading to flush only earlier footnotes
                 let heading_line = child.data.borrow().sourcepos.start.line;
-                // Footnotes come before link reference definitions
-                self.flush_footnotes_before(Some(heading_line));
-                self.flush_references();
-                self.flush_footnote_references_before(Some(heading_line));
+                self.flush_definitions_before(Some(heading_line));
             }
 
             // Add blank line between block elements (except after front matter)
@@ -175,6 +268,8 @@ impl<'a> Serializer<'a> {
                 );
                 if prev_is_front_matter {
                     // No extra blank line needed after front matter
+                } else if self.is_attached_to_template_tags(children[i - 1], child) {
+                    // Template tags stay attached to the blocks next to them
                 } else if is_h2 {
                     // Check if previous element was a heading (empty section)
                     let prev_is_heading =
@@ -220,18 +315,63 @@ impl<'a> Serializer<'a> {
                 continue;
             }
 
-            self.serialize_node(child);
+            let last = match self.serialize_container_as_alert(&children, i) {
+                Some(end) => {
+                    container_end = Some(end);
+                    children[end]
+                }
+                None => {
+                    self.serialize_node(child);
+                    child
+                }
+            };
+
+            // Place the definitions used by this block right after it
+            if self.options.link_reference_placement == ReferencePlacement::Block {
+                let next_line = last.data.borrow().sourcepos.end.line + 1;
+                self.flush_definitions_before(Some(next_line));
+            }
         }
 
-        // Footnotes come before link reference definitions
-        self.flush_footnotes();
-        self.flush_references();
-        self.flush_footnote_references();
+        self.flush_definitions_before(None);
 
         // Output trailing HTML blocks after references and footnotes
         self.output_trailing_html_blocks(&children, trailing_html_start);
     }
 
+    /// Find the end marker of the table of contents that starts at `start`.
+    /// Returns `None` if there is no end marker before the next heading, in
+    /// which case nothing after the start marker is replaced.
+    pub(super) fn find_toc_end<'b>(children: &[&'b AstNode<'b>], start: usize) -> Option<usize> {
+        for (i, child) in children.iter().enumerate().skip(start + 1) {
+            match &child.data.borrow().value {
+                NodeValue::HtmlBlock(html_block)
+                    if Directive::parse(&html_block.literal) == Some(Directive::TocEnd) =>
+                {
+                    return Some(i);
+                }
+                NodeValue::Heading(_) => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Check if the table of contents that starts at `start` has an end
+    /// marker after the next heading, before any other start marker.
+    fn has_misplaced_toc_end<'b>(children: &[&'b AstNode<'b>], start: usize) -> bool {
+        for child in &children[start + 1..] {
+            if let NodeValue::HtmlBlock(html_block) = &child.data.borrow().value {
+                match Directive::parse(&html_block.literal) {
+                    Some(Directive::TocEnd) => return true,
+                    Some(Directive::Toc { .. }) => return false,
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+
     /// Find the index where trailing HTML blocks start.
     /// Returns `children.len()` if there are no trailing HTML blocks.
     fn find_trailing_html_blocks<'b>(&self, children: &[&'b AstNode<'b>]) -> usize {
@@ -242,8 +382,13 @@ impl<'a> Serializer<'a> {
         for (i, child) in children.iter().enumerate().rev() {
             match &child.data.borrow().value {
                 NodeValue::HtmlBlock(html_block) => {
-                    // Skip formatting directives - they should stay where they are
-                    if Directive::parse(&html_block.literal).is_some() {
+                    // Skip formatting directives, MDX blocks, template tags,
+                    // and container fences - they should stay where they are
+                    if Directive::parse(&html_block.literal).is_some()
+                        || crate::mdx::is_masked_block(&html_block.literal)
+                        || crate::template::is_masked_block(&html_block.literal)
+                        || crate::container::masked_fence_length(&html_block.literal).is_some()
+                    {
                         break;
                     }
                     // This is a regular HTML block (e.g., comment) - mark as trailing
@@ -323,6 +468,7 @@ impl<'a> Serializer<'a> {
                             "",
                             &continuation,
                             self.options.line_width.get(),
+                            &self.options.template_delimiters,
                         );
                         self.output.push_str(&wrapped);
                         self.output.push('\n');
@@ -381,6 +527,7 @@ impl<'a> Serializer<'a> {
                             "",
                             &continuation,
                             self.options.line_width.get(),
+                            &self.options.template_delimiters,
                         );
                         self.output.push_str(&wrapped);
                         self.output.push('\n');
@@ -420,29 +567,32 @@ impl<'a> Serializer<'a> {
 
     pub(super) fn serialize_heading<'b>(&mut self, node: &'b AstNode<'b>, level: u8) {
         // Collect heading text first
-        let mut heading_text = self.collect_text(node);
-
-        // Apply sentence case if enabled
-        if self.options.heading_sentence_case {
-            // Merge config proper nouns with directive proper nouns
-            let mut proper_nouns = self.options.heading_proper_nouns.clone();
-            proper_nouns.extend(self.directive_proper_nouns.clone());
-
-            // Merge config common nouns with directive common nouns
-            let mut common_nouns = self.options.heading_common_nouns.clone();
-            common_nouns.extend(self.directive_common_nouns.clone());
-
-            heading_text =
-                super::heading::to_sentence_case(&heading_text, &proper_nouns, &common_nouns);
+        let mut heading_text = self.collect_with_case_conversion(
+            |this| this.collect_text(node),
+            |this, text, masks| {
+                this.report_sentence_case(node, text, masks);
+                this.format_heading_text(node, text)
+            },
+        );
+        if let Some(attributes) = super::attribute::heading_attributes(node) {
+            heading_text.push(' ');
+            heading_text.push_str(&attributes);
         }
 
-        if level == 1 && self.options.setext_h1 {
+        // Setext underlines would need the prefixes of block quotes and list
+        // items on their own lines, so headings in them are always ATX-style
+        let nested = self.in_block_quote || self.list_type.is_some();
+        if self.in_block_quote && self.list_type.is_none() {
+            self.output.push_str(&self.blockquote_outer_indent);
+            self.output.push_str(&self.blockquote_prefix);
+        }
+        if level == 1 && self.options.setext_h1 && !nested {
             // Setext-style with '='
             self.output.push_str(&heading_text);
             self.output.push('\n');
             self.output.push_str(&"=".repeat(heading_text.width()));
             self.output.push('\n');
-        } else if level == 2 && self.options.setext_h2 {
+        } else if level == 2 && self.options.setext_h2 && !nested {
             // Setext-style with '-'
             self.output.push_str(&heading_text);
             self.output.push('\n');
@@ -453,8 +603,106 @@ impl<'a> Serializer<'a> {
             self.output.push_str(&"#".repeat(level as usize));
             self.output.push(' ');
             self.output.push_str(&heading_text);
-            self.output.push('\n');
+            // Like paragraphs, the list item ends the line itself
+            if self.list_type.is_none() {
+                self.output.push('\n');
+            }
+        }
+    }
+
+    /// The case headings are converted to.
+    fn heading_case(&self) -> HeadingCase {
+        // `heading_sentence_case` is a shorthand for `HeadingCase::Sentence`
+        match self.options.heading_case {
+            HeadingCase::Preserve if self.options.heading_sentence_case => HeadingCase::Sentence,
+            case => case,
+        }
+    }
+
+    /// The proper nouns and common nouns that case conversion follows.
+    pub(super) fn heading_nouns(&self) -> (Vec<String>, Vec<String>) {
+        // Merge config proper nouns with directive proper nouns
+        let mut proper_nouns = self.options.heading_proper_nouns.clone();
+        proper_nouns.extend(self.directive_proper_nouns.clone());
+
+        // Merge config common nouns with directive common nouns
+        let mut common_nouns = self.options.heading_common_nouns.clone();
+        common_nouns.extend(self.directive_common_nouns.clone());
+
+        // Built-in proper nouns of disabled categories are treated as common
+        // nouns, unless they are also given as proper nouns
+        common_nouns.extend(
+            super::heading::builtin_proper_nouns_in(&self.options.heading_disabled_categories)
+                .filter(|noun| {
+                    let noun = noun.to_lowercase();
+                    !proper_nouns.iter().any(|p| p.to_lowercase() == noun)
+                })
+                .map(str::to_string),
+        );
+
+        (proper_nouns, common_nouns)
+    }
+
+    /// Apply the configured case to heading text.
+    pub(super) fn apply_heading_case(&self, heading_text: &str) -> String {
+        let case = self.heading_case();
+        if case == HeadingCase::Preserve {
+            return heading_text.to_string();
+        }
+        let (proper_nouns, common_nouns) = self.heading_nouns();
+        match case {
+            HeadingCase::Title => super::heading::to_title_case(
+                heading_text,
+                self.options.heading_title_case_style,
+                &proper_nouns,
+                &common_nouns,
+            ),
+            _ => super::heading::to_sentence_case(heading_text, &proper_nouns, &common_nouns),
+        }
+    }
+
+    /// Convert text other than a heading, such as a table header cell, to
+    /// sentence case.
+    pub(super) fn apply_sentence_case(&self, text: &str) -> String {
+        let (proper_nouns, common_nouns) = self.heading_nouns();
+        super::heading::to_sentence_case(text, &proper_nouns, &common_nouns)
+    }
+
+    /// In sentence case report mode, warn about a heading that sentence case
+    /// would change, naming the words it would lowercase, and remember those
+    /// words for the summary.
+    fn report_sentence_case<'b>(
+        &mut self,
+        node: &'b AstNode<'b>,
+        heading_text: &str,
+        masks: &[String],
+    ) {
+        if !self.options.heading_sentence_case_report
+            || self.heading_case() != HeadingCase::Preserve
+        {
+            return;
+        }
+        let (proper_nouns, common_nouns) = self.heading_nouns();
+        let converted =
+            super::heading::to_sentence_case(heading_text, &proper_nouns, &common_nouns);
+        if converted == heading_text {
+            return;
+        }
+        let words = super::heading::lowercased_words(heading_text, &converted);
+        let mut message = format!(
+            "sentence case would change heading to \"{}\"",
+            super::inline::unmask_case(&converted, masks)
+        );
+        if !words.is_empty() {
+            message.push_str(&format!("; lowercased words: {}", words.join(", ")));
         }
+        let line = node.data.borrow().sourcepos.start.line;
+        self.add_warning(line, message);
+        self.unknown_words.extend(
+            words
+                .into_iter()
+                .filter(|word| !super::heading::is_common_word(word)),
+        );
     }
 
     pub(super) fn serialize_paragraph<'b>(&mut self, node: &'b AstNode<'b>) {
@@ -469,9 +717,26 @@ impl<'a> Serializer<'a> {
             }
         }
 
+        // A paragraph of display math only is written as a math block
+        let display_math = self.display_math_lines(node);
+
         // Collect all inline content first
         let mut inline_content = String::new();
-        self.collect_inline_content(node, &mut inline_content);
+        let is_term = node
+            .parent()
+            .is_some_and(|parent| matches!(parent.data.borrow().value, NodeValue::DescriptionTerm));
+        if display_math.is_none() && is_term && self.options.heading_sentence_case_terms {
+            inline_content = self.collect_with_case_conversion(
+                |this| {
+                    let mut content = String::new();
+                    this.collect_inline_content(node, &mut content);
+                    content
+                },
+                |this, text, _| this.apply_sentence_case(text),
+            );
+        } else if display_math.is_none() {
+            self.collect_inline_content(node, &mut inline_content);
+        }
 
         if self.list_type.is_some() {
             // Inside a list item, wrap with proper continuation indent
@@ -511,12 +776,16 @@ impl<'a> Serializer<'a> {
             } else {
                 base_indent
             };
-            let wrapped = wrap::wrap_text_first_line(
-                inline_content.trim(),
-                "",
-                &continuation,
-                self.options.line_width.get(),
-            );
+            let wrapped = match display_math {
+                Some(lines) => lines.join(&format!("\n{}", continuation)),
+                None => wrap::wrap_text_first_line(
+                    inline_content.trim(),
+                    "",
+                    &continuation,
+                    self.options.line_width.get(),
+                    &self.options.template_delimiters,
+                ),
+            };
             self.output.push_str(&wrapped);
         } else {
             // Not in a list - wrap the paragraph at line_width
@@ -525,13 +794,52 @@ impl<'a> Serializer<'a> {
             } else {
                 String::new()
             };
-            let wrapped = wrap::wrap_text(&inline_content, &prefix, self.options.line_width.get());
+            let wrapped = match display_math {
+                Some(lines) => lines
+                    .iter()
+                    .map(|line| format!("{}{}", prefix, line))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => wrap::wrap_text(
+                    &inline_content,
+                    &prefix,
+                    self.options.line_width.get(),
+                    &self.options.template_delimiters,
+                ),
+            };
             self.output.push_str(&wrapped);
             self.output.push('\n');
         }
     }
 
     pub(super) fn serialize_front_matter(&mut self, content: &str) {
+        if self.options.front_matter_canonicalize
+            && let Some(front_matter) = self.front_matter.clone()
+            && let Some(delimiter) = front_matter.kind.delimiter()
+        {
+            let style = front_matter::Style {
+                indent_width: self.options.front_matter_indent_width.get(),
+                quote_style: self.options.front_matter_quote_style,
+                key_order: &self.options.front_matter_key_order,
+            };
+            match front_matter::canonicalize(front_matter.kind, front_matter.content, &style) {
+                Ok(canonical) => {
+                    self.output.push_str(delimiter);
+                    self.output.push('\n');
+                    self.output.push_str(canonical.trim_matches('\n'));
+                    if !canonical.trim_matches('\n').is_empty() {
+                        self.output.push('\n');
+                    }
+                    self.output.push_str(delimiter);
+                    self.output.push_str("\n\n");
+                    return;
+                }
+                Err(message) => {
+                    self.add_warning(1, format!("front matter left untouched: {}", message));
+                }
+            }
+        }
+
         // Front matter content from comrak includes the delimiters,
         // so we preserve it verbatim and add a trailing blank line
         self.output.push_str(content.trim());
@@ -595,7 +903,7 @@ impl<'a> Serializer<'a> {
     /// formatting directives (hongdown-disable, hongdown-disable-next-line, etc.).
     ///
     /// Returns a vector of (start_line, end_line) tuples representing disabled ranges.
-    fn collect_disabled_line_ranges<'b>(node: &'b AstNode<'b>) -> Vec<(usize, usize)> {
+    pub(super) fn collect_disabled_line_ranges<'b>(node: &'b AstNode<'b>) -> Vec<(usize, usize)> {
         let mut ranges = Vec::new();
         let children: Vec<_> = node.children().collect();
 
@@ -660,7 +968,10 @@ impl<'a> Serializer<'a> {
                     Directive::Enable => {
                         // Enable doesn't start a new range, it ends one
                     }
-                    Directive::ProperNouns(_) | Directive::CommonNouns(_) => {
+                    Directive::ProperNouns(_)
+                    | Directive::CommonNouns(_)
+                    | Directive::Toc { .. }
+                    | Directive::TocEnd => {
                         // These directives don't affect warning ranges
                     }
                 }
@@ -671,7 +982,7 @@ impl<'a> Serializer<'a> {
     }
 
     /// Check if a line number falls within any of the disabled ranges.
-    fn is_line_in_disabled_ranges(line: usize, ranges: &[(usize, usize)]) -> bool {
+    pub(super) fn is_line_in_disabled_ranges(line: usize, ranges: &[(usize, usize)]) -> bool {
         ranges
             .iter()
             .any(|(start, end)| line >= *start && line <= *end)
@@ -790,6 +1101,13 @@ impl<'a> Serializer<'a> {
                         continue;
                     }
 
+                    // Skip wikilink embeds ![[file]]
+                    if text[..full_match.start()].ends_with("![")
+                        && text[full_match.end()..].starts_with(']')
+                    {
+                        continue;
+                    }
+
                     // Skip PHP Markdown Extra abbreviations
                     if abbreviations.contains(label) {
                         continue;
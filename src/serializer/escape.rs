@@ -7,12 +7,27 @@ pub fn normalize_whitespace(text: &str) -> String {
 
 /// Escape special Markdown characters in text content.
 /// Characters that could be misinterpreted as Markdown syntax need escaping.
+///
+/// Math spans (see [`math_span_end`]) are kept verbatim, since TeX relies on
+/// the backslashes and underscores that escaping would add.
 pub fn escape_text(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
     let chars: Vec<char> = text.chars().collect();
+    let mut math_end = None;
 
     for (i, &ch) in chars.iter().enumerate() {
+        if let Some(end) = math_end {
+            result.push(ch);
+            if i == end {
+                math_end = None;
+            }
+            continue;
+        }
         match ch {
+            '$' if math_span_end(&chars, i).is_some() => {
+                math_end = math_span_end(&chars, i);
+                result.push(ch);
+            }
             // Asterisk always needs escaping (can create emphasis anywhere)
             '*' => {
                 result.push('\\');
@@ -70,6 +85,34 @@ pub fn escape_text(text: &str) -> String {
     result
 }
 
+/// Find the closing delimiter of a math span starting at `start`.
+///
+/// A span is either `$$...$$`, or `$...$` where the opening `$` is followed
+/// by a non-space character and the closing `$` is preceded by a non-space
+/// character and not followed by a digit, as Pandoc requires.  The latter
+/// rule keeps prices like `$5 and $10` from being taken for math.
+///
+/// Returns the index of the last character of the closing delimiter.
+pub fn math_span_end(chars: &[char], start: usize) -> Option<usize> {
+    if chars.get(start) != Some(&'$') {
+        return None;
+    }
+    if chars.get(start + 1) == Some(&'$') {
+        return (start + 3..chars.len().saturating_sub(1))
+            .find(|&j| chars[j] == '$' && chars[j + 1] == '$')
+            .map(|j| j + 1);
+    }
+    if chars.get(start + 1).is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    (start + 2..chars.len()).find(|&j| {
+        chars[j] == '$'
+            && !chars[j - 1].is_whitespace()
+            && chars[j - 1] != '\\'
+            && !chars.get(j + 1).is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
 /// Format a code span with the appropriate number of backticks.
 /// According to CommonMark spec, if the content contains N consecutive backticks,
 /// the delimiter must use at least N+1 backticks. Spaces are added if the content
@@ -185,4 +228,24 @@ mod tests {
         assert!(!is_valid_code_span("foo"));
         assert!(!is_valid_code_span("foo`"));
     }
+
+    #[test]
+    fn test_math_span_end() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(math_span_end(&chars("$x_1$ and"), 0), Some(4));
+        assert_eq!(math_span_end(&chars("$$ a + b $$"), 0), Some(10));
+        assert_eq!(math_span_end(&chars("$5 and $10"), 0), None);
+        assert_eq!(math_span_end(&chars("$ x$"), 0), None);
+        assert_eq!(math_span_end(&chars("$x \\$ y$"), 0), Some(7));
+        assert_eq!(math_span_end(&chars("$$"), 0), None);
+    }
+
+    #[test]
+    fn test_escape_text_math_span() {
+        assert_eq!(
+            escape_text("a_b $x_1 * \\alpha$ c_d"),
+            "a\\_b $x_1 * \\alpha$ c\\_d"
+        );
+        assert_eq!(escape_text("$5 and $10_000"), "$5 and $10\\_000");
+    }
 }
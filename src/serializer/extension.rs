@@ -0,0 +1,138 @@
+//! Serialization of nodes from the optional syntax extensions.
+
+use comrak::nodes::{AstNode, NodeMath, NodeShortCode, NodeValue};
+
+use super::Serializer;
+use crate::MathDelimiters;
+
+/// The canonical delimiter of an inline extension node that wraps its
+/// children, such as `~~` for strikethrough.
+pub(super) fn inline_delimiter(value: &NodeValue) -> &'static str {
+    match value {
+        NodeValue::Strikethrough => "~~",
+        NodeValue::Superscript => "^",
+        NodeValue::Subscript => "~",
+        NodeValue::Underline => "__",
+        NodeValue::SpoileredText => "||",
+        _ => "",
+    }
+}
+
+/// Format a math span with `$` (inline) or `$$` (display) delimiters.  The
+/// contents are kept verbatim.
+pub(super) fn format_math(math: &NodeMath) -> String {
+    let delimiter = if math.display_math { "$$" } else { "$" };
+    format!("{}{}{}", delimiter, math.literal, delimiter)
+}
+
+/// Format an emoji shortcode as `:code:`.
+pub(super) fn format_shortcode(shortcode: &NodeShortCode) -> String {
+    format!(":{}:", shortcode.code)
+}
+
+impl<'a> Serializer<'a> {
+    /// Check if a link is a bare URL, `www.` link, or email address that
+    /// the autolink extension recognized.  Such links are written back as
+    /// they are.
+    pub(super) fn is_extended_autolink<'b>(&self, node: &'b AstNode<'b>) -> bool {
+        self.options.extension_autolink
+            && self
+                .extract_source(node)
+                .is_some_and(|source| !source.starts_with(['[', '<']))
+    }
+
+    /// Format a wikilink or an embed as it was written, e.g.,
+    /// `[[Page#Heading|alias]]` or `![[image.png]]` (see [`crate::wikilink`]).
+    /// It is never converted to a reference link.
+    pub(super) fn format_wikilink<'b>(&self, node: &'b AstNode<'b>) -> String {
+        if let Some(source) = self.extract_source(node)
+            && (source.starts_with("[[") || source.starts_with("![["))
+            && source.ends_with("]]")
+            && !source.contains('\n')
+        {
+            return source;
+        }
+        let NodeValue::WikiLink(wikilink) = &node.data.borrow().value else {
+            return String::new();
+        };
+        // Line breaks within the brackets become spaces
+        let url = wikilink
+            .url
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (embed, url) = match url.strip_prefix('!') {
+            Some(url) => ("!", url),
+            None => ("", url.as_str()),
+        };
+        let title = self.collect_raw_text(node);
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        if title.is_empty() || title == wikilink.url || title == url {
+            format!("{}[[{}]]", embed, url)
+        } else {
+            format!("{}[[{}|{}]]", embed, url, title)
+        }
+    }
+
+    /// If `node` is a paragraph that consists of display math only, return
+    /// the lines of the math block to write in its place: the contents
+    /// between delimiters on lines of their own, either `$$` or a `math`
+    /// fence depending on the options.
+    pub(super) fn display_math_lines<'b>(&self, node: &'b AstNode<'b>) -> Option<Vec<String>> {
+        let child = node.first_child()?;
+        if child.next_sibling().is_some() {
+            return None;
+        }
+        let NodeValue::Math(math) = &child.data.borrow().value else {
+            return None;
+        };
+        if !math.display_math {
+            return None;
+        }
+        let body: Vec<&str> = math.literal.trim().lines().map(str::trim_end).collect();
+        let (opening, closing) = match self.options.extension_math_delimiters {
+            MathDelimiters::Dollar => ("$$".to_string(), "$$".to_string()),
+            MathDelimiters::Fence => {
+                let fence_char = self.options.fence_char.as_char();
+                let longest_run = body
+                    .iter()
+                    .map(|line| line.chars().take_while(|&c| c == fence_char).count())
+                    .max()
+                    .unwrap_or(0);
+                let length = self.options.min_fence_length.get().max(longest_run + 1);
+                let fence: String = std::iter::repeat_n(fence_char, length).collect();
+                let space = if self.options.space_after_fence {
+                    " "
+                } else {
+                    ""
+                };
+                (format!("{}{}math", fence, space), fence)
+            }
+        };
+        let mut lines = vec![opening];
+        lines.extend(body.into_iter().map(str::to_string));
+        lines.push(closing);
+        Some(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_math() {
+        let inline = NodeMath {
+            dollar_math: true,
+            display_math: false,
+            literal: "e = mc^2".to_string(),
+        };
+        assert_eq!(format_math(&inline), "$e = mc^2$");
+        let display = NodeMath {
+            dollar_math: true,
+            display_math: true,
+            literal: "\nx^2\n".to_string(),
+        };
+        assert_eq!(format_math(&display), "$$\nx^2\n$$");
+    }
+}
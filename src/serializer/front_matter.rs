@@ -0,0 +1,773 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Front matter canonicalization.
+//!
+//! Front matter is normalized line by line rather than parsed and dumped
+//! again, so that comments and the overall layout survive.  The result is
+//! then parsed and compared with the original data; if anything differs,
+//! the canonicalization is rejected and the block is left untouched.
+
+use crate::FrontMatterQuoteStyle;
+use crate::front_matter::FrontMatterKind;
+
+/// How front matter should be canonicalized.
+#[derive(Debug, Clone, Copy)]
+pub struct Style<'a> {
+    /// Indentation width for nested structures.
+    pub indent_width: usize,
+    /// Quoting style for quoted strings.
+    pub quote_style: FrontMatterQuoteStyle,
+    /// Top-level keys to move to the beginning, in this order.
+    pub key_order: &'a [String],
+}
+
+/// Canonicalize the content of a front matter block (without delimiters).
+///
+/// Returns an error message if the front matter cannot be parsed, or if
+/// canonicalization would change its data.
+pub fn canonicalize(kind: FrontMatterKind, content: &str, style: &Style) -> Result<String, String> {
+    match kind {
+        FrontMatterKind::Yaml => {
+            let original = parse_yaml(content)?;
+            let result = canonicalize_yaml(content, style);
+            if parse_yaml(&result).ok() != Some(original) {
+                return Err("canonicalization would change its data".to_string());
+            }
+            Ok(result)
+        }
+        FrontMatterKind::Toml => {
+            let original = parse_toml(content)?;
+            let result = canonicalize_toml(content, style);
+            if parse_toml(&result).ok() != Some(original) {
+                return Err("canonicalization would change its data".to_string());
+            }
+            Ok(result)
+        }
+        FrontMatterKind::Json => Ok(content.to_string()),
+    }
+}
+
+fn parse_yaml(content: &str) -> Result<serde_yaml_ng::Value, String> {
+    serde_yaml_ng::from_str(content).map_err(|e| e.to_string())
+}
+
+fn parse_toml(content: &str) -> Result<toml::Table, String> {
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| e.to_string().trim_end().to_string())
+}
+
+/// A canonicalized line along with what the key reordering needs to know.
+struct Line {
+    text: String,
+    /// The key, if this line starts a top-level entry.
+    top_key: Option<String>,
+    /// Whether this line is a comment at the top level.
+    top_comment: bool,
+}
+
+/// A block scalar (`|` or `>`) whose content lines are being copied.
+struct BlockScalar {
+    /// Content lines must be indented more than this column.
+    parent_indent: usize,
+    /// The new column of the key that introduced the block scalar.
+    parent_new_indent: usize,
+    /// Whether the header has an explicit indentation indicator.
+    explicit_indent: bool,
+    /// The original indentation of the first content line.
+    base: Option<usize>,
+}
+
+fn canonicalize_yaml(content: &str, style: &Style) -> String {
+    // Each entry maps an original indentation column to its new column.
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut block_scalar: Option<BlockScalar> = None;
+    let mut lines = Vec::new();
+
+    for raw in content.lines() {
+        let indent = raw.len() - raw.trim_start_matches(' ').len();
+
+        if let Some(scalar) = &mut block_scalar {
+            if raw.trim().is_empty() {
+                // Blank lines inside a block scalar are kept as they are,
+                // since spaces past the indentation are part of the content.
+                let base = scalar.base.unwrap_or(usize::MAX);
+                lines.push(plain_line(if raw.len() > base {
+                    raw.to_string()
+                } else {
+                    String::new()
+                }));
+                continue;
+            }
+            if indent > scalar.parent_indent {
+                let base = *scalar.base.get_or_insert(indent);
+                let new_base = if scalar.explicit_indent {
+                    scalar.parent_new_indent + (base - scalar.parent_indent)
+                } else {
+                    scalar.parent_new_indent + style.indent_width
+                };
+                let new_indent = (new_base + indent).saturating_sub(base);
+                lines.push(plain_line(format!(
+                    "{}{}",
+                    " ".repeat(new_indent),
+                    &raw[indent..]
+                )));
+                continue;
+            }
+            block_scalar = None;
+        }
+
+        let text = raw.trim_end();
+        if text.is_empty() {
+            lines.push(plain_line(String::new()));
+            continue;
+        }
+        let body = &text[indent..];
+
+        if body.starts_with('#') {
+            // Comments take the indentation of the entry they sit next to,
+            // but do not open a new level.
+            let new_indent =
+                stack
+                    .iter()
+                    .rev()
+                    .find(|(orig, _)| *orig <= indent)
+                    .map_or(0, |(orig, new)| {
+                        if *orig == indent {
+                            *new
+                        } else {
+                            new + style.indent_width
+                        }
+                    });
+            lines.push(Line {
+                text: format!("{}{}", " ".repeat(new_indent), body),
+                top_key: None,
+                top_comment: new_indent == 0,
+            });
+            continue;
+        }
+
+        while stack.last().is_some_and(|(orig, _)| *orig > indent) {
+            stack.pop();
+        }
+        let new_indent = match stack.last() {
+            Some((orig, new)) if *orig == indent => *new,
+            Some((_, new)) => {
+                let new = new + style.indent_width;
+                stack.push((indent, new));
+                new
+            }
+            None => {
+                stack.push((indent, 0));
+                0
+            }
+        };
+
+        // The content of a sequence item starts after its `- ` marker; the
+        // lines that continue it are aligned with that content.
+        let mut rest = body;
+        let mut column = indent;
+        let mut new_column = new_indent;
+        while let Some(after) = rest.strip_prefix('-')
+            && (after.is_empty() || after.starts_with(' '))
+        {
+            let spaces = after.len() - after.trim_start().len();
+            if after.trim_start().is_empty() {
+                break;
+            }
+            column += 1 + spaces;
+            new_column += 1 + spaces;
+            stack.push((column, new_column));
+            rest = after.trim_start();
+        }
+
+        if is_block_scalar_header(rest) {
+            let explicit_indent = rest
+                .rsplit_once(['|', '>'])
+                .is_some_and(|(_, indicator)| indicator.chars().any(|c| c.is_ascii_digit()));
+            block_scalar = Some(BlockScalar {
+                parent_indent: column,
+                parent_new_indent: new_column,
+                explicit_indent,
+                base: None,
+            });
+        }
+
+        let requoted = requote_yaml(rest, style.quote_style);
+        lines.push(Line {
+            text: format!(
+                "{}{}{}",
+                " ".repeat(new_indent),
+                &body[..body.len() - rest.len()],
+                requoted
+            ),
+            top_key: if new_indent == 0 && rest.len() == body.len() {
+                yaml_key(rest)
+            } else {
+                None
+            },
+            top_comment: false,
+        });
+    }
+
+    join_lines(reorder_keys(lines, style.key_order))
+}
+
+fn plain_line(text: String) -> Line {
+    Line {
+        text,
+        top_key: None,
+        top_comment: false,
+    }
+}
+
+/// Check whether a line ends with a block scalar indicator (`|` or `>`,
+/// optionally followed by chomping and indentation indicators).
+fn is_block_scalar_header(text: &str) -> bool {
+    let text = strip_yaml_comment(text).trim_end();
+    let indicator = text.trim_end_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+    let Some(before) = indicator
+        .strip_suffix('|')
+        .or_else(|| indicator.strip_suffix('>'))
+    else {
+        return false;
+    };
+    let before = before.trim_end();
+    before.is_empty() || before.ends_with(':') || before == "-"
+}
+
+/// Remove a trailing ` # comment` from a YAML line, ignoring `#` inside
+/// quoted strings.
+fn strip_yaml_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some('"') if c == '"' && prev != '\\' => quote = None,
+            Some('\'') if c == '\'' => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && prev.is_whitespace() => return &text[..i],
+            None => {}
+        }
+        prev = c;
+    }
+    text
+}
+
+/// Extract the key of a YAML mapping entry.
+fn yaml_key(text: &str) -> Option<String> {
+    if let Some(quote @ ('"' | '\'')) = text.chars().next() {
+        let end = text[1..].find(quote)? + 1;
+        return text[end + 1..]
+            .trim_start()
+            .starts_with(':')
+            .then(|| text[1..end].to_string());
+    }
+    let colon = text
+        .find(": ")
+        .or_else(|| text.strip_suffix(':').map(|key| key.len()))?;
+    Some(text[..colon].trim_end().to_string())
+}
+
+/// Change the quotes of a single-line quoted scalar value on a YAML line.
+fn requote_yaml(text: &str, quote_style: FrontMatterQuoteStyle) -> String {
+    if quote_style == FrontMatterQuoteStyle::Preserve {
+        return text.to_string();
+    }
+    let colon = match split_quoted(text) {
+        // A quoted key or a quoted sequence item
+        Some((_, rest)) => match rest.trim_start().strip_prefix(':') {
+            Some(after) if after.starts_with(' ') => Some(text.len() - after.len() - 1),
+            _ => None,
+        },
+        None => text.find(": "),
+    };
+    let value_start = match colon {
+        Some(colon) => colon + 2 + (text[colon + 2..].len() - text[colon + 2..].trim_start().len()),
+        None if text.starts_with(['"', '\'']) => 0,
+        None => return text.to_string(),
+    };
+    let value = &text[value_start..];
+    let Some((scalar, rest)) = split_quoted(value) else {
+        return text.to_string();
+    };
+    if !(rest.trim().is_empty() || rest.trim_start().starts_with('#') && rest.starts_with(' ')) {
+        return text.to_string();
+    }
+    let Some(requoted) = requote(scalar, quote_style, Syntax::Yaml) else {
+        return text.to_string();
+    };
+    format!("{}{}{}", &text[..value_start], requoted, rest)
+}
+
+/// The quoting rules of a front matter syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Yaml,
+    Toml,
+}
+
+/// Split a quoted string at the start of `text` from what follows it.
+///
+/// Returns `None` if `text` does not start with a quoted string that ends
+/// on the same line.
+fn split_quoted(text: &str) -> Option<(&str, &str)> {
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            // In YAML, a doubled single quote is an escaped single quote.
+            if quote == '\'' && chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                chars.next();
+                continue;
+            }
+            return Some((&text[..=i], &text[i + 1..]));
+        }
+    }
+    None
+}
+
+/// Convert a quoted string to the given quoting style.
+///
+/// Returns `None` if the string cannot be converted without changing its
+/// value, or is already in the requested style.
+fn requote(scalar: &str, quote_style: FrontMatterQuoteStyle, syntax: Syntax) -> Option<String> {
+    let inner = &scalar[1..scalar.len() - 1];
+    match (scalar.chars().next()?, quote_style) {
+        ('\'', FrontMatterQuoteStyle::Double) => {
+            let value = match syntax {
+                Syntax::Yaml => inner.replace("''", "'"),
+                Syntax::Toml => inner.to_string(),
+            };
+            if value.chars().any(|c| c.is_control() && c != '\t') {
+                return None;
+            }
+            Some(format!(
+                "\"{}\"",
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            ))
+        }
+        ('"', FrontMatterQuoteStyle::Single) => {
+            // Only simple escapes can be expressed in single quotes.
+            let mut value = String::new();
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                if c == '\\' {
+                    match chars.next()? {
+                        escaped @ ('\\' | '"') => value.push(escaped),
+                        _ => return None,
+                    }
+                } else {
+                    value.push(c);
+                }
+            }
+            match syntax {
+                Syntax::Yaml => Some(format!("'{}'", value.replace('\'', "''"))),
+                Syntax::Toml if value.contains('\'') => None,
+                Syntax::Toml => Some(format!("'{}'", value)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Lexical state of a TOML scanner at a line boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TomlState {
+    Normal,
+    MultilineBasic,
+    MultilineLiteral,
+}
+
+/// Scan a TOML line, returning the state and the bracket depth at its end.
+fn scan_toml_line(line: &str, mut state: TomlState, mut depth: usize) -> (TomlState, usize) {
+    let mut i = 0;
+    let bytes = line.as_bytes();
+    while i < bytes.len() {
+        let rest = &line[i..];
+        match state {
+            TomlState::MultilineBasic => {
+                if rest.starts_with('\\') {
+                    i += 2;
+                    continue;
+                }
+                if rest.starts_with("\"\"\"") {
+                    state = TomlState::Normal;
+                    i += 3;
+                    continue;
+                }
+            }
+            TomlState::MultilineLiteral => {
+                if rest.starts_with("'''") {
+                    state = TomlState::Normal;
+                    i += 3;
+                    continue;
+                }
+            }
+            TomlState::Normal => {
+                if rest.starts_with("\"\"\"") {
+                    state = TomlState::MultilineBasic;
+                    i += 3;
+                    continue;
+                }
+                if rest.starts_with("'''") {
+                    state = TomlState::MultilineLiteral;
+                    i += 3;
+                    continue;
+                }
+                if rest.starts_with(['"', '\'']) {
+                    match split_quoted(rest) {
+                        Some((quoted, _)) => i += quoted.len(),
+                        None => return (state, depth),
+                    }
+                    continue;
+                }
+                match bytes[i] {
+                    b'#' => return (state, depth),
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    (state, depth)
+}
+
+fn canonicalize_toml(content: &str, style: &Style) -> String {
+    let mut state = TomlState::Normal;
+    let mut depth = 0;
+    let mut in_root_table = true;
+    let mut lines = Vec::new();
+
+    for raw in content.lines() {
+        let (end_state, end_depth) = scan_toml_line(raw, state, depth);
+        if state != TomlState::Normal {
+            // Inside a multiline string everything is content.
+            lines.push(plain_line(raw.to_string()));
+        } else {
+            let mut text = raw.trim();
+            if end_state != TomlState::Normal {
+                // Trailing whitespace belongs to the string that is opened.
+                text = raw.trim_start();
+            }
+            let statement = depth == 0;
+            if statement && text.starts_with('[') {
+                in_root_table = false;
+            }
+            let indent = if statement {
+                0
+            } else {
+                let closing = text.starts_with([']', '}']);
+                (depth - usize::from(closing)) * style.indent_width
+            };
+            let text = if statement && !text.starts_with(['[', '#']) {
+                requote_toml(text, style.quote_style)
+            } else {
+                text.to_string()
+            };
+            let top_key = (statement && in_root_table && !text.is_empty())
+                .then(|| toml_key(&text))
+                .flatten();
+            lines.push(Line {
+                top_comment: statement && in_root_table && text.starts_with('#'),
+                text: if text.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", " ".repeat(indent), text)
+                },
+                top_key,
+            });
+        }
+        state = end_state;
+        depth = end_depth;
+    }
+
+    join_lines(reorder_keys(lines, style.key_order))
+}
+
+/// Extract the first segment of the key of a TOML key/value line.
+fn toml_key(text: &str) -> Option<String> {
+    if text.starts_with('#') {
+        return None;
+    }
+    let key = if let Some((quoted, _)) = split_quoted(text) {
+        &quoted[1..quoted.len() - 1]
+    } else {
+        let (key, _) = text.split_once('=')?;
+        key.split('.').next()?.trim()
+    };
+    Some(key.to_string())
+}
+
+/// Change the quotes of a single-line string value on a TOML line.
+fn requote_toml(text: &str, quote_style: FrontMatterQuoteStyle) -> String {
+    if quote_style == FrontMatterQuoteStyle::Preserve {
+        return text.to_string();
+    }
+    // Find the `=` that separates the key from the value.
+    let mut i = 0;
+    let equals = loop {
+        let rest = &text[i..];
+        if rest.is_empty() {
+            return text.to_string();
+        }
+        if let Some((quoted, _)) = split_quoted(rest) {
+            i += quoted.len();
+            continue;
+        }
+        if rest.starts_with('=') {
+            break i;
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    };
+    let value_start =
+        equals + 1 + (text[equals + 1..].len() - text[equals + 1..].trim_start().len());
+    let value = &text[value_start..];
+    if value.starts_with("\"\"\"") || value.starts_with("'''") {
+        return text.to_string();
+    }
+    let Some((scalar, rest)) = split_quoted(value) else {
+        return text.to_string();
+    };
+    if !(rest.trim().is_empty() || rest.trim_start().starts_with('#')) {
+        return text.to_string();
+    }
+    // TOML has no doubled-quote escape, so a literal string like 'it''s'
+    // is really two strings; leave anything unusual alone.
+    if scalar.starts_with('\'') && scalar[1..scalar.len() - 1].contains('\'') {
+        return text.to_string();
+    }
+    let Some(requoted) = requote(scalar, quote_style, Syntax::Toml) else {
+        return text.to_string();
+    };
+    format!("{}{}{}", &text[..value_start], requoted, rest)
+}
+
+/// Move the top-level entries named in `key_order` to the beginning.
+///
+/// An entry consists of its key line, the comment lines directly above it,
+/// and the lines that continue it.  The blank lines between entries stay in
+/// place, so the overall spacing of the block is preserved.
+fn reorder_keys(lines: Vec<Line>, key_order: &[String]) -> Vec<Line> {
+    if key_order.is_empty() {
+        return lines;
+    }
+
+    // Find where each entry starts, including its leading comments.
+    let mut starts: Vec<usize> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.top_key.is_some() {
+            let mut start = i;
+            while start > starts.last().map_or(0, |s| s + 1) && lines[start - 1].top_comment {
+                start -= 1;
+            }
+            starts.push(start);
+        }
+    }
+    if starts.is_empty() {
+        return lines;
+    }
+
+    // The region after the last root entry (e.g. TOML tables) stays last.
+    let region_end = lines
+        .iter()
+        .enumerate()
+        .skip(starts[starts.len() - 1])
+        .skip(1)
+        .find(|(_, line)| line.top_key.is_none() && line.text.starts_with('['))
+        .map_or(lines.len(), |(i, _)| i);
+
+    let mut entries: Vec<(Option<String>, Vec<Line>)> = Vec::new();
+    let mut gaps: Vec<Vec<Line>> = Vec::new();
+    let mut lines = lines.into_iter().enumerate().peekable();
+    let mut prefix = Vec::new();
+    while let Some((_, line)) = lines.next_if(|(i, _)| *i < starts[0]) {
+        prefix.push(line);
+    }
+    for (n, start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(region_end);
+        let mut entry: Vec<Line> = Vec::new();
+        while let Some((_, line)) = lines.next_if(|(i, _)| *i >= *start && *i < end) {
+            entry.push(line);
+        }
+        let mut gap = Vec::new();
+        while entry.last().is_some_and(|line| line.text.is_empty()) {
+            gap.push(entry.pop().unwrap());
+        }
+        let key = entry.iter().find_map(|line| line.top_key.clone());
+        entries.push((key, entry));
+        gaps.push(gap);
+    }
+    let suffix: Vec<Line> = lines.map(|(_, line)| line).collect();
+
+    let rank = |key: &Option<String>| {
+        key.as_ref()
+            .and_then(|key| key_order.iter().position(|k| k == key))
+            .unwrap_or(key_order.len())
+    };
+    entries.sort_by_key(|(key, _)| rank(key));
+
+    let mut result = prefix;
+    for ((_, entry), gap) in entries.into_iter().zip(gaps) {
+        result.extend(entry);
+        result.extend(gap);
+    }
+    result.extend(suffix);
+    result
+}
+
+fn join_lines(lines: Vec<Line>) -> String {
+    let mut result = String::new();
+    for line in lines {
+        result.push_str(&line.text);
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(quote_style: FrontMatterQuoteStyle, key_order: &[String]) -> Style<'_> {
+        Style {
+            indent_width: 2,
+            quote_style,
+            key_order,
+        }
+    }
+
+    fn yaml(content: &str, style: &Style) -> Result<String, String> {
+        canonicalize(FrontMatterKind::Yaml, content, style)
+    }
+
+    fn toml(content: &str, style: &Style) -> Result<String, String> {
+        canonicalize(FrontMatterKind::Toml, content, style)
+    }
+
+    #[test]
+    fn test_yaml_trailing_whitespace() {
+        let result = yaml(
+            "title: Hello   \ntags:  \n  - a \n",
+            &style(FrontMatterQuoteStyle::Preserve, &[]),
+        );
+        assert_eq!(result.unwrap(), "title: Hello\ntags:\n  - a\n");
+    }
+
+    #[test]
+    fn test_yaml_indentation() {
+        let input = "author:\n    name: Hong\n    links:\n        - a\n        - b\n# comment\ntags:\n- x\n";
+        let result = yaml(input, &style(FrontMatterQuoteStyle::Preserve, &[]));
+        assert_eq!(
+            result.unwrap(),
+            "author:\n  name: Hong\n  links:\n    - a\n    - b\n# comment\ntags:\n- x\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_sequence_of_mappings() {
+        let input =
+            "items:\n    -   name: a\n        value: 1\n    -   name: b\n        value: 2\n";
+        let result = yaml(input, &style(FrontMatterQuoteStyle::Preserve, &[]));
+        assert_eq!(
+            result.unwrap(),
+            "items:\n  -   name: a\n      value: 1\n  -   name: b\n      value: 2\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_block_scalar() {
+        let input = "description: |\n      First line.  \n\n        Indented line.\nnext: value\n";
+        let result = yaml(input, &style(FrontMatterQuoteStyle::Preserve, &[]));
+        assert_eq!(
+            result.unwrap(),
+            "description: |\n  First line.  \n\n    Indented line.\nnext: value\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_quote_style_double() {
+        let input = "title: 'It''s \"here\"'\nplain: text\nlist:\n  - 'a'\n'key': 'b'\n";
+        let result = yaml(input, &style(FrontMatterQuoteStyle::Double, &[]));
+        assert_eq!(
+            result.unwrap(),
+            "title: \"It's \\\"here\\\"\"\nplain: text\nlist:\n  - \"a\"\n'key': \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_quote_style_single() {
+        let input = "title: \"It's\"\nescaped: \"tab\\there\"\n";
+        let result = yaml(input, &style(FrontMatterQuoteStyle::Single, &[]));
+        assert_eq!(result.unwrap(), "title: 'It''s'\nescaped: \"tab\\there\"\n");
+    }
+
+    #[test]
+    fn test_yaml_key_order() {
+        let key_order = vec!["title".to_string(), "date".to_string()];
+        let input = "tags:\n  - a\n\n# The date\ndate: 2025-01-01\n\ntitle: Hello\n";
+        let result = yaml(input, &style(FrontMatterQuoteStyle::Preserve, &key_order));
+        assert_eq!(
+            result.unwrap(),
+            "title: Hello\n\n# The date\ndate: 2025-01-01\n\ntags:\n  - a\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_parse_error() {
+        let result = yaml(
+            "title: [unclosed\n",
+            &style(FrontMatterQuoteStyle::Preserve, &[]),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_toml_canonicalize() {
+        let input = "  title = 'Hello'  \n# Tags\ntags = [\n\"a\",\n      \"b\",\n]\n\n[extra]\n  key = \"value\"\n";
+        let result = toml(input, &style(FrontMatterQuoteStyle::Double, &[]));
+        assert_eq!(
+            result.unwrap(),
+            "title = \"Hello\"\n# Tags\ntags = [\n  \"a\",\n  \"b\",\n]\n\n[extra]\nkey = \"value\"\n"
+        );
+    }
+
+    #[test]
+    fn test_toml_multiline_string_untouched() {
+        let input = "body = \"\"\"\n  keep   \n    this\"\"\"\n";
+        let result = toml(input, &style(FrontMatterQuoteStyle::Single, &[]));
+        assert_eq!(result.unwrap(), input);
+    }
+
+    #[test]
+    fn test_toml_quote_style_single() {
+        let input = "a = \"plain\"\nb = \"it's\"\nc = \"back\\\\slash\"\n";
+        let result = toml(input, &style(FrontMatterQuoteStyle::Single, &[]));
+        assert_eq!(
+            result.unwrap(),
+            "a = 'plain'\nb = \"it's\"\nc = 'back\\slash'\n"
+        );
+    }
+
+    #[test]
+    fn test_toml_key_order() {
+        let key_order = vec!["title".to_string()];
+        let input = "draft = true\ntitle = \"Hello\"\n\n[taxonomies]\ntitle = \"x\"\n";
+        let result = toml(input, &style(FrontMatterQuoteStyle::Preserve, &key_order));
+        assert_eq!(
+            result.unwrap(),
+            "title = \"Hello\"\ndraft = true\n\n[taxonomies]\ntitle = \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn test_toml_parse_error() {
+        let result = toml("title = \n", &style(FrontMatterQuoteStyle::Preserve, &[]));
+        assert!(result.is_err());
+    }
+}
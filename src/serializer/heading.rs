@@ -2,9 +2,48 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 //! Heading sentence case conversion.
 
+use crate::{Options, TitleCaseStyle};
+
 // Include generated proper nouns constants
 include!(concat!(env!("OUT_DIR"), "/proper_nouns_generated.rs"));
 
+/// Errors that can occur during heading configuration validation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeadingError {
+    /// A disabled proper noun category is not one of
+    /// [`PROPER_NOUN_CATEGORIES`].
+    UnknownCategory(String),
+}
+
+impl std::fmt::Display for HeadingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeadingError::UnknownCategory(category) => {
+                write!(
+                    f,
+                    "unknown proper noun category '{}'; expected one of: {}",
+                    category,
+                    PROPER_NOUN_CATEGORIES.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeadingError {}
+
+/// Validate that every disabled proper noun category exists.
+pub fn validate_proper_noun_categories(options: &Options) -> Result<(), HeadingError> {
+    match options
+        .heading_disabled_categories
+        .iter()
+        .find(|category| !PROPER_NOUN_CATEGORIES.contains(&category.as_str()))
+    {
+        Some(category) => Err(HeadingError::UnknownCategory(category.clone())),
+        None => Ok(()),
+    }
+}
+
 /// Convert heading text to sentence case.
 ///
 /// This function applies intelligent heuristics to convert heading text:
@@ -58,6 +97,68 @@ pub fn to_sentence_case(
     result
 }
 
+/// Convert heading text to title case.
+///
+/// Every word is capitalized except articles, coordinating conjunctions, and
+/// prepositions, which are lowercased unless they are the first or the last
+/// word or follow a colon.  Which prepositions count follows `style`: all of
+/// them for Chicago, only those of up to three letters for AP.  Code spans,
+/// acronyms, proper nouns, and words with capitals inside (e.g., `iPhone`)
+/// are preserved as in [`to_sentence_case`], as are words with digits (e.g.,
+/// `v0.1.1`).  The parts of hyphenated compounds are capitalized like words,
+/// except that minor words after the first part stay lowercase.
+pub fn to_title_case(
+    text: &str,
+    style: TitleCaseStyle,
+    user_proper_nouns: &[String],
+    common_nouns: &[String],
+) -> String {
+    let tokens = tokenize_with_code_spans(text);
+    let last_text = tokens.iter().rposition(|token| match token {
+        Token::CodeSpan(_) => false,
+        Token::Quote(content, _) | Token::Text(content) => {
+            content.chars().any(char::is_alphanumeric)
+        }
+    });
+    let multiword_nouns = collect_multiword_proper_nouns(user_proper_nouns, common_nouns);
+    let context = TitleCaseContext {
+        style,
+        user_proper_nouns,
+        common_nouns,
+        multiword_nouns: &multiword_nouns,
+    };
+
+    let mut result = String::new();
+    let mut is_first_word = true;
+    for (i, token) in tokens.into_iter().enumerate() {
+        let is_last = Some(i) == last_text;
+        match token {
+            Token::CodeSpan(content) => {
+                result.push_str(&content);
+                is_first_word = false;
+            }
+            Token::Quote(content, is_double) => {
+                let (opening, closing) = if is_double {
+                    ('\u{201C}', '\u{201D}')
+                } else {
+                    ('\u{2018}', '\u{2019}')
+                };
+                // A quotation starts a new phrase, so its first word is
+                // capitalized
+                let mut is_first_quoted = true;
+                result.push(opening);
+                result.push_str(&context.process_text(&content, &mut is_first_quoted, is_last));
+                result.push(closing);
+                is_first_word = false;
+            }
+            Token::Text(content) => {
+                result.push_str(&context.process_text(&content, &mut is_first_word, is_last));
+            }
+        }
+    }
+    result
+}
+
 /// Token types for parsing heading text.
 #[derive(Debug, PartialEq)]
 enum Token {
@@ -210,6 +311,22 @@ fn tokenize_with_code_spans(text: &str) -> Vec<Token> {
                 }
             }
             tokens.push(Token::CodeSpan(code_span));
+        } else if ch == '[' && chars.peek() == Some(&'[') {
+            // Wikilinks name pages, so they are preserved as-is like code
+            // spans
+            if !current.is_empty() {
+                let normalized = normalize_quotes(&current);
+                tokens.extend(tokenize_quotes(&normalized));
+                current.clear();
+            }
+            let mut wikilink = String::from('[');
+            for ch in chars.by_ref() {
+                wikilink.push(ch);
+                if wikilink.ends_with("]]") {
+                    break;
+                }
+            }
+            tokens.push(Token::CodeSpan(wikilink));
         } else {
             current.push(ch);
         }
@@ -352,6 +469,36 @@ fn process_quoted_text(
     format!("{}{}{}", opening, processed, closing)
 }
 
+/// Find the words of `original` that `converted`, its sentence case form,
+/// lowercases, without the punctuation around them.
+pub(crate) fn lowercased_words(original: &str, converted: &str) -> Vec<String> {
+    original
+        .split_whitespace()
+        .zip(converted.split_whitespace())
+        .filter(|(before, after)| {
+            before
+                .chars()
+                .zip(after.chars())
+                .any(|(b, a)| b.is_uppercase() && a.is_lowercase())
+        })
+        .map(|(before, _)| {
+            before
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_string()
+        })
+        .collect()
+}
+
+/// The built-in proper nouns in any of the given categories.
+pub(crate) fn builtin_proper_nouns_in(
+    categories: &[String],
+) -> impl Iterator<Item = &'static str> + '_ {
+    PROPER_NOUNS
+        .iter()
+        .filter(|(_, _, category)| categories.iter().any(|c| c == category))
+        .map(|(canonical, _, _)| *canonical)
+}
+
 /// Collect all multi-word proper nouns (2+ words) from built-in and user lists.
 /// Returns Vec of (canonical_form, lowercase_search_key).
 /// Excludes any that appear in common_nouns.
@@ -363,7 +510,7 @@ fn collect_multiword_proper_nouns(
     let common_nouns_lower: Vec<String> = common_nouns.iter().map(|s| s.to_lowercase()).collect();
 
     // Collect from built-in proper nouns
-    for (canonical, _key) in PROPER_NOUNS {
+    for (canonical, _key, _category) in PROPER_NOUNS {
         if canonical.contains(' ') {
             let lowercase_key = canonical.to_lowercase();
             if !common_nouns_lower.contains(&lowercase_key) {
@@ -383,7 +530,7 @@ fn collect_multiword_proper_nouns(
     }
 
     // Sort by length (longest first) to handle overlapping matches correctly
-    multiword_nouns.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+    multiword_nouns.sort_by_key(|b| std::cmp::Reverse(b.0.len()));
 
     multiword_nouns
 }
@@ -856,7 +1003,7 @@ fn find_proper_noun(
     }
 
     // Check built-in proper nouns (excluding those in common_nouns)
-    for (canonical, key) in PROPER_NOUNS {
+    for (canonical, key, _category) in PROPER_NOUNS {
         if *key == core_word_lower {
             return Some(format!(
                 "{}{}{}{}",
@@ -868,6 +1015,229 @@ fn find_proper_noun(
     None
 }
 
+/// Articles and coordinating conjunctions, lowercased in title case in both
+/// styles, along with abbreviations that are never capitalized.
+const TITLE_CASE_MINOR_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "but", "for", "nor", "or", "as", "vs", "vs.", "v.", "e.g.", "i.e.",
+    "cf.", "etc.",
+];
+
+/// Prepositions of up to three letters, lowercased in title case in both
+/// styles.  Ones that usually serve as adverbs in headings, such as _up_ in
+/// _Setting Up_, are left out.
+const SHORT_PREPOSITIONS: &[&str] = &["at", "by", "in", "of", "on", "per", "to", "via"];
+
+/// Longer prepositions, lowercased in title case in the Chicago style only.
+/// As with the short ones, ones that usually serve as adverbs in headings,
+/// such as _down_, _off_, and _out_, are left out.
+const LONG_PREPOSITIONS: &[&str] = &[
+    "about",
+    "above",
+    "across",
+    "after",
+    "against",
+    "along",
+    "amid",
+    "among",
+    "amongst",
+    "around",
+    "atop",
+    "before",
+    "behind",
+    "below",
+    "beneath",
+    "beside",
+    "besides",
+    "between",
+    "beyond",
+    "concerning",
+    "despite",
+    "during",
+    "except",
+    "following",
+    "from",
+    "inside",
+    "into",
+    "like",
+    "near",
+    "onto",
+    "opposite",
+    "outside",
+    "over",
+    "past",
+    "regarding",
+    "since",
+    "than",
+    "through",
+    "throughout",
+    "till",
+    "toward",
+    "towards",
+    "under",
+    "underneath",
+    "unlike",
+    "until",
+    "upon",
+    "versus",
+    "with",
+    "within",
+    "without",
+];
+
+/// The words that AP style lowercases in title case besides the articles,
+/// conjunctions, and short prepositions.
+const AP_MINOR_WORDS: &[&str] = &["so", "yet"];
+
+/// Pronouns, determiners, auxiliary verbs, and other words that are too
+/// common to be proper nouns, left out of the unknown words along with the
+/// minor words of title case.
+const COMMON_WORDS: &[&str] = &[
+    "all", "am", "any", "are", "be", "been", "can", "could", "did", "do", "does", "each", "every",
+    "has", "have", "he", "her", "his", "how", "i", "if", "is", "it", "its", "may", "me", "might",
+    "must", "my", "no", "not", "our", "she", "should", "some", "that", "their", "them", "then",
+    "these", "they", "this", "those", "us", "was", "we", "were", "what", "when", "where", "which",
+    "who", "why", "will", "would", "you", "your",
+];
+
+/// Check if `word` is a minor word of title case or a word too common to be
+/// a proper noun.
+pub(crate) fn is_common_word(word: &str) -> bool {
+    let lower = word.to_lowercase();
+    [
+        TITLE_CASE_MINOR_WORDS,
+        SHORT_PREPOSITIONS,
+        LONG_PREPOSITIONS,
+        AP_MINOR_WORDS,
+        COMMON_WORDS,
+    ]
+    .iter()
+    .any(|words| words.contains(&lower.as_str()))
+}
+
+/// Settings shared by the words of a heading converted to title case.
+struct TitleCaseContext<'c> {
+    style: TitleCaseStyle,
+    user_proper_nouns: &'c [String],
+    common_nouns: &'c [String],
+    multiword_nouns: &'c [(String, String)],
+}
+
+impl TitleCaseContext<'_> {
+    /// Convert a run of text to title case.  The first word is capitalized
+    /// if `is_first_word` is set, and the last one if `ends_heading` is.
+    fn process_text(&self, text: &str, is_first_word: &mut bool, ends_heading: bool) -> String {
+        let (text, replacements) = replace_multiword_with_placeholders(text, self.multiword_nouns);
+        let words: Vec<&str> = text.split(' ').collect();
+        let last_word = if ends_heading {
+            words
+                .iter()
+                .rposition(|word| word.chars().any(char::is_alphanumeric))
+        } else {
+            None
+        };
+        let mut result = String::new();
+        let mut after_delimiter = false;
+        for (i, word) in words.into_iter().enumerate() {
+            if i > 0 {
+                result.push(' ');
+            }
+            if word.is_empty() {
+                continue;
+            }
+            let is_major = *is_first_word || after_delimiter || Some(i) == last_word;
+            result.push_str(&self.process_word(word, is_major));
+            *is_first_word = false;
+            after_delimiter = word.ends_with([':', ';', '—', '–']);
+        }
+        restore_placeholders(&result, &replacements)
+    }
+
+    /// Convert a word to title case.  A minor word is still capitalized if
+    /// `is_major` is set.
+    fn process_word(&self, word: &str, is_major: bool) -> String {
+        if (word.contains('-') || word.contains('/'))
+            && let Some(canonical) =
+                find_proper_noun(word, self.user_proper_nouns, self.common_nouns)
+        {
+            return canonical;
+        }
+        if word.contains('/') {
+            return word
+                .split('/')
+                .map(|part| self.process_word(part, is_major))
+                .collect::<Vec<_>>()
+                .join("/");
+        }
+        // Only the first part of a compound can be a minor word that is
+        // capitalized, as in _To-Do List_
+        word.split('-')
+            .enumerate()
+            .map(|(i, part)| self.process_word_simple(part, is_major && i == 0))
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Convert a word without hyphens or slashes to title case.
+    fn process_word_simple(&self, word: &str, is_major: bool) -> String {
+        if word.is_empty() || word.contains('\u{FFFD}') || is_acronym(word) {
+            return word.to_string();
+        }
+        if let Some(canonical) = is_first_person_pronoun_i(word) {
+            return canonical;
+        }
+        if let Some(canonical) = find_proper_noun(word, self.user_proper_nouns, self.common_nouns) {
+            return canonical;
+        }
+        // Words with capitals after the first letter, such as iPhone or
+        // McDonald, and words with digits, such as v0.1.1 or x86, are
+        // written the way they are meant to be
+        if word.chars().any(|c| c.is_ascii_digit())
+            || word
+                .chars()
+                .filter(|c| c.is_alphabetic())
+                .skip(1)
+                .any(char::is_uppercase)
+        {
+            return word.to_string();
+        }
+        let lower = word.to_lowercase();
+        if !is_major && self.is_minor_word(&lower) {
+            return lower;
+        }
+        capitalize_first_letter(&lower)
+    }
+
+    /// Check if a lowercase word, possibly with punctuation around it, is
+    /// one that title case does not capitalize.
+    fn is_minor_word(&self, word: &str) -> bool {
+        let word = word.trim_start_matches(|c: char| !c.is_alphanumeric());
+        if TITLE_CASE_MINOR_WORDS.contains(&word) {
+            return true;
+        }
+        let word = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+        TITLE_CASE_MINOR_WORDS.contains(&word)
+            || SHORT_PREPOSITIONS.contains(&word)
+            || match self.style {
+                TitleCaseStyle::Chicago => LONG_PREPOSITIONS.contains(&word),
+                TitleCaseStyle::Ap => AP_MINOR_WORDS.contains(&word),
+            }
+    }
+}
+
+/// Capitalize the first letter of a word, after any leading punctuation,
+/// leaving the rest as is.
+fn capitalize_first_letter(word: &str) -> String {
+    match word.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((i, first)) => {
+            let mut result = word[..i].to_string();
+            result.extend(first.to_uppercase());
+            result.push_str(&word[i + first.len_utf8()..]);
+            result
+        }
+        None => word.to_string(),
+    }
+}
+
 /// Capitalize the first letter of a word.
 fn capitalize_first(word: &str) -> String {
     let mut chars = word.chars();
@@ -1018,6 +1388,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_is_common_word() {
+        assert!(is_common_word("The"));
+        assert!(is_common_word("With"));
+        assert!(is_common_word("Your"));
+        assert!(is_common_word("Through"));
+        assert!(!is_common_word("Fedify"));
+        assert!(!is_common_word("Kubernetes"));
+    }
+
+    #[test]
+    fn test_lowercased_words() {
+        assert_eq!(
+            lowercased_words(
+                "Deploying Fedify To (Kubernetes), The JSON-RPC Way",
+                "Deploying fedify to (kubernetes), the JSON-RPC way"
+            ),
+            vec!["Fedify", "To", "Kubernetes", "The", "Way"]
+        );
+        assert_eq!(
+            lowercased_words("getting started", "Getting started"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_builtin_proper_nouns_in() {
+        let languages: Vec<_> =
+            builtin_proper_nouns_in(&["programming-languages".to_string()]).collect();
+        assert!(languages.contains(&"JavaScript"));
+        assert!(!languages.contains(&"France"));
+        assert!(PROPER_NOUN_CATEGORIES.contains(&"countries"));
+        assert!(
+            PROPER_NOUNS
+                .iter()
+                .all(|(_, _, category)| PROPER_NOUN_CATEGORIES.contains(category))
+        );
+    }
+
+    #[test]
+    fn test_validate_proper_noun_categories() {
+        let mut options = Options {
+            heading_disabled_categories: vec!["countries".to_string()],
+            ..Options::default()
+        };
+        assert!(validate_proper_noun_categories(&options).is_ok());
+        options
+            .heading_disabled_categories
+            .push("contries".to_string());
+        assert_eq!(
+            validate_proper_noun_categories(&options),
+            Err(HeadingError::UnknownCategory("contries".to_string()))
+        );
+    }
+
     #[test]
     fn test_proper_noun_case_insensitive() {
         // Should match "github actions" and convert to "GitHub Actions"
@@ -1121,6 +1546,14 @@ mod tests {
         assert_eq!(to_sentence_case("Hello", &[], &[]), "Hello");
     }
 
+    #[test]
+    fn test_preserve_wikilinks() {
+        assert_eq!(
+            to_sentence_case("About [[Home Page|Start Here]] Notes", &[], &[]),
+            "About [[Home Page|Start Here]] notes"
+        );
+    }
+
     #[test]
     fn test_code_span_with_quotes() {
         // Code span takes precedence
@@ -1712,4 +2145,101 @@ mod tests {
             "I think therefore I am"
         );
     }
+
+    fn chicago(text: &str) -> String {
+        to_title_case(text, TitleCaseStyle::Chicago, &[], &[])
+    }
+
+    fn ap(text: &str) -> String {
+        to_title_case(text, TitleCaseStyle::Ap, &[], &[])
+    }
+
+    #[test]
+    fn test_title_case_minor_words() {
+        assert_eq!(chicago("the lord of the rings"), "The Lord of the Rings");
+        assert_eq!(chicago("what is it for"), "What Is It For");
+        assert_eq!(
+            chicago("a guide to working with files"),
+            "A Guide to Working with Files"
+        );
+        assert_eq!(
+            ap("a guide to working with files"),
+            "A Guide to Working With Files"
+        );
+        assert_eq!(chicago("fast yet simple"), "Fast Yet Simple");
+        assert_eq!(ap("fast yet simple"), "Fast yet Simple");
+    }
+
+    #[test]
+    fn test_title_case_chicago_prepositions() {
+        assert_eq!(
+            chicago("life after death over the hill like a boss since then until now"),
+            "Life after Death over the Hill like a Boss since Then until Now"
+        );
+        assert_eq!(
+            chicago("before the storm near the end past midnight"),
+            "Before the Storm near the End past Midnight"
+        );
+        assert_eq!(
+            ap("life after death over the hill"),
+            "Life After Death Over the Hill"
+        );
+    }
+
+    #[test]
+    fn test_title_case_after_colon() {
+        assert_eq!(chicago("part 1: the beginning"), "Part 1: The Beginning");
+    }
+
+    #[test]
+    fn test_title_case_hyphenated() {
+        assert_eq!(chicago("an up-to-date guide"), "An Up-to-Date Guide");
+        assert_eq!(chicago("built-in tools"), "Built-in Tools");
+        assert_eq!(chicago("using JSON-RPC"), "Using JSON-RPC");
+    }
+
+    #[test]
+    fn test_title_case_preserves() {
+        assert_eq!(
+            chicago("working with javascript and HTTP APIs"),
+            "Working with JavaScript and HTTP APIs"
+        );
+        assert_eq!(
+            chicago("using the `foo_bar` function in iOS"),
+            "Using the `foo_bar` Function in iOS"
+        );
+        assert_eq!(
+            chicago("deploying to github pages"),
+            "Deploying to GitHub Pages"
+        );
+        assert_eq!(chicago("what i think"), "What I Think");
+        assert_eq!(
+            to_title_case(
+                "about myapp",
+                TitleCaseStyle::Chicago,
+                &["MyApp".to_string()],
+                &[]
+            ),
+            "About MyApp"
+        );
+        assert_eq!(chicago("setting up e.g. hooks"), "Setting Up e.g. Hooks");
+    }
+
+    #[test]
+    fn test_title_case_words_with_digits() {
+        assert_eq!(chicago("release v0.1.1 notes"), "Release v0.1.1 Notes");
+        assert_eq!(
+            chicago("support for x86 and x86-64"),
+            "Support for x86 and x86-64"
+        );
+        assert_eq!(chicago("v2 api"), "v2 Api");
+    }
+
+    #[test]
+    fn test_title_case_quotes() {
+        assert_eq!(
+            chicago("the \"art of war\" in code"),
+            "The \u{201C}Art of War\u{201D} in Code"
+        );
+    }
 }
@@ -0,0 +1,173 @@
+//! Heading level normalization.
+//!
+//! Documents assembled from several sources often start at a deep level or
+//! skip levels.  The levels of the headings can be shifted so that the
+//! shallowest heading is at a given level or so that the first heading
+//! becomes an h1, and gaps in the
+//! hierarchy can be closed.  The new levels are written into the AST before
+//! serialization, so everything that depends on them, such as the choice
+//! between setext and ATX headings, follows.
+
+use comrak::nodes::{AstNode, NodeValue};
+
+use super::Serializer;
+
+impl<'a> Serializer<'a> {
+    /// Rewrite the levels of the headings of the document, including those in
+    /// block quotes and list items, as configured, warning about every
+    /// heading whose level changes or would be shifted out of range.
+    /// Headings in regions where formatting is disabled are left alone.
+    pub(super) fn normalize_heading_levels<'b>(&mut self, document: &'b AstNode<'b>) {
+        if self.options.heading_top_level == 0
+            && !self.options.heading_promote_first
+            && !self.options.heading_fix_skipped_levels
+        {
+            return;
+        }
+        let disabled_ranges = Self::collect_disabled_line_ranges(document);
+        let headings: Vec<_> = document
+            .descendants()
+            .filter(|child| {
+                let data = child.data.borrow();
+                matches!(data.value, NodeValue::Heading(_))
+                    && !Self::is_line_in_disabled_ranges(
+                        data.sourcepos.start.line,
+                        &disabled_ranges,
+                    )
+            })
+            .collect();
+        let levels: Vec<u8> = headings
+            .iter()
+            .map(|heading| match &heading.data.borrow().value {
+                NodeValue::Heading(heading) => heading.level,
+                _ => unreachable!(),
+            })
+            .collect();
+        let offset = shift_offset(
+            &levels,
+            self.options.heading_top_level,
+            self.options.heading_promote_first,
+        );
+        let new_levels = normalize_levels(
+            &levels,
+            self.options.heading_top_level,
+            self.options.heading_promote_first,
+            self.options.heading_fix_skipped_levels,
+        );
+        for ((heading, old), new) in headings.into_iter().zip(levels).zip(new_levels) {
+            let shifted = i16::from(old) + offset;
+            let clamped = !(1..=6).contains(&shifted);
+            if old == new && !clamped {
+                continue;
+            }
+            let mut data = heading.data.borrow_mut();
+            if let NodeValue::Heading(heading) = &mut data.value {
+                heading.level = new;
+            }
+            let line = data.sourcepos.start.line;
+            drop(data);
+            let message = if clamped {
+                format!(
+                    "heading level {} would be shifted to {}, which is out of range; \
+                     using {} instead",
+                    old, shifted, new
+                )
+            } else {
+                format!("heading level changed from {} to {}", old, new)
+            };
+            self.add_warning(line, message);
+        }
+    }
+}
+
+/// Compute the new levels of a sequence of headings.
+///
+/// The levels are first shifted so that the shallowest heading is at
+/// `top_level`, or, if `promote_first` is set, so that the first heading is
+/// an h1, and kept between 1 and 6.  Since both are targets, normalizing
+/// the new levels again changes nothing.
+/// Then, if `fix_skipped` is set, each heading is made at most one level
+/// deeper than the heading it belongs to, so that an h4 right under an h2
+/// becomes an h3, along with the headings under it.
+pub(super) fn normalize_levels(
+    levels: &[u8],
+    top_level: u8,
+    promote_first: bool,
+    fix_skipped: bool,
+) -> Vec<u8> {
+    let offset = shift_offset(levels, top_level, promote_first);
+    // The enclosing headings: their shifted levels and their new levels
+    let mut parents: Vec<(u8, u8)> = Vec::new();
+    levels
+        .iter()
+        .map(|&level| {
+            let shifted = (i16::from(level) + offset).clamp(1, 6) as u8;
+            if !fix_skipped {
+                return shifted;
+            }
+            while parents.last().is_some_and(|&(parent, _)| parent >= shifted) {
+                parents.pop();
+            }
+            let new = parents.last().map_or(shifted, |&(_, parent)| parent + 1);
+            parents.push((shifted, new));
+            new
+        })
+        .collect()
+}
+
+/// The number of levels a sequence of headings is shifted by: whatever
+/// makes the first heading an h1 if `promote_first` is set, or else whatever
+/// makes the shallowest heading an h`top_level`, unless it is 0.
+fn shift_offset(levels: &[u8], top_level: u8, promote_first: bool) -> i16 {
+    match (levels.first(), levels.iter().min()) {
+        (Some(&first), _) if promote_first => 1 - i16::from(first),
+        (_, Some(&shallowest)) if top_level > 0 => {
+            i16::from(top_level.min(6)) - i16::from(shallowest)
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_levels, shift_offset};
+
+    #[test]
+    fn test_top_level() {
+        assert_eq!(normalize_levels(&[1, 2, 1], 2, false, false), vec![2, 3, 2]);
+        assert_eq!(normalize_levels(&[2, 3, 2], 2, false, false), vec![2, 3, 2]);
+        assert_eq!(normalize_levels(&[3, 6], 1, false, false), vec![1, 4]);
+        assert_eq!(normalize_levels(&[2, 1, 6], 2, false, false), vec![3, 2, 6]);
+        assert_eq!(normalize_levels(&[1, 2], 9, false, false), vec![6, 6]);
+        assert_eq!(normalize_levels(&[3, 4], 0, false, false), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_promote_first() {
+        assert_eq!(normalize_levels(&[3, 4, 3], 5, true, false), vec![1, 2, 1]);
+        assert_eq!(normalize_levels(&[], 0, true, false), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_shift_offset() {
+        assert_eq!(shift_offset(&[3, 4], 2, false), -1);
+        assert_eq!(shift_offset(&[4, 3], 5, false), 2);
+        assert_eq!(shift_offset(&[3, 4], 0, false), 0);
+        assert_eq!(shift_offset(&[3, 4], 2, true), -2);
+        assert_eq!(shift_offset(&[], 2, true), 0);
+    }
+
+    #[test]
+    fn test_fix_skipped() {
+        assert_eq!(
+            normalize_levels(&[1, 2, 4, 5, 4, 3, 2, 6], 0, false, true),
+            vec![1, 2, 3, 4, 3, 3, 2, 3]
+        );
+        assert_eq!(normalize_levels(&[3, 5, 2], 0, false, true), vec![3, 4, 2]);
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(normalize_levels(&[3, 5, 4], 0, true, true), vec![1, 2, 2]);
+    }
+}
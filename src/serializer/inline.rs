@@ -3,7 +3,9 @@
 use comrak::nodes::{AstNode, NodeValue};
 
 use super::Serializer;
+use super::attribute;
 use super::escape;
+use super::extension;
 use super::punctuation;
 
 impl<'a> Serializer<'a> {
@@ -37,18 +39,53 @@ impl<'a> Serializer<'a> {
     }
 
     fn collect_text_recursive<'b>(&mut self, node: &'b AstNode<'b>, text: &mut String) {
+        if self.case_masks.is_some() && is_case_protected(&node.data.borrow().value) {
+            let masks = self.case_masks.take();
+            let mut markup = String::new();
+            self.collect_text_recursive(node, &mut markup);
+            self.case_masks = masks;
+
+            // The text of a link can be converted along with the heading,
+            // unless it is also the label, as in `[text]` and `[text][]`
+            let is_link = matches!(node.data.borrow().value, NodeValue::Link(_));
+            if is_link && self.options.heading_sentence_case_links {
+                let link_text = match self.get_reference_style_info(node) {
+                    Some((link_text, _)) => link_text,
+                    None => self.collect_raw_text(node),
+                };
+                let prefix = format!("[{}]", link_text);
+                if let Some(rest) = markup.strip_prefix(&prefix)
+                    && !rest.is_empty()
+                    && rest != "[]"
+                {
+                    text.push_str(&prefix);
+                    self.push_case_protected(text, rest);
+                    return;
+                }
+            }
+            self.push_case_protected(text, &markup);
+            return;
+        }
         match &node.data.borrow().value {
             NodeValue::Text(t) => {
+                // A heading's attribute block is written separately
+                let t = attribute::split_heading_attributes(node, t).map_or(&t[..], |(t, _)| t);
+
                 // Apply punctuation transformation first
                 let transformed = punctuation::transform_punctuation(t, self.options);
 
                 // Try to preserve escapes from the original source
                 if let Some(source) = self.extract_source(node) {
-                    text.push_str(&Self::escape_text_preserving_source(&transformed, &source));
+                    let source = attribute::split_heading_attributes(node, &source)
+                        .map_or(source.as_str(), |(source, _)| source);
+                    text.push_str(&Self::escape_text_preserving_source(&transformed, source));
                 } else {
                     text.push_str(&escape::escape_text(&transformed));
                 }
             }
+            NodeValue::Code(_) if self.is_mdx_expression(node) || self.is_template_tag(node) => {
+                text.push_str(&self.extract_source(node).unwrap_or_default());
+            }
             NodeValue::Code(code) => {
                 // Try to use original source to preserve spacing, but validate it first.
                 // comrak may provide incorrect sourcepos for code spans in table cells
@@ -81,9 +118,33 @@ impl<'a> Serializer<'a> {
                 }
                 text.push_str(delim);
             }
+            value @ (NodeValue::Strikethrough
+            | NodeValue::Superscript
+            | NodeValue::Subscript
+            | NodeValue::Underline
+            | NodeValue::SpoileredText) => {
+                let delimiter = extension::inline_delimiter(value);
+                text.push_str(delimiter);
+                for child in node.children() {
+                    self.collect_text_recursive(child, text);
+                }
+                text.push_str(delimiter);
+            }
+            NodeValue::Math(math) => {
+                text.push_str(&extension::format_math(math));
+            }
+            NodeValue::WikiLink(_) => {
+                text.push_str(&self.format_wikilink(node));
+            }
+            NodeValue::ShortCode(shortcode) => {
+                text.push_str(&extension::format_shortcode(shortcode));
+            }
             NodeValue::SoftBreak => {
                 text.push(' ');
             }
+            NodeValue::Link(_) if self.is_extended_autolink(node) => {
+                text.push_str(&self.collect_raw_text(node));
+            }
             NodeValue::Link(link) => {
                 // Handle reference-style links in headings
                 if let Some((link_text, label)) = self.get_reference_style_info(node) {
@@ -92,7 +153,7 @@ impl<'a> Serializer<'a> {
                     // For inline links, just output plain text (or format as inline?)
                     // In headings, we typically want reference style for external links
                     let link_text = self.collect_raw_text(node);
-                    if Self::is_external_url(&link.url) {
+                    if self.converts_to_reference(&link.url) {
                         // Headings don't have footnote references as siblings, so no need for collapsed style
                         self.format_external_link_as_reference(
                             text,
@@ -100,6 +161,7 @@ impl<'a> Serializer<'a> {
                             &link.url,
                             &link.title,
                             false,
+                            node.data.borrow().sourcepos.start.line,
                         );
                     } else {
                         Self::format_inline_link(text, &link_text, &link.url, &link.title);
@@ -130,10 +192,20 @@ impl<'a> Serializer<'a> {
     }
 
     pub(super) fn collect_inline_node<'b>(&mut self, node: &'b AstNode<'b>, content: &mut String) {
+        if self.case_masks.is_some() && is_case_protected(&node.data.borrow().value) {
+            let masks = self.case_masks.take();
+            let mut markup = String::new();
+            self.collect_inline_node(node, &mut markup);
+            self.case_masks = masks;
+            self.push_case_protected(content, &markup);
+            return;
+        }
         match &node.data.borrow().value {
             NodeValue::Text(text) => {
+                let text = self.lint_proper_nouns(node, text);
+
                 // Apply punctuation transformation first
-                let transformed = punctuation::transform_punctuation(text, self.options);
+                let transformed = punctuation::transform_punctuation(&text, self.options);
 
                 // Try to preserve escapes from the original source
                 if let Some(source) = self.extract_source(node) {
@@ -166,6 +238,32 @@ impl<'a> Serializer<'a> {
                 }
                 content.push_str(delim);
             }
+            value @ (NodeValue::Strikethrough
+            | NodeValue::Superscript
+            | NodeValue::Subscript
+            | NodeValue::Underline
+            | NodeValue::SpoileredText) => {
+                let delimiter = extension::inline_delimiter(value);
+                content.push_str(delimiter);
+                for child in node.children() {
+                    self.collect_inline_node(child, content);
+                }
+                content.push_str(delimiter);
+            }
+            NodeValue::Math(math) => {
+                // Line breaks in math are soft breaks, so that short lines
+                // of display math stay as they are
+                content.push_str(&extension::format_math(math).replace('\n', "\x00"));
+            }
+            NodeValue::WikiLink(_) => {
+                content.push_str(&self.format_wikilink(node));
+            }
+            NodeValue::ShortCode(shortcode) => {
+                content.push_str(&extension::format_shortcode(shortcode));
+            }
+            NodeValue::Code(_) if self.is_mdx_expression(node) || self.is_template_tag(node) => {
+                content.push_str(&self.extract_source(node).unwrap_or_default());
+            }
             NodeValue::Code(code) => {
                 // Try to use original source to preserve spacing, but validate it first.
                 // comrak may provide incorrect sourcepos for code spans in table cells
@@ -182,6 +280,9 @@ impl<'a> Serializer<'a> {
                     content.push_str(&escape::format_code_span(&code.literal));
                 }
             }
+            NodeValue::Link(_) if self.is_extended_autolink(node) => {
+                content.push_str(&self.collect_raw_text(node));
+            }
             NodeValue::Link(link) => {
                 // Check if link contains an image (badge-style link)
                 let contains_image = node
@@ -197,7 +298,10 @@ impl<'a> Serializer<'a> {
                     // Preserve reference style
                     if contains_image {
                         // Badge-style with reference: [![alt][img-ref]][link-ref]
-                        let actual_label = label.strip_prefix('\x01').unwrap_or(&label);
+                        let merged_label = self.merged_label(&label);
+                        let actual_label = merged_label
+                            .as_deref()
+                            .unwrap_or_else(|| label.strip_prefix('\x01').unwrap_or(&label));
                         content.push('[');
                         for child in node.children() {
                             self.collect_inline_node(child, content);
@@ -232,7 +336,7 @@ impl<'a> Serializer<'a> {
                     content.push(')');
                 } else if is_autolink {
                     Self::format_autolink(content, &link.url);
-                } else if Self::is_external_url(&link.url) {
+                } else if self.converts_to_reference(&link.url) {
                     // External URL: collect link text first
                     let mut link_text = String::new();
                     for child in node.children() {
@@ -246,9 +350,10 @@ impl<'a> Serializer<'a> {
                         &link.url,
                         &link.title,
                         use_collapsed,
+                        node.data.borrow().sourcepos.start.line,
                     );
                 } else {
-                    // Relative/local URL: keep as inline link
+                    // Relative/local URL, or external link kept inline as configured
                     let mut link_text = String::new();
                     for child in node.children() {
                         self.collect_inline_node(child, &mut link_text);
@@ -286,13 +391,41 @@ impl<'a> Serializer<'a> {
         }
     }
 
+    /// Push markup that case conversion must leave alone: as is, or as
+    /// a placeholder while the case of the text around it is converted.
+    fn push_case_protected(&mut self, text: &mut String, markup: &str) {
+        match &mut self.case_masks {
+            Some(masks) => {
+                text.push_str(&case_placeholder(masks.len()));
+                masks.push(markup.to_string());
+            }
+            None => text.push_str(markup),
+        }
+    }
+
+    /// Collect text with `collect` while masking the links, images, and other
+    /// markup in it, convert its case with `convert`, which also gets the
+    /// masked markup, and put the markup back.
+    pub(super) fn collect_with_case_conversion(
+        &mut self,
+        collect: impl FnOnce(&mut Self) -> String,
+        convert: impl FnOnce(&mut Self, &str, &[String]) -> String,
+    ) -> String {
+        let outer = self.case_masks.replace(Vec::new());
+        let text = collect(self);
+        let masks = std::mem::replace(&mut self.case_masks, outer).unwrap_or_default();
+        let converted = convert(self, &text, &masks);
+        unmask_case(&converted, &masks)
+    }
+
     /// Escape text while preserving escapes from the original source.
     ///
     /// When comrak parses text like `node\_modules`, it stores `node_modules` in the AST.
     /// This function compares the parsed text with the original source to detect which
     /// characters were escaped, and preserves those escapes in the output.
     ///
-    /// Also preserves HTML entities (e.g., `&lt;`, `&amp;`, `&#60;`) from the source.
+    /// Also preserves HTML entities (e.g., `&lt;`, `&amp;`, `&#60;`) from the source,
+    /// and copies math spans like `$\alpha_1$` from the source verbatim.
     fn escape_text_preserving_source(text: &str, source: &str) -> String {
         let mut result = String::with_capacity(source.len());
         let text_chars: Vec<char> = text.chars().collect();
@@ -305,7 +438,15 @@ impl<'a> Serializer<'a> {
             let text_char = text_chars[text_idx];
             let source_char = source_chars[source_idx];
 
-            if source_char == '\\' && source_idx + 1 < source_chars.len() {
+            if text_char == '$'
+                && source_char == '$'
+                && let Some(text_end) = escape::math_span_end(&text_chars, text_idx)
+                && let Some(source_end) = escape::math_span_end(&source_chars, source_idx)
+            {
+                result.extend(&source_chars[source_idx..=source_end]);
+                text_idx = text_end + 1;
+                source_idx = source_end + 1;
+            } else if source_char == '\\' && source_idx + 1 < source_chars.len() {
                 if text_char == '\\' {
                     // Both source and text have a backslash
                     // Check if source has an escaped backslash (\\)
@@ -460,3 +601,42 @@ impl<'a> Serializer<'a> {
         }
     }
 }
+
+/// Check if case conversion must leave an inline node alone: links, whose
+/// URLs and labels must be kept, and images, math, and the like.
+fn is_case_protected(value: &NodeValue) -> bool {
+    matches!(
+        value,
+        NodeValue::Link(_)
+            | NodeValue::Image(_)
+            | NodeValue::WikiLink(_)
+            | NodeValue::Math(_)
+            | NodeValue::ShortCode(_)
+            | NodeValue::HtmlInline(_)
+            | NodeValue::FootnoteReference(_)
+    )
+}
+
+/// The placeholder for the masked markup at `index`.  It consists of
+/// private use characters, which case conversion leaves alone and takes as
+/// punctuation.
+pub(super) fn case_placeholder(index: usize) -> String {
+    let digits = format!("{:x}", index);
+    let mut placeholder = String::from('\u{E000}');
+    placeholder.extend(
+        digits
+            .chars()
+            .map(|d| char::from_u32(0xE010 + d.to_digit(16).unwrap_or(0)).unwrap_or('\u{E010}')),
+    );
+    placeholder.push('\u{E001}');
+    placeholder
+}
+
+/// Put the masked markup back in place of its placeholders.
+pub(super) fn unmask_case(text: &str, masks: &[String]) -> String {
+    let mut text = text.to_string();
+    for (index, markup) in masks.iter().enumerate() {
+        text = text.replacen(&case_placeholder(index), markup, 1);
+    }
+    text
+}
@@ -3,6 +3,8 @@
 use comrak::nodes::{AstNode, NodeValue};
 
 use super::Serializer;
+use super::reference::normalize_label;
+use crate::DuplicateUrls;
 
 impl<'a> Serializer<'a> {
     /// Format a reference-style link and write to output buffer.
@@ -14,6 +16,25 @@ impl<'a> Serializer<'a> {
         url: &str,
         title: &str,
     ) {
+        // A renumbered label used as the link text, as in `[1]` or `[1][]`,
+        // is renumbered in the text as well
+        let original_label = label.strip_prefix('\x01').unwrap_or(label);
+        let renumbered = if text == original_label {
+            self.renumbered_label(label).map(|new| {
+                let prefix = &label[..label.len() - original_label.len()];
+                (new.to_string(), format!("{}{}", prefix, new))
+            })
+        } else {
+            None
+        };
+        let merged_label;
+        let (text, label) = match &renumbered {
+            Some((new_text, new_label)) => (new_text.as_str(), new_label.as_str()),
+            None => {
+                merged_label = self.merged_label(label);
+                (text, merged_label.as_deref().unwrap_or(label))
+            }
+        };
         if label.starts_with('\x01') {
             // Collapsed reference: [text][]
             let actual_label = label.strip_prefix('\x01').unwrap();
@@ -67,6 +88,10 @@ impl<'a> Serializer<'a> {
     /// If `use_collapsed` is true, outputs `[text][]` (collapsed reference) instead of
     /// `[text]` (shortcut reference). This is needed to disambiguate when the link is
     /// immediately followed by something that starts with `[`, like a footnote reference.
+    ///
+    /// A link to a URL that already has a reference label is checked against
+    /// [`DuplicateUrls`] the same way a duplicate definition in the source is,
+    /// with `line` as the line of the link.
     pub(super) fn format_external_link_as_reference(
         &mut self,
         output: &mut String,
@@ -74,17 +99,66 @@ impl<'a> Serializer<'a> {
         url: &str,
         title: &str,
         use_collapsed: bool,
+        line: usize,
     ) {
         // Normalize: replace SoftBreak markers with spaces for shortcut refs
         let normalized_text = text.replace('\x00', " ");
+        let destination = (url.to_string(), title.to_string());
+        let existing = self.url_labels.get(&destination).cloned();
+        if let Some((label, _)) = &existing
+            && self.options.link_duplicate_urls == DuplicateUrls::Merge
+        {
+            // Reuse the label already pointing to the same URL
+            let label = if use_collapsed && normalized_text == *label {
+                format!("\x01{label}")
+            } else {
+                label.clone()
+            };
+            self.format_reference_link(output, &normalized_text, &label, url, title);
+            return;
+        }
+        let label = self
+            .generated_reference_label(url, title)
+            .or_else(|| Some(self.text_reference_label(&normalized_text, url, title)))
+            .filter(|label| normalize_label(label) != normalize_label(&normalized_text));
+        let reference_label = label.as_deref().unwrap_or(&normalized_text);
+        match existing {
+            Some((first_label, first_line)) => {
+                if normalize_label(&first_label) != normalize_label(reference_label)
+                    && self.options.link_duplicate_urls == DuplicateUrls::Warn
+                {
+                    self.add_warning(
+                        line,
+                        format!(
+                            "duplicate reference URL: [{}] points to the same URL as [{}] on line {}",
+                            reference_label, first_label, first_line
+                        ),
+                    );
+                }
+            }
+            None => {
+                self.url_labels
+                    .insert(destination, (reference_label.to_string(), line));
+            }
+        }
         output.push('[');
         output.push_str(&normalized_text);
         output.push(']');
-        if use_collapsed {
-            output.push_str("[]");
+        match label {
+            Some(label) => {
+                // Full reference: [text][label]
+                output.push('[');
+                output.push_str(&label);
+                output.push(']');
+                self.add_reference(label, url.to_string(), title.to_string());
+            }
+            None => {
+                if use_collapsed {
+                    output.push_str("[]");
+                }
+                self.add_reference(normalized_text, url.to_string(), title.to_string());
+            }
         }
-
-        self.add_reference(normalized_text, url.to_string(), title.to_string());
     }
 
     /// Check if the next sibling of a node starts with `[`.
@@ -110,6 +184,8 @@ impl<'a> Serializer<'a> {
         url: &str,
         title: &str,
     ) {
+        let merged_label = self.merged_label(label);
+        let label = merged_label.as_deref().unwrap_or(label);
         if label.starts_with('\x01') {
             // Collapsed reference: ![alt][]
             let actual_label = label.strip_prefix('\x01').unwrap();
@@ -171,7 +247,10 @@ impl<'a> Serializer<'a> {
                     self.serialize_node(child);
                 }
                 self.output.push_str("][");
-                let actual_label = label.strip_prefix('\x01').unwrap_or(&label);
+                let merged_label = self.merged_label(&label);
+                let actual_label = merged_label
+                    .as_deref()
+                    .unwrap_or_else(|| label.strip_prefix('\x01').unwrap_or(&label));
                 self.output.push_str(actual_label);
                 self.output.push(']');
                 self.add_reference(actual_label.to_string(), url.to_string(), title.to_string());
@@ -197,7 +276,7 @@ impl<'a> Serializer<'a> {
             self.output.push(')');
         } else if is_autolink {
             Self::format_autolink(&mut self.output, url);
-        } else if Self::is_external_url(url) {
+        } else if self.converts_to_reference(url) {
             let link_text = self.collect_text(node);
             let mut output = String::new();
             let use_collapsed = Self::next_sibling_starts_with_bracket(node);
@@ -207,10 +286,11 @@ impl<'a> Serializer<'a> {
                 url,
                 title,
                 use_collapsed,
+                node.data.borrow().sourcepos.start.line,
             );
             self.output.push_str(&output);
         } else {
-            // Relative/local URL: keep as inline link
+            // Relative/local URL, or external link kept inline as configured
             let link_text = self.collect_text(node);
             Self::format_inline_link(&mut self.output, &link_text, url, title);
         }
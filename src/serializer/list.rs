@@ -239,10 +239,10 @@ impl<'a> Serializer<'a> {
                     if !is_first {
                         // Check if previous child ends with a newline (code blocks, nested lists)
                         let prev_ends_with_newline = i > 0
-                            && matches!(
+                            && (matches!(
                                 &children[i - 1].data.borrow().value,
                                 NodeValue::CodeBlock(_) | NodeValue::List(_)
-                            );
+                            ) || is_container_fence(children[i - 1]));
                         if prev_ends_with_newline {
                             // Previous element already ends with \n, so just add one more \n
                             self.output.push('\n');
@@ -258,18 +258,34 @@ impl<'a> Serializer<'a> {
                     self.serialize_node(child);
                 }
                 NodeValue::CodeBlock(code_block) => {
-                    // Code blocks in list items need blank line and indentation
-                    self.output.push_str("\n\n");
-                    if self.in_block_quote {
-                        self.output.push_str("> ");
+                    // Code blocks in list items need blank line and indentation,
+                    // except as the first block, which starts on the marker line
+                    if !is_first {
+                        self.output.push_str("\n\n");
+                        if self.in_block_quote {
+                            self.output.push_str("> ");
+                        }
+                        self.output.push_str(&base_indent);
                     }
-                    self.output.push_str(&base_indent);
                     self.serialize_code_block_indented(
                         &code_block.info,
                         &code_block.literal,
                         &base_indent,
                     );
                 }
+                NodeValue::HtmlBlock(html_block) if is_container_fence(child) => {
+                    // Container fences in list items are separated like paragraphs
+                    if !is_first {
+                        let prev_ends_with_newline = is_container_fence(children[i - 1]);
+                        self.output
+                            .push_str(if prev_ends_with_newline { "\n" } else { "\n\n" });
+                        if self.in_block_quote {
+                            self.output.push_str("> ");
+                        }
+                        self.output.push_str(&base_indent);
+                    }
+                    self.serialize_container_fence(child, &html_block.literal);
+                }
                 NodeValue::BlockQuote | NodeValue::Alert(_) => {
                     // Block quotes and alerts in list items need blank line
                     // The indentation is handled by the blockquote/alert serialization itself
@@ -302,10 +318,19 @@ impl<'a> Serializer<'a> {
                     | NodeValue::CodeBlock(_)
                     | NodeValue::BlockQuote
                     | NodeValue::Alert(_)
-            )
+            ) || is_container_fence(child)
         });
         if !last_child_ends_with_newline {
             self.output.push('\n');
         }
     }
 }
+
+/// Check if a node stands for a container fence (see [`crate::container`]).
+fn is_container_fence<'b>(node: &'b AstNode<'b>) -> bool {
+    matches!(
+        &node.data.borrow().value,
+        NodeValue::HtmlBlock(html_block)
+            if crate::container::masked_fence_length(&html_block.literal).is_some()
+    )
+}
@@ -0,0 +1,19 @@
+//! Serialization of the MDX-specific parts of a document.
+//!
+//! These are masked before parsing (see [`crate::mdx`]), so they are
+//! written back from the original source (see also
+//! [`Serializer::serialize_source_lines`]).
+
+use comrak::nodes::AstNode;
+
+use super::Serializer;
+
+impl<'a> Serializer<'a> {
+    /// Check if a code span stands for an inline MDX `{expression}`.
+    pub(super) fn is_mdx_expression<'b>(&self, node: &'b AstNode<'b>) -> bool {
+        self.options.extension_mdx
+            && self
+                .extract_source(node)
+                .is_some_and(|source| source.starts_with('{'))
+    }
+}
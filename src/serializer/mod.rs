@@ -1,18 +1,30 @@
 //! Serializer for converting comrak AST to formatted Markdown.
 
+pub(crate) mod anchor;
+mod attribute;
 mod block;
 mod code;
 mod document;
 mod escape;
+mod extension;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod formatter;
+mod front_matter;
 pub mod heading;
+mod heading_level;
 mod inline;
 mod link;
 mod list;
+mod mdx;
+mod numbering;
+mod proper_noun;
 pub mod punctuation;
+mod reference;
+mod slug;
 mod state;
 mod table;
+mod template;
+mod toc;
 mod wrap;
 
 #[cfg(feature = "wasm")]
@@ -22,7 +34,7 @@ pub use state::{ReferenceLink, Serializer, Warning};
 use comrak::nodes::{AstNode, NodeValue};
 use unicode_width::UnicodeWidthStr;
 
-use crate::Options;
+use crate::{Options, ReferenceOrder};
 
 /// Result of serialization including output and any warnings.
 pub struct SerializeResult {
@@ -30,6 +42,9 @@ pub struct SerializeResult {
     pub output: String,
     /// Warnings generated during formatting.
     pub warnings: Vec<Warning>,
+    /// Capitalized words in headings that sentence case would lowercase,
+    /// except for common words, collected in sentence case report mode.
+    pub unknown_words: Vec<String>,
 }
 
 /// Serializes a comrak AST node to a formatted Markdown string,
@@ -52,13 +67,25 @@ pub fn serialize_with_source_and_warnings<'a>(
     let source_lines: Vec<&str> = source.map(|s| s.lines().collect()).unwrap_or_default();
     let source_ends_with_newline = source.is_some_and(|s| s.ends_with('\n'));
     let mut serializer = Serializer::new(options, source_lines, source_ends_with_newline);
+    serializer.front_matter = source.and_then(crate::front_matter::detect);
     serializer.serialize_node(node);
     SerializeResult {
         output: serializer.output,
         warnings: serializer.warnings,
+        unknown_words: serializer.unknown_words,
     }
 }
 
+/// Collects the anchors a document defines: the GitHub-style slugs of its
+/// headings and the `id` and `name` attributes in its raw HTML.
+pub fn collect_anchors<'a>(
+    node: &'a AstNode<'a>,
+    options: &Options,
+) -> std::collections::HashSet<String> {
+    let serializer = Serializer::new(options, Vec::new(), false);
+    serializer.document_anchors(node)
+}
+
 /// Serializes with a code formatter callback (WASM only).
 #[cfg(feature = "wasm")]
 pub fn serialize_with_code_formatter<'a>(
@@ -75,10 +102,12 @@ pub fn serialize_with_code_formatter<'a>(
         source_ends_with_newline,
         code_formatter,
     );
+    serializer.front_matter = source.and_then(crate::front_matter::detect);
     serializer.serialize_node(node);
     SerializeResult {
         output: serializer.output,
         warnings: serializer.warnings,
+        unknown_words: serializer.unknown_words,
     }
 }
 
@@ -183,7 +212,7 @@ impl<'a> Serializer<'a> {
 
         // Take ownership of references to avoid borrow issues
         // Filter out references that have already been emitted
-        let refs: Vec<ReferenceLink> = self
+        let mut refs: Vec<ReferenceLink> = self
             .pending_references
             .values()
             .filter(|r| !self.emitted_references.contains(&r.label))
@@ -195,48 +224,35 @@ impl<'a> Serializer<'a> {
             return;
         }
 
-        // Count numeric references to decide sorting strategy
-        let numeric_count = refs
-            .iter()
-            .filter(|r| Self::extract_numeric_label(&r.label).is_some())
-            .count();
-
-        self.ensure_blank_line();
-
-        if numeric_count < 2 {
-            // Less than 2 numeric refs: output all in insertion order
-            for reference in &refs {
-                Self::write_reference(&mut self.output, reference);
-                self.emitted_references.insert(reference.label.clone());
-            }
-        } else {
-            // 2+ numeric refs: separate, sort numeric ones, output regular first
-            let mut regular_refs: Vec<&ReferenceLink> = Vec::new();
-            let mut numeric_refs: Vec<(u64, &ReferenceLink)> = Vec::new();
-
-            for reference in &refs {
-                if let Some(num) = Self::extract_numeric_label(&reference.label) {
-                    numeric_refs.push((num, reference));
-                } else {
-                    regular_refs.push(reference);
+        match self.options.link_reference_order {
+            ReferenceOrder::Auto => {
+                // With 2+ numeric refs, output regular ones first (in
+                // insertion order), then numeric ones sorted by number
+                let numeric_count = refs
+                    .iter()
+                    .filter(|r| Self::extract_numeric_label(&r.label).is_some())
+                    .count();
+                if numeric_count >= 2 {
+                    refs.sort_by_key(|r| Self::extract_numeric_label(&r.label));
                 }
             }
-
-            // Sort numeric references by their numeric value
-            numeric_refs.sort_by_key(|(num, _)| *num);
-
-            // Output regular references first (in insertion order)
-            for reference in regular_refs {
-                Self::write_reference(&mut self.output, reference);
-                self.emitted_references.insert(reference.label.clone());
+            ReferenceOrder::Appearance => {}
+            ReferenceOrder::Alphabetical => {
+                refs.sort_by_cached_key(|r| r.label.to_lowercase());
             }
-
-            // Output numeric references (sorted by number)
-            for (_, reference) in numeric_refs {
-                Self::write_reference(&mut self.output, reference);
-                self.emitted_references.insert(reference.label.clone());
+            ReferenceOrder::NumericThenAlpha => {
+                refs.sort_by_cached_key(|r| match Self::extract_numeric_label(&r.label) {
+                    Some(num) => (false, num, String::new()),
+                    None => (true, 0, r.label.to_lowercase()),
+                });
             }
         }
+
+        self.ensure_blank_line();
+        for reference in &refs {
+            Self::write_reference(&mut self.output, reference);
+            self.emitted_references.insert(reference.label.clone());
+        }
     }
 
     /// Extract numeric value from a reference label like "123" or "#123"
@@ -345,11 +361,6 @@ impl<'a> Serializer<'a> {
         name.parse::<u64>().ok()
     }
 
-    /// Output all pending footnote definitions
-    fn flush_footnotes(&mut self) {
-        self.flush_footnotes_before(None);
-    }
-
     /// Output pending footnote reference definitions whose parent footnote was referenced
     /// before the given line. If `before_line` is None, flush all pending footnote references.
     fn flush_footnote_references_before(&mut self, before_line: Option<usize>) {
@@ -394,9 +405,15 @@ impl<'a> Serializer<'a> {
         }
     }
 
-    /// Output all pending footnote reference definitions.
-    fn flush_footnote_references(&mut self) {
-        self.flush_footnote_references_before(None);
+    /// Output the pending footnotes, reference definitions, and footnote
+    /// reference definitions used before the given line.  Footnotes come
+    /// before link reference definitions.  If `before_line` is None, flush
+    /// everything, including kept unused reference definitions.
+    fn flush_definitions_before(&mut self, before_line: Option<usize>) {
+        self.flush_footnotes_before(before_line);
+        self.queue_unused_references_before(before_line);
+        self.flush_references();
+        self.flush_footnote_references_before(before_line);
     }
 
     /// Write a single footnote definition to output, wrapping at line_width
@@ -526,6 +543,17 @@ impl<'a> Serializer<'a> {
             NodeValue::BlockQuote => {
                 self.serialize_block_quote(node);
             }
+            NodeValue::HtmlBlock(html_block)
+                if crate::mdx::is_masked_block(&html_block.literal)
+                    || crate::template::is_masked_block(&html_block.literal) =>
+            {
+                self.serialize_source_lines(node);
+            }
+            NodeValue::HtmlBlock(html_block)
+                if crate::container::masked_fence_length(&html_block.literal).is_some() =>
+            {
+                self.serialize_container_fence(node, &html_block.literal);
+            }
             NodeValue::HtmlBlock(html_block) => {
                 // Preserve HTML blocks (like comments) as-is
                 self.output.push_str(&html_block.literal);
@@ -611,6 +639,10 @@ impl<'a> Serializer<'a> {
                 self.serialize_children(node);
                 self.output.push_str("**");
             }
+            NodeValue::Code(_) if self.is_mdx_expression(node) || self.is_template_tag(node) => {
+                self.output
+                    .push_str(&self.extract_source(node).unwrap_or_default());
+            }
             NodeValue::Code(code) => {
                 // Try to use original source to preserve spacing
                 if let Some(source) = self.extract_source(node) {
@@ -620,6 +652,27 @@ impl<'a> Serializer<'a> {
                         .push_str(&escape::format_code_span(&code.literal));
                 }
             }
+            value @ (NodeValue::Strikethrough
+            | NodeValue::Superscript
+            | NodeValue::Subscript
+            | NodeValue::Underline
+            | NodeValue::SpoileredText) => {
+                let delimiter = extension::inline_delimiter(value);
+                self.output.push_str(delimiter);
+                self.serialize_children(node);
+                self.output.push_str(delimiter);
+            }
+            NodeValue::Math(math) => {
+                self.output.push_str(&extension::format_math(math));
+            }
+            NodeValue::ShortCode(shortcode) => {
+                self.output
+                    .push_str(&extension::format_shortcode(shortcode));
+            }
+            NodeValue::Link(_) if self.is_extended_autolink(node) => {
+                let text = self.collect_raw_text(node);
+                self.output.push_str(&text);
+            }
             NodeValue::Link(link) => {
                 self.serialize_link(node, &link.url, &link.title);
             }
@@ -0,0 +1,162 @@
+//! Section numbering of headings.
+//!
+//! Specifications number their sections hierarchically, as in
+//! `2.3.1 Transport`.  When numbering is enabled, the numbers are computed
+//! anew from the structure of the document on each format, and any number
+//! already at the start of a heading is replaced rather than kept, so that
+//! they stay correct when sections move.
+
+use std::collections::HashMap;
+
+use comrak::nodes::{AstNode, NodeValue};
+
+use super::Serializer;
+use super::attribute;
+
+impl<'a> Serializer<'a> {
+    /// Compute the section numbers of the top-level headings of a document
+    /// whose levels are in the configured range, keyed by their source
+    /// lines.  Headings marked `.unnumbered` get no number and do not count.
+    /// Numbers follow the headings that are there, so a heading right under
+    /// one two levels above it gets the next part, and no part is zero.
+    pub(super) fn number_headings<'b>(&self, document: &'b AstNode<'b>) -> HashMap<usize, String> {
+        let mut numbers = HashMap::new();
+        if !self.options.heading_numbering {
+            return numbers;
+        }
+        let min_level = self.options.heading_numbering_min_level.clamp(1, 6);
+        let max_level = self.options.heading_numbering_max_level.clamp(1, 6);
+        // The enclosing numbered headings: their levels and their counters
+        let mut sections: Vec<(u8, usize)> = Vec::new();
+        for child in document.children() {
+            let level = match &child.data.borrow().value {
+                NodeValue::Heading(heading) => heading.level,
+                _ => continue,
+            };
+            if level < min_level {
+                // A heading above the range starts the numbering over
+                sections.clear();
+                continue;
+            }
+            if level > max_level || attribute::is_unnumbered(child) {
+                continue;
+            }
+            let mut previous = None;
+            while sections.last().is_some_and(|&(last, _)| last > level) {
+                previous = sections.pop();
+            }
+            match sections.last_mut() {
+                Some((last, counter)) if *last == level => *counter += 1,
+                // A sibling that was deeper than this heading still counts
+                _ => sections.push((level, previous.map_or(1, |(_, counter)| counter + 1))),
+            }
+            let number: Vec<String> = sections
+                .iter()
+                .map(|(_, counter)| counter.to_string())
+                .collect();
+            numbers.insert(child.data.borrow().sourcepos.start.line, number.join("."));
+        }
+        numbers
+    }
+
+    /// Apply the configured case to the text of a heading, and put its
+    /// section number in front of it in place of any number it starts with.
+    pub(super) fn format_heading_text<'b>(&self, node: &'b AstNode<'b>, text: &str) -> String {
+        let line = node.data.borrow().sourcepos.start.line;
+        match self.heading_numbers.get(&line) {
+            Some(number) => {
+                let replace_plain = self.options.heading_numbering_replace_plain;
+                let text = self.apply_heading_case(strip_number(text, number, replace_plain));
+                if text.is_empty() {
+                    number.clone()
+                } else {
+                    format!("{} {}", number, text)
+                }
+            }
+            None => self.apply_heading_case(text),
+        }
+    }
+}
+
+/// Strip an old section number and the whitespace after it from the start
+/// of heading text, whose new section number is `number`.  Only numbers
+/// shaped like section numbers are taken as one, so that headings starting
+/// with a year or a version keep it: numbers with a trailing period such as
+/// `2.` or `2.3.` (possibly escaped) and dotted numbers such as `2.3.1`, none
+/// of whose parts is zero or starts with one.  A plain number such as `3`
+/// may well be part of the text, as in `3 ways to win`, so it is only taken
+/// as one if `number` is also plain and either the same or `replace_plain`
+/// is set.
+fn strip_number<'t>(text: &'t str, number: &str, replace_plain: bool) -> &'t str {
+    let mut rest = text;
+    let mut parts: Vec<&str> = Vec::new();
+    let trailing_period = loop {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 || rest.starts_with('0') {
+            return text;
+        }
+        parts.push(&rest[..digits]);
+        rest = &rest[digits..];
+        let separator = if rest.starts_with('.') {
+            1
+        } else if rest.starts_with("\\.") {
+            2
+        } else {
+            0
+        };
+        rest = &rest[separator..];
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            break separator > 0;
+        }
+        if separator == 0 {
+            return text;
+        }
+    };
+    let is_number = trailing_period
+        || parts.len() > 1
+        || !number.contains('.') && (replace_plain || parts[0] == number);
+    if is_number { rest.trim_start() } else { text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_number;
+
+    #[test]
+    fn test_strip_number() {
+        assert_eq!(strip_number("2.3.1 Transport", "2.3.1", false), "Transport");
+        assert_eq!(strip_number("2.3. Transport", "1", false), "Transport");
+        assert_eq!(
+            strip_number("1\\. Introduction", "1", false),
+            "Introduction"
+        );
+        assert_eq!(strip_number("2 Transport", "2", false), "Transport");
+        assert_eq!(strip_number("12", "12", false), "");
+        assert_eq!(strip_number("3D graphics", "1", true), "3D graphics");
+        assert_eq!(strip_number("1.5x faster", "1", true), "1.5x faster");
+        assert_eq!(strip_number("Transport", "1", true), "Transport");
+    }
+
+    #[test]
+    fn test_strip_number_of_any_depth() {
+        assert_eq!(strip_number("1.1 Intro", "1", false), "Intro");
+        assert_eq!(strip_number("1.1.1 Detail", "1.1", false), "Detail");
+        assert_eq!(strip_number("2.3 Transport", "1.2.1", false), "Transport");
+    }
+
+    #[test]
+    fn test_strip_number_plain() {
+        assert_eq!(strip_number("3 Ways to win", "1", false), "3 Ways to win");
+        assert_eq!(strip_number("3 Ways to win", "3", false), "Ways to win");
+        assert_eq!(strip_number("3 Ways to win", "1", true), "Ways to win");
+        assert_eq!(strip_number("3 Ways to win", "1.2", true), "3 Ways to win");
+    }
+
+    #[test]
+    fn test_strip_number_keeps_years_and_versions() {
+        assert_eq!(strip_number("2023 Results", "1", false), "2023 Results");
+        assert_eq!(strip_number("1.0 Release", "1", true), "1.0 Release");
+        assert_eq!(strip_number("1.0 Release", "1.1", true), "1.0 Release");
+        assert_eq!(strip_number("05 Notes", "1", true), "05 Notes");
+    }
+}
@@ -0,0 +1,178 @@
+//! Proper noun checks of the body text.
+//!
+//! The built-in proper nouns, along with the configured and directive
+//! proper nouns and common nouns, are also used to find proper nouns written
+//! in the wrong case in the body text, such as `Javascript` or `github`.
+//! Only text is checked, so code spans, code blocks, and URLs are left
+//! alone.  Many proper nouns, like _Go_ or _Turkey_, are also common words,
+//! so a word written in lowercase is only reported if the proper noun has
+//! capitals after its first letter, as in _GitHub_.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use comrak::nodes::AstNode;
+
+use super::Serializer;
+use super::heading::PROPER_NOUNS;
+use crate::ProperNounLint;
+
+impl<'a> Serializer<'a> {
+    /// Check a text node for proper nouns written in the wrong case, warning
+    /// about each, and return the text with them fixed if so configured.
+    pub(super) fn lint_proper_nouns<'t, 'b>(
+        &mut self,
+        node: &'b AstNode<'b>,
+        text: &'t str,
+    ) -> Cow<'t, str> {
+        if self.options.lint_proper_nouns == ProperNounLint::Off {
+            return Cow::Borrowed(text);
+        }
+        let index = match &self.proper_noun_index {
+            Some(index) => index,
+            None => {
+                let (proper_nouns, common_nouns) = self.heading_nouns();
+                self.proper_noun_index
+                    .insert(build_index(&proper_nouns, &common_nouns))
+            }
+        };
+        let misspellings = find_misspellings(text, index);
+        if misspellings.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        let fix = self.options.lint_proper_nouns == ProperNounLint::Fix;
+        let line = node.data.borrow().sourcepos.start.line;
+        let mut fixed = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, canonical) in misspellings {
+            let word = &text[start..end];
+            let message = if fix {
+                format!("proper noun \"{}\" changed to \"{}\"", word, canonical)
+            } else {
+                format!("proper noun \"{}\" should be \"{}\"", word, canonical)
+            };
+            self.add_warning(line, message);
+            fixed.push_str(&text[last..start]);
+            fixed.push_str(&canonical);
+            last = end;
+        }
+        fixed.push_str(&text[last..]);
+        if fix {
+            Cow::Owned(fixed)
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+}
+
+/// Map the lowercase forms of the single-word proper nouns to their
+/// canonical forms.  Proper nouns given by the user take precedence over
+/// built-in ones, and common nouns take precedence over both.
+fn build_index(proper_nouns: &[String], common_nouns: &[String]) -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    let builtin = PROPER_NOUNS.iter().map(|(canonical, _, _)| *canonical);
+    for noun in builtin.chain(proper_nouns.iter().map(String::as_str)) {
+        if !noun.contains(char::is_whitespace) {
+            index.insert(key(noun), noun.to_string());
+        }
+    }
+    for noun in common_nouns {
+        index.remove(&key(noun));
+    }
+    index
+}
+
+/// The key a word is looked up by: lowercased, with curly apostrophes.
+fn key(word: &str) -> String {
+    word.to_lowercase().replace('\'', "\u{2019}")
+}
+
+/// Find the words of `text` that are proper nouns in the `index` written in
+/// the wrong case, as their byte ranges along with their canonical forms.
+fn find_misspellings(text: &str, index: &HashMap<String, String>) -> Vec<(usize, usize, String)> {
+    let mut misspellings = Vec::new();
+    let mut offset = 0;
+    for token in text.split_inclusive(char::is_whitespace) {
+        let token_start = offset;
+        offset += token.len();
+        let start = token_start + token.len()
+            - token
+                .trim_start_matches(|c: char| !c.is_alphanumeric())
+                .len();
+        let trimmed = token.trim_end_matches(|c: char| !c.is_alphanumeric());
+        let trimmed = trimmed
+            .strip_suffix("'s")
+            .or_else(|| trimmed.strip_suffix("\u{2019}s"))
+            .unwrap_or(trimmed);
+        let end = token_start + trimmed.len();
+        if start >= end {
+            continue;
+        }
+        let word = &text[start..end];
+        // Paths, URLs, e-mail addresses, and identifiers are not prose
+        if word.contains(['/', ':', '@', '_', '\\']) {
+            continue;
+        }
+        let Some(canonical) = index.get(&key(word)) else {
+            continue;
+        };
+        if is_misspelling(word, canonical) {
+            let canonical = if word.contains('\'') {
+                canonical.replace('\u{2019}', "'")
+            } else {
+                canonical.clone()
+            };
+            misspellings.push((start, end, canonical));
+        }
+    }
+    misspellings
+}
+
+/// Check if `word` is `canonical` written in the wrong case.  Words in all
+/// capitals are taken as deliberate, and words in lowercase are only wrong
+/// if the canonical form has capitals after its first letter.
+fn is_misspelling(word: &str, canonical: &str) -> bool {
+    let canonical_curly = canonical.replace('\'', "\u{2019}");
+    if word.replace('\'', "\u{2019}") == canonical_curly {
+        return false;
+    }
+    let has_upper = word.chars().any(char::is_uppercase);
+    let has_lower = word.chars().any(char::is_lowercase);
+    if has_upper && !has_lower && word.chars().filter(|c| c.is_alphabetic()).count() > 1 {
+        return false;
+    }
+    has_upper || canonical.chars().skip(1).any(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn misspellings(text: &str) -> Vec<(String, String)> {
+        let index = build_index(&["MyAPI".to_string()], &["Deno".to_string()]);
+        find_misspellings(text, &index)
+            .into_iter()
+            .map(|(start, end, canonical)| (text[start..end].to_string(), canonical))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_misspellings() {
+        assert_eq!(
+            misspellings("Use Javascript (or typescript) on github's CI, with myapi."),
+            vec![
+                ("Javascript".to_string(), "JavaScript".to_string()),
+                ("typescript".to_string(), "TypeScript".to_string()),
+                ("github".to_string(), "GitHub".to_string()),
+                ("myapi".to_string(), "MyAPI".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_misspellings_ignores_correct_and_ambiguous_words() {
+        assert!(misspellings("Let's go to GitHub and write some rust.").is_empty());
+        assert!(misspellings("JAVASCRIPT is loud, deno is common here.").is_empty());
+        assert!(misspellings("See github.com/foo or a@github or my_github.").is_empty());
+    }
+}
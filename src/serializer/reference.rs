@@ -0,0 +1,433 @@
+//! Analysis of the reference definitions written in the source.
+//!
+//! comrak resolves reference links while parsing and drops the definitions
+//! from the AST, so unused, duplicate, and conflicting definitions can only
+//! be found by looking at the source lines.
+
+use std::collections::{HashMap, HashSet};
+
+use comrak::nodes::{AstNode, NodeValue};
+use regex::Regex;
+
+use super::Serializer;
+use super::escape;
+use super::state::{ReferenceLink, url_host};
+use crate::{ConflictingLabels, DuplicateUrls, ReferenceLabel, UnusedReferences};
+
+/// A reference definition found in the source.
+#[derive(Debug, Clone)]
+pub struct SourceDefinition {
+    /// Line number of the definition (1-indexed).
+    pub line: usize,
+    /// The label as written.
+    pub label: String,
+    /// The destination as written, without angle brackets.
+    pub url: String,
+    /// The title without its delimiters, or an empty string.
+    pub title: String,
+}
+
+impl<'a> Serializer<'a> {
+    /// Collect the single-line reference definitions of the source, skipping
+    /// code blocks, HTML blocks, and front matter.
+    pub(super) fn collect_source_definitions<'b>(
+        &self,
+        document: &'b AstNode<'b>,
+    ) -> Vec<SourceDefinition> {
+        let def_pattern = Regex::new(
+            r#"^ {0,3}\[((?:[^\]\\]|\\.)+)\]:[ \t]*(?:<([^>]*)>|(\S+))(?:[ \t]+(?:"([^"]*)"|'([^']*)'|\(([^)]*)\)))?[ \t]*$"#,
+        )
+        .unwrap();
+
+        let mut verbatim_ranges = Vec::new();
+        for node in document.descendants() {
+            if matches!(
+                node.data.borrow().value,
+                NodeValue::CodeBlock(_) | NodeValue::HtmlBlock(_) | NodeValue::FrontMatter(_)
+            ) {
+                let sourcepos = node.data.borrow().sourcepos;
+                verbatim_ranges.push((sourcepos.start.line, sourcepos.end.line));
+            }
+        }
+
+        let mut definitions = Vec::new();
+        for (i, line) in self.source_lines.iter().enumerate() {
+            let line_num = i + 1;
+            if Self::is_line_in_disabled_ranges(line_num, &verbatim_ranges) {
+                continue;
+            }
+            let Some(caps) = def_pattern.captures(line) else {
+                continue;
+            };
+            // Footnote definitions look like reference definitions
+            if caps[1].starts_with('^') {
+                continue;
+            }
+            let group = |i: usize| caps.get(i).map(|m| m.as_str().to_string());
+            definitions.push(SourceDefinition {
+                line: line_num,
+                label: caps[1].to_string(),
+                url: group(2).or_else(|| group(3)).unwrap_or_default(),
+                title: group(4)
+                    .or_else(|| group(5))
+                    .or_else(|| group(6))
+                    .unwrap_or_default(),
+            });
+        }
+        definitions
+    }
+
+    /// Look for unused, duplicate, and conflicting reference definitions,
+    /// emitting warnings and preparing label merges and kept definitions as
+    /// configured.
+    pub(super) fn analyze_reference_definitions<'b>(&mut self, document: &'b AstNode<'b>) {
+        if self.source_lines.is_empty() {
+            return;
+        }
+
+        let disabled_ranges = Self::collect_disabled_line_ranges(document);
+        let definitions: Vec<SourceDefinition> = self
+            .collect_source_definitions(document)
+            .into_iter()
+            .filter(|def| !Self::is_line_in_disabled_ranges(def.line, &disabled_ranges))
+            .collect();
+        let used_labels = self.collect_used_labels(document);
+
+        // The first definition of each label is the one CommonMark uses
+        let mut first_by_label: HashMap<String, &SourceDefinition> = HashMap::new();
+        // The first label defined for each destination
+        let mut first_by_url: HashMap<(&str, &str), &SourceDefinition> = HashMap::new();
+        let mut warnings = Vec::new();
+        let mut aliases = HashMap::new();
+        let mut unused = Vec::new();
+
+        for def in &definitions {
+            let key = normalize_label(&def.label);
+            if let Some(first) = first_by_label.get(&key) {
+                if (first.url.as_str(), first.title.as_str())
+                    != (def.url.as_str(), def.title.as_str())
+                    && self.options.link_conflicting_labels == ConflictingLabels::Warn
+                {
+                    warnings.push((
+                        def.line,
+                        format!(
+                            "conflicting reference definition: [{}] is already defined on line {} with a different URL",
+                            def.label, first.line
+                        ),
+                    ));
+                }
+                continue;
+            }
+            first_by_label.insert(key.clone(), def);
+
+            if let Some(first) = first_by_url.get(&(def.url.as_str(), def.title.as_str())) {
+                match self.options.link_duplicate_urls {
+                    DuplicateUrls::Keep => {}
+                    DuplicateUrls::Warn => warnings.push((
+                        def.line,
+                        format!(
+                            "duplicate reference URL: [{}] points to the same URL as [{}] on line {}",
+                            def.label, first.label, first.line
+                        ),
+                    )),
+                    DuplicateUrls::Merge => {
+                        aliases.insert(key, first.label.clone());
+                        continue;
+                    }
+                }
+            } else {
+                first_by_url.insert((def.url.as_str(), def.title.as_str()), def);
+            }
+
+            if !used_labels.contains(&key) {
+                match self.options.link_unused_references {
+                    UnusedReferences::Remove => {}
+                    UnusedReferences::Keep => unused.push(def.clone()),
+                    UnusedReferences::Warn => {
+                        warnings.push((
+                            def.line,
+                            format!("unused reference definition: [{}]", def.label),
+                        ));
+                        unused.push(def.clone());
+                    }
+                }
+            }
+        }
+
+        for (line, message) in warnings {
+            self.add_warning(line, message);
+        }
+        self.reference_aliases = aliases;
+        self.url_labels = first_by_url
+            .into_iter()
+            .map(|((url, title), def)| {
+                (
+                    (url.to_string(), title.to_string()),
+                    (def.label.clone(), def.line),
+                )
+            })
+            .collect();
+        self.unused_definitions = unused
+            .into_iter()
+            .map(|def| {
+                (
+                    def.line,
+                    ReferenceLink {
+                        label: def.label,
+                        url: def.url,
+                        title: def.title,
+                    },
+                )
+            })
+            .collect();
+    }
+
+    /// Collect the normalized labels of all reference-style links and images.
+    fn collect_used_labels<'b>(&self, document: &'b AstNode<'b>) -> HashSet<String> {
+        document
+            .descendants()
+            .filter(|node| {
+                matches!(
+                    node.data.borrow().value,
+                    NodeValue::Link(_) | NodeValue::Image(_)
+                )
+            })
+            .filter_map(|node| self.get_reference_style_info(node))
+            .map(|(_, label)| normalize_label(label.strip_prefix('\x01').unwrap_or(&label)))
+            .collect()
+    }
+
+    /// Queue the kept unused definitions that appear before `line` (or all of
+    /// them if `line` is `None`) so that the next flush emits them.
+    pub(super) fn queue_unused_references_before(&mut self, line: Option<usize>) {
+        let split = match line {
+            Some(line) => self
+                .unused_definitions
+                .iter()
+                .position(|(def_line, _)| *def_line >= line)
+                .unwrap_or(self.unused_definitions.len()),
+            None => self.unused_definitions.len(),
+        };
+        for (_, reference) in self.unused_definitions.drain(..split).collect::<Vec<_>>() {
+            self.add_reference(reference.label.clone(), reference.url, reference.title);
+        }
+    }
+
+    /// Record the labels the source defines, so that labels generated for
+    /// external links do not clash with them.
+    pub(super) fn reserve_source_labels<'b>(&mut self, document: &'b AstNode<'b>) {
+        for def in self.collect_source_definitions(document) {
+            self.taken_labels
+                .entry(normalize_label(&def.label))
+                .or_insert((def.url, def.title));
+        }
+    }
+
+    /// Generate the label of an external link converted to reference style,
+    /// as configured: a number in order of appearance, or the domain of the
+    /// URL.  The same destination always gets the same label, and labels the
+    /// source already uses for other destinations get a numeric suffix or
+    /// are skipped.  Returns `None` if the link text should be the label.
+    pub(super) fn generated_reference_label(&mut self, url: &str, title: &str) -> Option<String> {
+        let destination = (url.to_string(), title.to_string());
+        if let Some(label) = self.generated_labels.get(&destination) {
+            return Some(label.clone());
+        }
+        let candidates: Box<dyn Iterator<Item = String>> = match self.options.link_reference_label {
+            ReferenceLabel::Text => return None,
+            ReferenceLabel::Numeric => Box::new((1..).map(|n: u64| n.to_string())),
+            ReferenceLabel::Domain => {
+                let host = url_host(url)?.to_lowercase();
+                let base = host.strip_prefix("www.").unwrap_or(&host).to_string();
+                Box::new(
+                    std::iter::once(base.clone()).chain((2..).map(move |n| format!("{base}-{n}"))),
+                )
+            }
+        };
+        for candidate in candidates {
+            let key = normalize_label(&candidate);
+            if self
+                .taken_labels
+                .get(&key)
+                .is_some_and(|taken| *taken != destination)
+            {
+                continue;
+            }
+            self.taken_labels.insert(key, destination.clone());
+            self.generated_labels.insert(destination, candidate.clone());
+            return Some(candidate);
+        }
+        None
+    }
+
+    /// Return the label of an external link whose text is its label: the
+    /// text itself, or, if the source or an earlier link already uses the
+    /// text as a label for another destination, the text with a `-2`, `-3`,
+    /// … suffix.
+    pub(super) fn text_reference_label(&mut self, text: &str, url: &str, title: &str) -> String {
+        let destination = (url.to_string(), title.to_string());
+        let label = std::iter::once(text.to_string())
+            .chain((2..).map(|n: u64| format!("{text}-{n}")))
+            .find(|candidate| {
+                self.taken_labels
+                    .get(&normalize_label(candidate))
+                    .is_none_or(|taken| *taken == destination)
+            })
+            .unwrap();
+        self.taken_labels
+            .entry(normalize_label(&label))
+            .or_insert(destination);
+        label
+    }
+
+    /// Renumber the numeric reference labels in order of first use, if
+    /// configured.  Unused definitions that are kept come after the used
+    /// ones.  External links that get numeric labels are numbered along with
+    /// them, so that formatting the output again yields the same numbers.
+    /// Labels used or defined in disabled regions keep their numbers.
+    pub(super) fn renumber_references<'b>(&mut self, document: &'b AstNode<'b>) {
+        if !self.options.link_renumber_references {
+            return;
+        }
+
+        // A numeric label, or the destination of an external link that gets
+        // a generated numeric label
+        enum Numbered {
+            Label(String),
+            Destination(String, String),
+        }
+
+        let disabled_ranges = Self::collect_disabled_line_ranges(document);
+        let definitions = self.collect_source_definitions(document);
+        let mut reserved: HashSet<u64> = HashSet::new();
+        for def in &definitions {
+            if Self::is_line_in_disabled_ranges(def.line, &disabled_ranges)
+                && let Some(num) = numeric_label(&def.label)
+            {
+                reserved.insert(num);
+            }
+        }
+
+        let mut order: Vec<Numbered> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut push_label = |order: &mut Vec<Numbered>, label: String| {
+            if seen.insert(label.clone()) {
+                order.push(Numbered::Label(label));
+            }
+        };
+        for node in document.descendants() {
+            let (url, title) = match &node.data.borrow().value {
+                NodeValue::Link(link) | NodeValue::Image(link) => {
+                    (link.url.clone(), link.title.clone())
+                }
+                _ => continue,
+            };
+            let line = node.data.borrow().sourcepos.start.line;
+            let disabled = Self::is_line_in_disabled_ranges(line, &disabled_ranges);
+            if let Some((_, label)) = self.get_reference_style_info(node) {
+                let label = self
+                    .merged_label(&label)
+                    .unwrap_or_else(|| label.strip_prefix('\x01').unwrap_or(&label).to_string());
+                let label = normalize_label(&label);
+                match numeric_label(&label) {
+                    Some(num) if disabled => {
+                        reserved.insert(num);
+                    }
+                    Some(_) => push_label(&mut order, label),
+                    None => {}
+                }
+            } else if !disabled
+                && self.options.link_reference_label == ReferenceLabel::Numeric
+                && !matches!(node.data.borrow().value, NodeValue::Image(_))
+                && self.converts_to_reference(&url)
+            {
+                // Reuse a numeric label the source defines for the same URL
+                match definitions.iter().find(|def| {
+                    numeric_label(&def.label).is_some() && def.url == url && def.title == title
+                }) {
+                    Some(def) => push_label(&mut order, normalize_label(&def.label)),
+                    // The link is merged into another label for the same URL
+                    None if self.options.link_duplicate_urls == DuplicateUrls::Merge
+                        && self.url_labels.contains_key(&(url.clone(), title.clone())) => {}
+                    None => order.push(Numbered::Destination(url, title)),
+                }
+            }
+        }
+        for (_, reference) in &self.unused_definitions {
+            if numeric_label(&reference.label).is_some() {
+                push_label(&mut order, normalize_label(&reference.label));
+            }
+        }
+
+        // Generated labels must not clash with the new numbers
+        self.taken_labels
+            .retain(|label, _| numeric_label(label).is_none_or(|num| reserved.contains(&num)));
+        let mut next = 1;
+        for numbered in order {
+            while reserved.contains(&next) {
+                next += 1;
+            }
+            let label = next.to_string();
+            next += 1;
+            match numbered {
+                Numbered::Label(old) => {
+                    if let Some(def) = definitions
+                        .iter()
+                        .find(|def| normalize_label(&def.label) == old)
+                    {
+                        self.taken_labels
+                            .insert(label.clone(), (def.url.clone(), def.title.clone()));
+                    }
+                    self.renumbered_labels.insert(old, label);
+                }
+                Numbered::Destination(url, title) => {
+                    self.taken_labels
+                        .insert(label.clone(), (url.clone(), title.clone()));
+                    self.generated_labels.insert((url, title), label);
+                }
+            }
+        }
+        for (_, reference) in &mut self.unused_definitions {
+            if let Some(label) = self
+                .renumbered_labels
+                .get(&normalize_label(&reference.label))
+            {
+                reference.label = label.clone();
+            }
+        }
+    }
+
+    /// Return the new number of a renumbered numeric reference label, if
+    /// any.
+    pub(super) fn renumbered_label(&self, label: &str) -> Option<&str> {
+        let label = label.strip_prefix('\x01').unwrap_or(label);
+        self.renumbered_labels
+            .get(&normalize_label(label))
+            .map(String::as_str)
+    }
+
+    /// Return the label a reference label has been merged into or
+    /// renumbered to, if any.
+    pub(super) fn merged_label(&self, label: &str) -> Option<String> {
+        let label = label.strip_prefix('\x01').unwrap_or(label);
+        let merged = self.reference_aliases.get(&normalize_label(label)).cloned();
+        let current = merged.as_deref().unwrap_or(label);
+        self.renumbered_label(current)
+            .map(str::to_string)
+            .or(merged)
+    }
+}
+
+/// Normalize a reference label for matching: CommonMark matches labels
+/// case-insensitively, with consecutive whitespace collapsed.
+pub(super) fn normalize_label(label: &str) -> String {
+    escape::normalize_whitespace(&label.replace('\x00', " ")).to_lowercase()
+}
+
+/// Parse a label that consists only of digits, such as `1` or `42`.
+fn numeric_label(label: &str) -> Option<u64> {
+    if label.is_empty() || !label.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    label.parse().ok()
+}
@@ -0,0 +1,95 @@
+//! GitHub-compatible heading anchor slugs.
+
+use std::collections::HashMap;
+
+/// Convert heading text to an anchor slug the way GitHub does.
+///
+/// The text is lowercased, every character other than letters, numbers,
+/// hyphens, underscores, and spaces is removed, and each space becomes
+/// a hyphen.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generates unique slugs for the headings of a document.
+///
+/// Repeated slugs get a numeric suffix (`-1`, `-2`, ...), matching the
+/// anchors GitHub assigns to headings with the same text.
+#[derive(Debug, Default)]
+pub struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the unique slug for the next heading with the given text.
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = slugify(text);
+        let mut slug = base.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self.occurrences.entry(base.clone()).or_insert(0);
+            *count += 1;
+            slug = format!("{}-{}", base, count);
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+
+    /// Record an explicit identifier given to a heading, so that no slug
+    /// generated later collides with it.
+    pub fn explicit(&mut self, id: &str) -> String {
+        self.occurrences.entry(id.to_string()).or_insert(0);
+        id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Slugger, slugify};
+
+    #[test]
+    fn test_slugify_basic() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("What's new?"), "whats-new");
+        assert_eq!(slugify("`Options` struct"), "options-struct");
+    }
+
+    #[test]
+    fn test_slugify_keeps_hyphens_and_underscores() {
+        assert_eq!(slugify("foo_bar - baz"), "foo_bar---baz");
+    }
+
+    #[test]
+    fn test_slugify_unicode() {
+        assert_eq!(slugify("설치 방법"), "설치-방법");
+        assert_eq!(slugify("Über — uns"), "über--uns");
+    }
+
+    #[test]
+    fn test_slugger_deduplicates() {
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("Usage"), "usage");
+        assert_eq!(slugger.slug("Usage"), "usage-1");
+        assert_eq!(slugger.slug("Usage"), "usage-2");
+        assert_eq!(slugger.slug("Usage 1"), "usage-1-1");
+    }
+
+    #[test]
+    fn test_slugger_explicit() {
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.explicit("usage"), "usage");
+        assert_eq!(slugger.slug("Usage"), "usage-1");
+    }
+}
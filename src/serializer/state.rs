@@ -3,8 +3,10 @@
 use indexmap::IndexMap;
 
 use comrak::nodes::{AstNode, ListType, NodeValue};
+use unicode_width::UnicodeWidthStr;
 
-use crate::Options;
+use crate::front_matter::FrontMatter;
+use crate::{ExternalLinks, Options};
 
 /// The current formatting skip mode.
 ///
@@ -43,8 +45,20 @@ pub enum Directive {
     ProperNouns(Vec<String>),
     /// Define common nouns for sentence case (case-sensitive).
     CommonNouns(Vec<String>),
+    /// Start of a generated table of contents listing the headings whose
+    /// levels are between `min_level` and `max_level` (inclusive).
+    Toc { min_level: u8, max_level: u8 },
+    /// End of a generated table of contents.
+    TocEnd,
 }
 
+/// Default lowest heading level listed in a table of contents.  The
+/// document title (h1) is left out.
+pub const TOC_DEFAULT_MIN_LEVEL: u8 = 2;
+
+/// Default highest heading level listed in a table of contents.
+pub const TOC_DEFAULT_MAX_LEVEL: u8 = 6;
+
 impl Directive {
     /// Parse a directive from an HTML comment.
     /// Returns `Some(Directive)` if the comment contains a valid directive.
@@ -64,6 +78,13 @@ impl Directive {
             "hongdown-disable-next-section" => return Some(Directive::DisableNextSection),
             "hongdown-disable" => return Some(Directive::Disable),
             "hongdown-enable" => return Some(Directive::Enable),
+            "hongdown-toc" => {
+                return Some(Directive::Toc {
+                    min_level: TOC_DEFAULT_MIN_LEVEL,
+                    max_level: TOC_DEFAULT_MAX_LEVEL,
+                });
+            }
+            "/hongdown-toc" => return Some(Directive::TocEnd),
             _ => {}
         }
 
@@ -86,8 +107,69 @@ impl Directive {
             return Some(Directive::CommonNouns(nouns));
         }
 
+        if let Some(args) = content.strip_prefix("hongdown-toc:") {
+            return Self::parse_toc_args(args).ok();
+        }
+
         None
     }
+
+    /// Return why the arguments of a table of contents directive are invalid,
+    /// if the comment is such a directive and they are.  [`Directive::parse`]
+    /// does not recognize the directive then, so it stays an ordinary comment.
+    pub fn toc_args_error(html: &str) -> Option<String> {
+        let content = html
+            .trim()
+            .strip_prefix("<!--")?
+            .strip_suffix("-->")?
+            .trim();
+        Self::parse_toc_args(content.strip_prefix("hongdown-toc:")?).err()
+    }
+
+    /// Parse the `min-level=N, max-level=N` arguments of a table of contents
+    /// directive.  Returns an error message if any argument is unknown or out
+    /// of range.
+    fn parse_toc_args(args: &str) -> Result<Self, String> {
+        let mut min_level = TOC_DEFAULT_MIN_LEVEL;
+        let mut max_level = TOC_DEFAULT_MAX_LEVEL;
+        for arg in args.split([',', ' ', '\t']).filter(|s| !s.is_empty()) {
+            let Some((key, value)) = arg.split_once('=') else {
+                return Err(format!(
+                    "expected `min-level=N` or `max-level=N`, found `{}`",
+                    arg
+                ));
+            };
+            let key = key.trim();
+            if key != "min-level" && key != "max-level" {
+                return Err(format!("unknown argument `{}`", key));
+            }
+            let level = match value.trim().parse::<u8>() {
+                Ok(level) if (1..=6).contains(&level) => level,
+                _ => {
+                    return Err(format!(
+                        "`{}` must be a level from 1 to 6, found `{}`",
+                        key,
+                        value.trim()
+                    ));
+                }
+            };
+            if key == "min-level" {
+                min_level = level;
+            } else {
+                max_level = level;
+            }
+        }
+        if min_level > max_level {
+            return Err(format!(
+                "`min-level` ({}) is greater than `max-level` ({})",
+                min_level, max_level
+            ));
+        }
+        Ok(Directive::Toc {
+            min_level,
+            max_level,
+        })
+    }
 }
 
 /// A reference link definition: label -> (url, title)
@@ -195,6 +277,40 @@ pub struct Warning {
     pub message: String,
 }
 
+/// Extract the host of a URL: the authority of `scheme://` and
+/// protocol-relative URLs without user info and port, or the domain of
+/// a `mailto:` address.
+pub fn url_host(url: &str) -> Option<&str> {
+    let authority = if let Some(rest) = url.strip_prefix("//") {
+        rest
+    } else if let Some((scheme, rest)) = url.split_once("://")
+        && !scheme.is_empty()
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    {
+        rest
+    } else if let Some(address) = url
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| &url[7..])
+    {
+        let address = address.split('?').next().unwrap_or(address);
+        return address.rsplit_once('@').map(|(_, domain)| domain);
+    } else {
+        return None;
+    };
+    let authority = authority.split(['/', '?', '#']).next().unwrap_or(authority);
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => host,
+    };
+    Some(host).filter(|host| !host.is_empty())
+}
+
 /// Safely slice a string, ensuring the indices are valid UTF-8 boundaries.
 /// If the indices are not valid boundaries, adjusts to the nearest valid boundary.
 fn safe_str_slice(s: &str, start: usize, end: usize) -> &str {
@@ -265,6 +381,9 @@ pub struct Serializer<'a> {
     pub description_details_first_list: bool,
     /// Warnings generated during formatting
     pub warnings: Vec<Warning>,
+    /// Capitalized words in headings that sentence case would lowercase,
+    /// except for common words, collected in sentence case report mode
+    pub unknown_words: Vec<String>,
     /// Maximum number of items in the current ordered list (for padding calculation)
     pub ordered_list_max_items: usize,
     /// Whether the original source ends with a newline
@@ -282,6 +401,34 @@ pub struct Serializer<'a> {
     pub directive_proper_nouns: Vec<String>,
     /// Common nouns defined via directives for sentence case (merged with config)
     pub directive_common_nouns: Vec<String>,
+    /// Front matter detected at the start of the source, including its kind
+    pub front_matter: Option<FrontMatter<'a>>,
+    /// Reference labels merged into another label pointing to the same URL
+    /// (key: normalized label, value: label to use instead)
+    pub reference_aliases: std::collections::HashMap<String, String>,
+    /// Unused reference definitions to keep, with their source line numbers
+    pub unused_definitions: Vec<(usize, ReferenceLink)>,
+    /// Labels generated for converted external links
+    /// (key: URL and title, value: label)
+    pub generated_labels: std::collections::HashMap<(String, String), String>,
+    /// Reference labels in use, with the URL and title they point to
+    /// (key: normalized label)
+    pub taken_labels: std::collections::HashMap<String, (String, String)>,
+    /// First reference label defined or generated for each destination,
+    /// with the line it comes from (key: URL and title)
+    pub url_labels: std::collections::HashMap<(String, String), (String, usize)>,
+    /// New numbers of renumbered numeric reference labels
+    /// (key: old label, value: new label)
+    pub renumbered_labels: std::collections::HashMap<String, String>,
+    /// Section numbers of numbered headings
+    /// (key: source line of the heading, value: number)
+    pub heading_numbers: std::collections::HashMap<usize, String>,
+    /// Canonical forms of the proper nouns the body text is checked against
+    /// (key: lowercase form), built when first needed
+    pub proper_noun_index: Option<std::collections::HashMap<String, String>>,
+    /// Markup masked by placeholders while the case of the text around it
+    /// is converted, or `None` if no case is being converted
+    pub case_masks: Option<Vec<String>>,
     /// Code formatter callback for WASM builds.
     #[cfg(feature = "wasm")]
     pub code_formatter_callback: CodeFormatterCallback,
@@ -310,6 +457,7 @@ impl<'a> Serializer<'a> {
             in_description_details: false,
             description_details_first_list: false,
             warnings: Vec::new(),
+            unknown_words: Vec::new(),
             ordered_list_max_items: 0,
             source_ends_with_newline,
             list_item_indent: String::new(),
@@ -317,6 +465,16 @@ impl<'a> Serializer<'a> {
             blockquote_entry_list_depth: 0,
             directive_proper_nouns: Vec::new(),
             directive_common_nouns: Vec::new(),
+            front_matter: None,
+            reference_aliases: std::collections::HashMap::new(),
+            unused_definitions: Vec::new(),
+            generated_labels: std::collections::HashMap::new(),
+            taken_labels: std::collections::HashMap::new(),
+            url_labels: std::collections::HashMap::new(),
+            renumbered_labels: std::collections::HashMap::new(),
+            heading_numbers: std::collections::HashMap::new(),
+            proper_noun_index: None,
+            case_masks: None,
             #[cfg(feature = "wasm")]
             code_formatter_callback: None,
         }
@@ -347,6 +505,7 @@ impl<'a> Serializer<'a> {
             in_description_details: false,
             description_details_first_list: false,
             warnings: Vec::new(),
+            unknown_words: Vec::new(),
             ordered_list_max_items: 0,
             source_ends_with_newline,
             list_item_indent: String::new(),
@@ -354,6 +513,16 @@ impl<'a> Serializer<'a> {
             blockquote_entry_list_depth: 0,
             directive_proper_nouns: Vec::new(),
             directive_common_nouns: Vec::new(),
+            front_matter: None,
+            reference_aliases: std::collections::HashMap::new(),
+            unused_definitions: Vec::new(),
+            generated_labels: std::collections::HashMap::new(),
+            taken_labels: std::collections::HashMap::new(),
+            url_labels: std::collections::HashMap::new(),
+            renumbered_labels: std::collections::HashMap::new(),
+            heading_numbers: std::collections::HashMap::new(),
+            proper_noun_index: None,
+            case_masks: None,
             code_formatter_callback: callback,
         }
     }
@@ -437,6 +606,19 @@ impl<'a> Serializer<'a> {
         Some(result)
     }
 
+    /// Write the source lines that `node` spans as they are.  Used for blocks
+    /// masked before parsing, such as MDX blocks (see [`crate::mdx`]), whose
+    /// columns do not match the source.
+    pub fn serialize_source_lines<'b>(&mut self, node: &'b AstNode<'b>) {
+        let sourcepos = node.data.borrow().sourcepos;
+        let start = sourcepos.start.line.saturating_sub(1);
+        let end = sourcepos.end.line.min(self.source_lines.len());
+        for line in self.source_lines.get(start..end).unwrap_or_default() {
+            self.output.push_str(line.trim_end());
+            self.output.push('\n');
+        }
+    }
+
     /// Check if formatting should be skipped for this node.
     pub fn should_skip_formatting(&self) -> bool {
         self.skip_mode != FormatSkipMode::None
@@ -458,9 +640,38 @@ impl<'a> Serializer<'a> {
         }
     }
 
-    /// Check if a URL is external (starts with http:// or https://).
-    pub fn is_external_url(url: &str) -> bool {
-        url.starts_with("http://") || url.starts_with("https://")
+    /// Check if a URL is external: `http://` and `https://` URLs, URLs with
+    /// one of the configured external schemes, and URLs to one of the
+    /// configured external domains or their subdomains.
+    pub fn is_external_url(&self, url: &str) -> bool {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return true;
+        }
+        if let Some((scheme, _)) = url.split_once(':')
+            && self
+                .options
+                .link_external_schemes
+                .iter()
+                .any(|s| s.trim_end_matches(':').eq_ignore_ascii_case(scheme))
+        {
+            return true;
+        }
+        url_host(url).is_some_and(|host| {
+            self.options.link_external_domains.iter().any(|domain| {
+                host.eq_ignore_ascii_case(domain)
+                    || host
+                        .to_ascii_lowercase()
+                        .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+            })
+        })
+    }
+
+    /// Check if an inline link to `url` should be converted to reference
+    /// style, as configured.
+    pub fn converts_to_reference(&self, url: &str) -> bool {
+        self.options.link_external_links == ExternalLinks::Reference
+            && self.is_external_url(url)
+            && UnicodeWidthStr::width(url) > self.options.link_min_url_width
     }
 
     /// Get the emphasis delimiter character.
@@ -484,6 +695,10 @@ impl<'a> Serializer<'a> {
     /// Uses "__" if the content contains '*' (to avoid escaping).
     /// Otherwise, preserves the original delimiter from source, defaulting to "**".
     pub fn get_strong_delimiter<'b>(&self, node: &'b AstNode<'b>) -> &'static str {
+        // `__` means underline when the underline extension is enabled
+        if self.options.extension_underline {
+            return "**";
+        }
         // If content contains '*', use '__' to avoid escaping
         if self.node_text_contains_char(node, '*') {
             return "__";
@@ -514,7 +729,85 @@ impl<'a> Serializer<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::safe_str_slice;
+    use super::{Directive, safe_str_slice, url_host};
+
+    #[test]
+    fn test_directive_parse_toc() {
+        assert_eq!(
+            Directive::parse("<!-- hongdown-toc -->"),
+            Some(Directive::Toc {
+                min_level: 2,
+                max_level: 6
+            })
+        );
+        assert_eq!(
+            Directive::parse("<!-- hongdown-toc: min-level=1, max-level=3 -->"),
+            Some(Directive::Toc {
+                min_level: 1,
+                max_level: 3
+            })
+        );
+        assert_eq!(
+            Directive::parse("<!-- /hongdown-toc -->"),
+            Some(Directive::TocEnd)
+        );
+    }
+
+    #[test]
+    fn test_directive_parse_toc_invalid_args() {
+        assert_eq!(Directive::parse("<!-- hongdown-toc: max-level=7 -->"), None);
+        assert_eq!(Directive::parse("<!-- hongdown-toc: depth=2 -->"), None);
+        assert_eq!(
+            Directive::parse("<!-- hongdown-toc: min-level=4, max-level=2 -->"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_directive_toc_args_error() {
+        assert_eq!(
+            Directive::toc_args_error("<!-- hongdown-toc: max-level=7 -->").as_deref(),
+            Some("`max-level` must be a level from 1 to 6, found `7`")
+        );
+        assert_eq!(
+            Directive::toc_args_error("<!-- hongdown-toc: depth=2 -->").as_deref(),
+            Some("unknown argument `depth`")
+        );
+        assert_eq!(
+            Directive::toc_args_error("<!-- hongdown-toc: min-level=3, max-level=2 -->").as_deref(),
+            Some("`min-level` (3) is greater than `max-level` (2)")
+        );
+        assert_eq!(
+            Directive::toc_args_error("<!-- hongdown-toc: levels -->").as_deref(),
+            Some("expected `min-level=N` or `max-level=N`, found `levels`")
+        );
+        assert_eq!(
+            Directive::toc_args_error("<!-- hongdown-toc: max-level=3 -->"),
+            None
+        );
+        assert_eq!(Directive::toc_args_error("<!-- hongdown-toc -->"), None);
+        assert_eq!(Directive::toc_args_error("<!-- a comment -->"), None);
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(url_host("https://example.com/path"), Some("example.com"));
+        assert_eq!(
+            url_host("ftp://user@files.example.com:21/a"),
+            Some("files.example.com")
+        );
+        assert_eq!(
+            url_host("//cdn.example.com/lib.js"),
+            Some("cdn.example.com")
+        );
+        assert_eq!(
+            url_host("mailto:someone@example.com?subject=Hi"),
+            Some("example.com")
+        );
+        assert_eq!(url_host("ipfs://bafybeigdyrzt"), Some("bafybeigdyrzt"));
+        assert_eq!(url_host("docs/guide.md"), None);
+        assert_eq!(url_host("#usage"), None);
+    }
 
     #[test]
     fn test_safe_str_slice_ascii() {
@@ -1,6 +1,6 @@
 //! Table serialization logic.
 
-use comrak::nodes::{AstNode, NodeTable, TableAlignment};
+use comrak::nodes::{AstNode, NodeTable, NodeValue, TableAlignment};
 use unicode_width::UnicodeWidthStr;
 
 use super::Serializer;
@@ -29,11 +29,23 @@ impl<'a> Serializer<'a> {
 
         for row in &rows {
             let mut row_cells: Vec<String> = Vec::new();
+            let convert_case = self.options.heading_sentence_case_table_headers
+                && matches!(row.data.borrow().value, NodeValue::TableRow(true));
 
             for (i, cell) in row.children().enumerate() {
                 // Use collect_inline_content to preserve links and formatting
-                let mut content = String::new();
-                self.collect_inline_content(cell, &mut content);
+                let collect = |this: &mut Self| {
+                    let mut content = String::new();
+                    this.collect_inline_content(cell, &mut content);
+                    content
+                };
+                let content = if convert_case {
+                    self.collect_with_case_conversion(collect, |this, text, _| {
+                        this.apply_sentence_case(text)
+                    })
+                } else {
+                    collect(self)
+                };
                 // Escape pipe characters in table cells to prevent cell boundary confusion
                 let content = escape::escape_table_cell(&content);
                 if i < col_widths.len() {
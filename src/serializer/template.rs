@@ -0,0 +1,39 @@
+//! Serialization of template tags.
+//!
+//! These are masked before parsing (see [`crate::template`]), so they are
+//! written back from the original source.
+
+use comrak::nodes::{AstNode, NodeValue};
+
+use super::Serializer;
+
+impl<'a> Serializer<'a> {
+    /// Check if a code span stands for an inline template tag.
+    pub(super) fn is_template_tag<'b>(&self, node: &'b AstNode<'b>) -> bool {
+        !self.options.template_delimiters.is_empty()
+            && self.extract_source(node).is_some_and(|source| {
+                self.options
+                    .template_delimiters
+                    .iter()
+                    .any(|(open, _)| !open.is_empty() && source.starts_with(open.as_str()))
+            })
+    }
+
+    /// Check if either of two consecutive blocks is a line of template tags
+    /// and no blank line separates them in the source.
+    pub(super) fn is_attached_to_template_tags<'b>(
+        &self,
+        prev: &'b AstNode<'b>,
+        next: &'b AstNode<'b>,
+    ) -> bool {
+        let is_tags = |node: &'b AstNode<'b>| {
+            matches!(
+                &node.data.borrow().value,
+                NodeValue::HtmlBlock(html_block)
+                    if crate::template::is_masked_block(&html_block.literal)
+            )
+        };
+        (is_tags(prev) || is_tags(next))
+            && next.data.borrow().sourcepos.start.line == prev.data.borrow().sourcepos.end.line + 1
+    }
+}
@@ -341,6 +341,112 @@ fn test_serialize_yaml_front_matter_only() {
     assert_eq!(result, "---\ntitle: Test\n---\n\nSome content.\n");
 }
 
+#[test]
+fn test_serialize_toml_front_matter() {
+    let input = "+++\ntitle = \"Hello\"\ntags = [\"a\", \"b\"]\n+++\n\n# Heading\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert_eq!(
+        result,
+        "+++\ntitle = \"Hello\"\ntags = [\"a\", \"b\"]\n+++\n\nHeading\n=======\n"
+    );
+}
+
+#[test]
+fn test_serialize_json_front_matter() {
+    let input = "{\n  \"title\": \"Hello\",\n  \"tags\": [\"a\"]\n}\n\n# Heading\n\nSome text.\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert_eq!(
+        result,
+        "{\n  \"title\": \"Hello\",\n  \"tags\": [\"a\"]\n}\n\nHeading\n=======\n\nSome text.\n"
+    );
+}
+
+#[test]
+fn test_json_front_matter_keeps_line_numbers() {
+    let input = "{\n  \"title\": \"Hello\"\n}\n\n[undefined][ref]\n";
+    let result = crate::format_with_warnings(input, &Options::default()).unwrap();
+    assert!(
+        result
+            .output
+            .starts_with("{\n  \"title\": \"Hello\"\n}\n\n")
+    );
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 5);
+}
+
+#[test]
+fn test_front_matter_kind_recorded() {
+    use crate::front_matter::FrontMatterKind;
+
+    for (input, kind) in [
+        ("---\ntitle: a\n---\n\nText.\n", FrontMatterKind::Yaml),
+        ("+++\ntitle = \"a\"\n+++\n\nText.\n", FrontMatterKind::Toml),
+        ("{\n\"title\": \"a\"\n}\n\nText.\n", FrontMatterKind::Json),
+    ] {
+        let arena = Arena::new();
+        let options = Options::default();
+        let root = crate::parse_document(&arena, input, &options);
+        let mut serializer = Serializer::new(&options, input.lines().collect(), true);
+        serializer.front_matter = crate::front_matter::detect(input);
+        serializer.serialize_node(root);
+        assert_eq!(serializer.front_matter.map(|f| f.kind), Some(kind));
+        assert!(serializer.output.ends_with("\n\nText.\n"));
+    }
+}
+
+#[test]
+fn test_front_matter_canonicalize_disabled_by_default() {
+    let input = "---\ntitle:   'Hello'   \ntags:\n    - a\n---\n\nText.\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert_eq!(result, input);
+}
+
+#[test]
+fn test_front_matter_canonicalize_yaml() {
+    let input = "---\ntags:   \n    - a\n# Title comment\ntitle: 'Hello'\n---\n\nText.\n";
+    let options = Options {
+        front_matter_canonicalize: true,
+        front_matter_quote_style: crate::FrontMatterQuoteStyle::Double,
+        front_matter_key_order: vec!["title".to_string()],
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "---\n# Title comment\ntitle: \"Hello\"\ntags:\n  - a\n---\n\nText.\n"
+    );
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_front_matter_canonicalize_toml() {
+    let input = "+++\n  title = 'Hello'  \n+++\n\nText.\n";
+    let options = Options {
+        front_matter_canonicalize: true,
+        ..Options::default()
+    };
+    let result = crate::format(input, &options).unwrap();
+    assert_eq!(result, "+++\ntitle = 'Hello'\n+++\n\nText.\n");
+}
+
+#[test]
+fn test_front_matter_canonicalize_parse_error_warning() {
+    let input = "---\ntitle: [unclosed   \n---\n\nText.\n";
+    let options = Options {
+        front_matter_canonicalize: true,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert!(result.output.starts_with("---\ntitle: [unclosed   \n---\n"));
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 1);
+    assert!(
+        result.warnings[0]
+            .message
+            .starts_with("front matter left untouched:")
+    );
+}
+
 #[test]
 fn test_serialize_two_blank_lines_before_h2() {
     let input = "# Title\n\nParagraph.\n\n## Section";
@@ -817,6 +923,60 @@ fn test_blockquote_inside_list_item() {
     assert!(result.contains("2.  Next item."));
 }
 
+#[test]
+fn test_alert_keeps_title_and_fold_marker() {
+    let input = "> [!tip]- Custom *title*\n> Body.\n\n> [!Note] Title\n> Body.\n";
+    assert_eq!(
+        crate::format(input, &Options::default()).unwrap(),
+        "> [!TIP]- Custom *title*\n> Body.\n\n> [!NOTE] Title\n> Body.\n"
+    );
+}
+
+#[test]
+fn test_callouts_of_any_type() {
+    let options = Options {
+        extension_callouts: true,
+        extension_callout_type_case: crate::CalloutTypeCase::Lower,
+        ..Options::default()
+    };
+    let input = "> [!FAQ]+ Why?\n> Because.\n\n * item\n\n   > [!Todo]\n   > Do it.\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "> [!faq]+ Why?\n> Because.\n\n -  item\n\n    > [!todo]\n    > Do it.\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+}
+
+#[test]
+fn test_callouts_in_code_kept() {
+    let options = Options {
+        extension_callouts: true,
+        ..Options::default()
+    };
+    let input = "Para\n\n    > [!custom]- Title\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Para\n\n~~~~\n> [!custom]- Title\n~~~~\n"
+    );
+}
+
+#[test]
+fn test_callouts_warn_for_github_target() {
+    let options = Options {
+        extension_callouts: true,
+        extension_callout_type_case: crate::CalloutTypeCase::Preserve,
+        extension_callout_target: crate::CalloutTarget::Github,
+        ..Options::default()
+    };
+    let input = "> [!Warning]\n> Hot.\n\n> [!abstract] Summary\n> Text.\n";
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(result.output, input);
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 4);
+    assert!(result.warnings[0].message.contains("[!abstract]"));
+}
+
 #[test]
 fn test_alert_inside_list_item() {
     // Alerts inside list items should have proper indentation
@@ -1467,657 +1627,1086 @@ fn test_very_long_word_in_paragraph() {
 fn test_strikethrough_text() {
     let input = "~~strikethrough~~";
     let result = parse_and_serialize(input);
-    // Strikethrough may or may not be supported, but should not crash
-    assert!(!result.is_empty());
+    // Without the strikethrough extension, the tildes are plain text
+    assert_eq!(result, "~~strikethrough~~\n");
+}
+
+fn extensions_options() -> Options {
+    Options {
+        extension_strikethrough: true,
+        extension_superscript: true,
+        extension_subscript: true,
+        extension_math: true,
+        extension_autolink: true,
+        extension_underline: true,
+        extension_spoiler: true,
+        extension_shortcodes: true,
+        ..Options::default()
+    }
 }
 
 #[test]
-fn test_mixed_ordered_unordered_lists() {
-    let input = " 1. Ordered item\n\n -  Unordered item";
-    let result = parse_and_serialize(input);
-    assert!(result.contains("1."));
-    assert!(result.contains("-"));
+fn test_extension_inline_delimiters() {
+    let options = extensions_options();
+    let input = "A ~~strike~~, H~2~O, x^2^, __under__, ||spoiler||, and :rabbit:.\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
 }
 
 #[test]
-fn test_horizontal_rule() {
-    let input = "Before\n\n---\n\nAfter";
-    let result = parse_and_serialize(input);
-    // Horizontal rules should be preserved with default style
-    assert!(result.contains("Before"));
-    assert!(
-        result
-            .contains("- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -")
+fn test_extension_strikethrough_without_subscript() {
+    let options = Options {
+        extension_strikethrough: true,
+        ..Options::default()
+    };
+    let input = "A ~single~ and ~~double~~ strike.\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "A ~~single~~ and ~~double~~ strike.\n"
     );
-    assert!(result.contains("After"));
 }
 
 #[test]
-fn test_thematic_break_default_leading_spaces() {
-    let input = "Before\n\n---\n\nAfter";
-    let result = parse_and_serialize(input);
-    // Default leading_spaces is 3
-    assert!(
-        result.contains(
-            "\n   - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -\n"
-        ),
-        "Expected 3 leading spaces by default, got:\n{}",
-        result
-    );
+fn test_extension_underline_uses_asterisks_for_strong() {
+    let options = Options {
+        extension_underline: true,
+        ..Options::default()
+    };
+    let input = "**strong** and __under__.\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
 }
 
 #[test]
-fn test_thematic_break_custom_style() {
-    let input = "Before\n\n---\n\nAfter";
-    let mut options = Options::default();
-    options.thematic_break_style = ThematicBreakStyle::new("---".to_string()).unwrap();
-    options.thematic_break_leading_spaces = LeadingSpaces::new(0).unwrap();
-    let result = parse_and_serialize_with_options(input, &options);
-    assert!(
-        result.contains("\n---\n"),
-        "Expected custom style thematic break, got:\n{}",
-        result
-    );
+fn test_extension_math() {
+    let options = extensions_options();
+    let input =
+        "Inline $a_1 + b_1$ math.\n\n$$\n\\int_0^1 f(x)\\,dx\n$$\n\n -  $$\n    x^2\n    $$\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(output, input);
 }
 
 #[test]
-fn test_thematic_break_leading_spaces() {
-    let input = "Before\n\n---\n\nAfter";
-    let mut options = Options::default();
-    options.thematic_break_style = ThematicBreakStyle::new("*  *  *".to_string()).unwrap();
-    options.thematic_break_leading_spaces = LeadingSpaces::new(3).unwrap();
-    let result = parse_and_serialize_with_options(input, &options);
-    // 3 leading spaces should be applied
-    assert!(
-        result.contains("\n   *  *  *\n"),
-        "Expected 3 leading spaces, got:\n{}",
-        result
+fn test_extension_math_in_heading() {
+    let options = extensions_options();
+    let input = "The $E = mc^2$ formula\n======================\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
+}
+
+#[test]
+fn test_extension_display_math_delimiters_on_own_lines() {
+    let options = extensions_options();
+    let input = "$$ e = mc^2 $$\n\n> $$\\sum_{i=1}^n i\n> $$\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "$$\ne = mc^2\n$$\n\n> $$\n> \\sum_{i=1}^n i\n> $$\n"
     );
 }
 
 #[test]
-fn test_thematic_break_idempotent() {
-    // Test that formatting twice produces the same result (fixes the bug)
-    let input = "Before\n\n---\n\nAfter";
-    let first_pass = parse_and_serialize(input);
-    let second_pass = parse_and_serialize(&first_pass);
+fn test_extension_math_not_wrapped() {
+    let options = Options {
+        line_width: LineWidth::new(30).unwrap(),
+        ..extensions_options()
+    };
+    let input = "Euler wrote the identity $e^{i \\pi} + 1 = 0$ long ago.\n";
     assert_eq!(
-        first_pass, second_pass,
-        "Thematic break formatting should be idempotent"
+        crate::format(input, &options).unwrap(),
+        "Euler wrote the identity\n$e^{i \\pi} + 1 = 0$ long ago.\n"
     );
 }
 
 #[test]
-fn test_thematic_break_various_input_styles() {
-    // Test various input styles are normalized to default style
-    let inputs = vec!["---", "***", "___", "- - -", "* * *", "_ _ _"];
-    let expected = "- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
-    for input in inputs {
-        let full_input = format!("Before\n\n{}\n\nAfter", input);
-        let result = parse_and_serialize(&full_input);
-        assert!(
-            result.contains(expected),
-            "Input '{}' should be normalized to '{}', got:\n{}",
-            input,
-            expected,
-            result
-        );
-    }
+fn test_math_span_not_escaped_without_extension() {
+    let input = "Where $\\alpha_1 * \\beta_2$ holds, but not_here.\n";
+    assert_eq!(
+        crate::format(input, &Options::default()).unwrap(),
+        "Where $\\alpha_1 * \\beta_2$ holds, but not\\_here.\n"
+    );
 }
 
 #[test]
-fn test_unicode_in_heading() {
-    let input = "# 한글 제목";
-    let result = parse_and_serialize(input);
-    assert!(
-        result.contains("한글 제목"),
-        "Unicode heading should be preserved, got:\n{}",
-        result
+fn test_extension_math_fence_delimiters() {
+    let options = Options {
+        extension_math_delimiters: crate::MathDelimiters::Fence,
+        ..extensions_options()
+    };
+    let input = "$$\nx^2\n$$\n\n -  $$ y $$\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "~~~~ math\nx^2\n~~~~\n\n -  ~~~~ math\n    y\n    ~~~~\n"
     );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
 }
 
 #[test]
-fn test_unicode_in_link_text() {
-    let input = "[한글 링크](https://example.com)";
-    let result = parse_and_serialize(input);
-    assert!(
-        result.contains("한글 링크"),
-        "Unicode in link text should be preserved, got:\n{}",
-        result
+fn test_mdx_blocks_kept_verbatim() {
+    let options = Options {
+        extension_mdx: true,
+        ..Options::default()
+    };
+    let input = "import {Tabs} from '@theme/Tabs'\nexport const meta = {\n  title: 'A',\n}\n\n\
+                 # Title\n\n<Tabs>\n  <Tab label={labels.a_b}>\n\n* one\n* two\n\n  </Tab>\n</Tabs>\n\n\
+                 {/* a comment */}\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "import {Tabs} from '@theme/Tabs'\nexport const meta = {\n  title: 'A',\n}\n\n\
+         Title\n=====\n\n<Tabs>\n  <Tab label={labels.a_b}>\n\n -  one\n -  two\n\n  </Tab>\n</Tabs>\n\n\
+         {/* a comment */}\n"
     );
 }
 
 #[test]
-fn test_footnote_with_multiple_paragraphs() {
-    let input = "Text[^1]\n\n[^1]: First paragraph of footnote";
-    let result = parse_and_serialize(input);
-    assert!(result.contains("[^1]"));
+fn test_mdx_inline_expressions() {
+    let options = Options {
+        extension_mdx: true,
+        line_width: LineWidth::new(40).unwrap(),
+        ..Options::default()
+    };
+    let input = "Hello {props.user_name}, your score is {score * 2 + bonus_points} today, a_b.\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Hello {props.user_name}, your score is\n{score * 2 + bonus_points} today, a\\_b.\n"
+    );
 }
 
 #[test]
-fn test_nested_emphasis() {
-    let input = "***bold and italic***";
-    let result = parse_and_serialize(input);
-    // Should preserve some form of emphasis
-    assert!(result.contains("*"));
+fn test_mdx_expressions_in_code_kept() {
+    let options = Options {
+        extension_mdx: true,
+        ..Options::default()
+    };
+    let input = " -  ~~~~\n    {a}\n    ~~~~\n\n> ~~~~ js\n> export const a = {b}\n> ~~~~\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
+    let input = "Para\n\n    {expr} and {more}\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Para\n\n~~~~\n{expr} and {more}\n~~~~\n"
+    );
 }
 
 #[test]
-fn test_code_block_with_blank_lines() {
-    let input = "~~~~ text\nline 1\n\nline 3\n~~~~";
-    let result = parse_and_serialize(input);
-    assert!(result.contains("line 1"));
-    assert!(result.contains("line 3"));
+fn test_containers_normalized() {
+    let options = Options {
+        extension_containers: true,
+        ..Options::default()
+    };
+    let input = "::::::note Title\nOuter *text*.\n:::: tip\nInner.\n::::\n::::::\n\n\
+                 ::: {.callout}\n* a\n:::\n\n* item\n\n  :::note\n  In list.\n  :::\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "::::note Title\n\nOuter *text*.\n\n::: tip\n\nInner.\n\n:::\n\n::::\n\n\
+         ::: {.callout}\n\n -  a\n\n:::\n\n -  item\n\n    :::note\n\n    In list.\n\n    :::\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
 }
 
 #[test]
-fn test_gfm_task_list_checked() {
-    let input = " - [x] Completed task";
-    let result = parse_and_serialize(input);
-    assert_eq!(result, " -  [x] Completed task\n");
+fn test_container_markers_never_emitted() {
+    let options = Options {
+        extension_containers: true,
+        ..Options::default()
+    };
+    let input = "Para\n\n    :::tip\n    x\n    :::\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Para\n\n~~~~\n:::tip\nx\n:::\n~~~~\n"
+    );
+    let input = "- item\n\n    :::tip\n    x\n    :::\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        " -  item\n\n    :::tip\n\n    x\n\n    :::\n"
+    );
+    let input = "Para\n    :::tip\n    x\n";
+    assert_eq!(crate::format(input, &options).unwrap(), "Para\n:::tip\nx\n");
 }
 
 #[test]
-fn test_gfm_task_list_unchecked() {
-    let input = " - [ ] Pending task";
-    let result = parse_and_serialize(input);
-    assert_eq!(result, " -  [ ] Pending task\n");
+fn test_containers_disabled_by_default() {
+    let input = ":::note\nText.\n:::\n";
+    assert_eq!(parse_and_serialize(input), ":::note\nText.\n:::\n");
 }
 
 #[test]
-fn test_gfm_task_list_mixed() {
-    let input = " - [x] Done\n - [ ] Todo\n - [x] Also done";
-    let result = parse_and_serialize(input);
-    assert!(result.contains("[x] Done"));
-    assert!(result.contains("[ ] Todo"));
-    assert!(result.contains("[x] Also done"));
+fn test_containers_to_alerts() {
+    let options = Options {
+        extension_containers: true,
+        extension_container_style: crate::ContainerStyle::Alert,
+        ..Options::default()
+    };
+    // Only top-level containers become alerts
+    let input = ":::: details\nText.\n\n:::Warning\nCareful.\n:::\n::::\n\n:::tip\nA tip.\n:::\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        ":::: details\n\nText.\n\n:::Warning\n\nCareful.\n\n:::\n\n::::\n\n> [!TIP]\n> A tip.\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
 }
 
 #[test]
-fn test_gfm_task_list_nested() {
-    let input = " - [x] Parent task\n    - [ ] Child task";
-    let result = parse_and_serialize(input);
-    assert!(result.contains("[x] Parent task"));
-    assert!(result.contains("[ ] Child task"));
+fn test_alerts_to_containers() {
+    let options = Options {
+        extension_containers: true,
+        extension_container_style: crate::ContainerStyle::Container,
+        ..Options::default()
+    };
+    let input = "> [!CAUTION]\n> Hot.\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(output, ":::caution\n\nHot.\n\n:::\n");
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
 }
 
 #[test]
-fn test_definition_list_no_extra_blank_line() {
-    let input = "Term\n:   Definition here";
-    let result = parse_and_serialize(input);
-    assert_eq!(result, "Term\n:   Definition here\n");
+fn test_alerts_in_containers_kept() {
+    let options = Options {
+        extension_containers: true,
+        extension_container_style: crate::ContainerStyle::Container,
+        ..Options::default()
+    };
+    // Only top-level alerts become containers
+    let input = ":::: details\n> [!NOTE]\n> Nested.\n::::\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(output, "::: details\n\n> [!NOTE]\n> Nested.\n\n:::\n");
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+}
+
+fn template_options() -> Options {
+    Options {
+        template_delimiters: vec![
+            ("{{".to_string(), "}}".to_string()),
+            ("{%".to_string(), "%}".to_string()),
+        ],
+        ..Options::default()
+    }
 }
 
 #[test]
-fn test_definition_list_multiple_items() {
-    let input = "Term1\n:   Definition1\n\nTerm2\n:   Definition2";
-    let result = parse_and_serialize(input);
-    assert!(result.contains("Term1\n:   Definition1"));
-    assert!(result.contains("Term2\n:   Definition2"));
-    // Should have blank line between items, but not between term and definition
-    assert!(!result.contains("Term1\n\n:"));
-}
-
-#[test]
-fn test_abbreviation_definition_preserved() {
-    let input = "*[JSX]: JavaScript XML";
-    let result = parse_and_serialize_with_source(input);
-    assert_eq!(result, "*[JSX]: JavaScript XML\n");
+fn test_template_tags_kept_verbatim() {
+    let options = Options {
+        line_width: LineWidth::new(40).unwrap(),
+        ..template_options()
+    };
+    let input = "Hi {{ page.user_name }}, see \"this\" and {{< figure src=\"a_b.png\" >}} now.\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "Hi {{ page.user_name }}, see \u{201c}this\u{201d} and\n{{< figure src=\"a_b.png\" >}} now.\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
 }
 
 #[test]
-fn test_abbreviation_definition_multiple() {
-    let input = "*[HTML]: HyperText Markup Language\n\n*[CSS]: Cascading Style Sheets";
-    let result = parse_and_serialize_with_source(input);
-    assert!(result.contains("*[HTML]: HyperText Markup Language"));
-    assert!(result.contains("*[CSS]: Cascading Style Sheets"));
+fn test_template_tag_lines_kept_verbatim() {
+    let options = template_options();
+    let input = "{% include header.html %}\n\n{% if site.show %}\nSome  *text*.\n{% endif %}\n\n\
+                 {{< tabs\n    name=\"x\" >}}\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "{% include header.html %}\n\n{% if site.show %}\nSome  *text*.\n{% endif %}\n\n\
+         {{< tabs\n    name=\"x\" >}}\n"
+    );
 }
 
 #[test]
-fn test_definition_list_with_list_as_first_child() {
-    let input = "Pros\n:    -  First item\n     -  Second item";
-    let result = parse_and_serialize(input);
-    // The list should be on the same line as the colon to ensure idempotent formatting
-    // Format: `:    -  First item` (colon + 4 spaces + list marker)
-    assert!(result.contains("Pros\n:    -  First item"));
-    assert!(result.contains("     -  Second item"));
+fn test_template_tags_in_html_and_code_kept() {
+    let options = template_options();
+    let input = "Para\n\n<div>\n  {{ page.title }}\n</div>\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
+    let input = "Para\n\n    {{ a }} and {% b %}\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Para\n\n~~~~\n{{ a }} and {% b %}\n~~~~\n"
+    );
+    let input = "> ~~~~\n> {{ x }}\n> ~~~~\n\n -  ~~~~\n    {% y %}\n    ~~~~\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
 }
 
 #[test]
-fn test_reference_used_in_multiple_sections_not_duplicated() {
-    let input = r#"Section
--------
-
-### First
-
-Text with [link].
-
-### Second
-
-More text with [link].
-
-[link]: https://example.com
-"#;
-    let result = parse_and_serialize_with_source(input);
-    // The reference should appear only once, after first use
-    let count = result.matches("[link]: https://example.com").count();
+fn test_template_tags_disabled_by_default() {
     assert_eq!(
-        count, 1,
-        "Reference should appear exactly once, but found {} times in:\n{}",
-        count, result
+        parse_and_serialize("Hi {{ page.user_name }}.\n"),
+        "Hi {{ page.user\\_name }}.\n"
     );
 }
 
 #[test]
-fn test_heading_with_reference_link() {
-    let input = r#"[BotKit] by Fedify
-==================
-
-[BotKit]: https://botkit.fedify.dev/
-"#;
-    let result = parse_and_serialize_with_source(input);
-    // The reference link in the heading should be preserved
-    assert!(
-        result.contains("[BotKit] by Fedify"),
-        "Heading should contain reference link syntax, got:\n{}",
-        result
+fn test_lint_proper_nouns_warn() {
+    let input = "Use Javascript, not `javascript`, on [github](https://github.com/).\n";
+    let options = Options {
+        lint_proper_nouns: crate::ProperNounLint::Warn,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "Use Javascript, not `javascript`, on [github].\n\n[github]: https://github.com/\n"
     );
-    assert!(
-        result.contains("[BotKit]: https://botkit.fedify.dev/"),
-        "Reference definition should be preserved, got:\n{}",
-        result
+    let messages: Vec<_> = result.warnings.iter().map(|w| &w.message[..]).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "proper noun \"Javascript\" should be \"JavaScript\"",
+            "proper noun \"github\" should be \"GitHub\"",
+        ]
     );
 }
 
 #[test]
-fn test_footnote_at_section_end_before_subheading() {
-    let input = r#"Section
--------
-
-Text with footnote[^1].
-
-### Subsection
-
-More text here.
-
-[^1]: This is a footnote.
-"#;
-    let result = parse_and_serialize_with_source(input);
-    // Footnote should appear before the subsection, not at document end
-    let footnote_pos = result.find("[^1]: This is a footnote.").unwrap();
-    let subsection_pos = result.find("### Subsection").unwrap();
-    assert!(
-        footnote_pos < subsection_pos,
-        "Footnote should appear before subsection, got:\n{}",
-        result
-    );
+fn test_lint_proper_nouns_fix() {
+    let input = "We moved from Postgresql to Mongodb.\n";
+    let options = Options {
+        lint_proper_nouns: crate::ProperNounLint::Fix,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(result.output, "We moved from PostgreSQL to MongoDB.\n");
+    assert_eq!(result.warnings.len(), 2);
+    assert_eq!(result.warnings[0].line, 1);
 }
 
 #[test]
-fn test_footnote_definition_wrapped_at_80_chars() {
-    let input = r#"Text[^1].
-
-[^1]: This is a very long footnote definition that definitely exceeds eighty characters and should be wrapped.
-"#;
-    let result = parse_and_serialize_with_source(input);
-    // Check that no line exceeds 80 characters
-    for line in result.lines() {
-        assert!(
-            line.len() <= 80,
-            "Line exceeds 80 characters: '{}' (len={})",
-            line,
-            line.len()
-        );
-    }
-    // Should still contain the footnote content
-    assert!(
-        result.contains("This is a very long footnote"),
-        "Footnote content should be preserved, got:\n{}",
-        result
+fn test_lint_proper_nouns_with_user_nouns_and_directives() {
+    let input =
+        "Fedify and Myapi.\n\n<!-- hongdown-proper-nouns: FeDiFy -->\n\nFedify and Myapi.\n";
+    let options = Options {
+        lint_proper_nouns: crate::ProperNounLint::Fix,
+        heading_proper_nouns: vec!["MyAPI".to_string()],
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "Fedify and MyAPI.\n\n<!-- hongdown-proper-nouns: FeDiFy -->\n\nFeDiFy and MyAPI.\n"
     );
-}
-
-#[test]
-fn test_footnote_continuation_indent_matches_prefix() {
-    let input = r#"Text[^note].
 
-[^note]: This is a long footnote with a longer name that should wrap with proper indentation.
-"#;
-    let result = parse_and_serialize_with_source(input);
-    // The continuation line should be indented to align with content after "[^note]: "
-    // "[^note]: " is 9 characters, so continuation should have 9 spaces
-    assert!(
-        result.contains("\n         "), // 9 spaces
-        "Continuation should be indented with 9 spaces to match '[^note]: ', got:\n{}",
-        result
+    let options = Options {
+        lint_proper_nouns: crate::ProperNounLint::Fix,
+        heading_common_nouns: vec!["GitHub".to_string()],
+        ..Options::default()
+    };
+    assert_eq!(
+        crate::format("Push to github.\n", &options).unwrap(),
+        "Push to github.\n"
     );
 }
 
 #[test]
-fn test_table_warns_on_unescaped_pipe_in_cell() {
-    use crate::format_with_warnings;
-
-    let input = r#"| Property | Type | Required |
-|----------|------|----------|
-| `strategy` | `"a" | "b"` | Yes |"#;
-    let result = format_with_warnings(input, &crate::Options::default()).unwrap();
-    assert_eq!(result.warnings.len(), 1);
-    assert!(result.warnings[0].message.contains("unescaped"));
-    assert_eq!(result.warnings[0].line, 3);
+fn test_code_block_as_first_block_of_list_item() {
+    let input = " -  ~~~~ sh\n    ls\n    ~~~~\n";
+    assert_eq!(parse_and_serialize(input), input);
 }
 
 #[test]
-fn test_heading_setext_h1_disabled() {
-    let options = Options {
-        setext_h1: false,
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("# Document Title", &options);
-    assert_eq!(result, "# Document Title\n");
-}
+fn test_extension_autolink_kept_bare() {
+    let options = extensions_options();
+    let input = "See https://example.com/a_b, www.example.com, or foo@example.com.\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
 
-#[test]
-fn test_heading_setext_h1_enabled() {
-    let options = Options {
-        setext_h1: true,
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("# Document Title", &options);
-    assert_eq!(result, "Document Title\n==============\n");
+    // Explicit links are still formatted as usual
+    let input = "See [site](https://example.com/) and <https://example.org/>.\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "See [site] and <https://example.org/>.\n\n[site]: https://example.com/\n"
+    );
 }
 
 #[test]
-fn test_heading_setext_h2_disabled() {
+fn test_wikilinks_kept_verbatim() {
     let options = Options {
-        setext_h2: false,
+        extension_wikilinks: true,
+        line_width: LineWidth::new(40).unwrap(),
         ..Options::default()
     };
-    let result = parse_and_serialize_with_options("## Section Title", &options);
-    assert_eq!(result, "## Section Title\n");
+    let input = "See [[Page]], [[Other Page#Some Heading|the alias text]], and ![[my_image.png]].\n\n\
+                 Not `![[code]]`.\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "See [[Page]],\n[[Other Page#Some Heading|the alias text]],\nand ![[my_image.png]].\n\n\
+         Not `![[code]]`.\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
 }
 
 #[test]
-fn test_heading_setext_h2_enabled() {
+fn test_wikilink_embeds_in_html_and_code_kept() {
     let options = Options {
-        setext_h2: true,
+        extension_wikilinks: true,
         ..Options::default()
     };
-    let result = parse_and_serialize_with_options("## Section Title", &options);
-    assert_eq!(result, "Section Title\n-------------\n");
+    let input = "Para\n\n<div>\n![[in html]]\n</div>\n\n    ![[in code]]\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Para\n\n<div>\n![[in html]]\n</div>\n\n~~~~\n![[in code]]\n~~~~\n"
+    );
 }
 
 #[test]
-fn test_list_unordered_marker_asterisk() {
+fn test_wikilinks_in_heading_keep_case() {
     let options = Options {
-        unordered_marker: crate::UnorderedMarker::Asterisk,
+        extension_wikilinks: true,
+        heading_sentence_case: true,
         ..Options::default()
     };
-    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
-    assert_eq!(result, " *  Item one\n *  Item two\n");
+    let input = "# About [[Home Page|Start Here]] Notes\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "About [[Home Page|Start Here]] notes\n====================================\n"
+    );
 }
 
 #[test]
-fn test_list_unordered_marker_plus() {
-    let options = Options {
-        unordered_marker: crate::UnorderedMarker::Plus,
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
-    assert_eq!(result, " +  Item one\n +  Item two\n");
+fn test_mixed_ordered_unordered_lists() {
+    let input = " 1. Ordered item\n\n -  Unordered item";
+    let result = parse_and_serialize(input);
+    assert!(result.contains("1."));
+    assert!(result.contains("-"));
 }
 
 #[test]
-fn test_list_unordered_marker_default() {
-    let options = Options::default();
-    let result = parse_and_serialize_with_options(" *  Item one\n *  Item two", &options);
-    assert_eq!(result, " -  Item one\n -  Item two\n");
+fn test_horizontal_rule() {
+    let input = "Before\n\n---\n\nAfter";
+    let result = parse_and_serialize(input);
+    // Horizontal rules should be preserved with default style
+    assert!(result.contains("Before"));
+    assert!(
+        result
+            .contains("- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -")
+    );
+    assert!(result.contains("After"));
 }
 
 #[test]
-fn test_list_leading_spaces_zero() {
-    let options = Options {
-        leading_spaces: LeadingSpaces::new(0).unwrap(),
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
-    assert_eq!(result, "-  Item one\n-  Item two\n");
+fn test_thematic_break_default_leading_spaces() {
+    let input = "Before\n\n---\n\nAfter";
+    let result = parse_and_serialize(input);
+    // Default leading_spaces is 3
+    assert!(
+        result.contains(
+            "\n   - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -\n"
+        ),
+        "Expected 3 leading spaces by default, got:\n{}",
+        result
+    );
 }
 
 #[test]
-fn test_list_leading_spaces_two() {
+fn test_thematic_break_custom_style() {
+    let input = "Before\n\n---\n\nAfter";
     let options = Options {
-        leading_spaces: LeadingSpaces::new(2).unwrap(),
+        thematic_break_style: ThematicBreakStyle::new("---".to_string()).unwrap(),
+        thematic_break_leading_spaces: LeadingSpaces::new(0).unwrap(),
         ..Options::default()
     };
-    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
-    assert_eq!(result, "  -  Item one\n  -  Item two\n");
+    let result = parse_and_serialize_with_options(input, &options);
+    assert!(
+        result.contains("\n---\n"),
+        "Expected custom style thematic break, got:\n{}",
+        result
+    );
 }
 
 #[test]
-fn test_list_trailing_spaces_one() {
+fn test_thematic_break_leading_spaces() {
+    let input = "Before\n\n---\n\nAfter";
     let options = Options {
-        trailing_spaces: TrailingSpaces::new(1).unwrap(),
+        thematic_break_style: ThematicBreakStyle::new("*  *  *".to_string()).unwrap(),
+        thematic_break_leading_spaces: LeadingSpaces::new(3).unwrap(),
         ..Options::default()
     };
-    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
-    assert_eq!(result, " - Item one\n - Item two\n");
+    let result = parse_and_serialize_with_options(input, &options);
+    // 3 leading spaces should be applied
+    assert!(
+        result.contains("\n   *  *  *\n"),
+        "Expected 3 leading spaces, got:\n{}",
+        result
+    );
 }
 
 #[test]
-fn test_list_trailing_spaces_three() {
-    let options = Options {
-        trailing_spaces: TrailingSpaces::new(3).unwrap(),
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
-    assert_eq!(result, " -   Item one\n -   Item two\n");
-}
-
-#[test]
-fn test_list_indent_width_two() {
-    // indent_width=2: nested list has 2 spaces indent before " -  " prefix
-    // Result: 2 spaces + " -  " = "   -  " (3 spaces before marker)
-    let options = Options {
-        indent_width: IndentWidth::new(2).unwrap(),
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options(" -  Item one\n     -  Nested", &options);
-    assert_eq!(result, " -  Item one\n   -  Nested\n");
-}
-
-#[test]
-fn test_list_indent_width_default() {
-    // indent_width=4 (default): nested list has 4 spaces indent before " -  " prefix
-    // Result: 4 spaces + " -  " = "     -  " (5 spaces before marker)
-    let options = Options::default();
-    let result = parse_and_serialize_with_options(" -  Item one\n     -  Nested", &options);
-    assert_eq!(result, " -  Item one\n     -  Nested\n");
+fn test_thematic_break_idempotent() {
+    // Test that formatting twice produces the same result (fixes the bug)
+    let input = "Before\n\n---\n\nAfter";
+    let first_pass = parse_and_serialize(input);
+    let second_pass = parse_and_serialize(&first_pass);
+    assert_eq!(
+        first_pass, second_pass,
+        "Thematic break formatting should be idempotent"
+    );
 }
 
 #[test]
-fn test_ordered_list_odd_level_marker() {
-    let options = Options {
-        odd_level_marker: crate::OrderedMarker::Parenthesis,
-        ..Options::default()
-    };
-    // trailing_spaces=2, so "N)  " format
-    let result = parse_and_serialize_with_options("1. First\n2. Second", &options);
-    assert_eq!(result, "1)  First\n2)  Second\n");
+fn test_thematic_break_various_input_styles() {
+    // Test various input styles are normalized to default style
+    let inputs = vec!["---", "***", "___", "- - -", "* * *", "_ _ _"];
+    let expected = "- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
+    for input in inputs {
+        let full_input = format!("Before\n\n{}\n\nAfter", input);
+        let result = parse_and_serialize(&full_input);
+        assert!(
+            result.contains(expected),
+            "Input '{}' should be normalized to '{}', got:\n{}",
+            input,
+            expected,
+            result
+        );
+    }
 }
 
 #[test]
-fn test_ordered_list_even_level_marker() {
-    let options = Options {
-        even_level_marker: crate::OrderedMarker::Period,
-        ..Options::default()
-    };
-    // Nested ordered list (level 2)
-    // trailing_spaces=2, so "N.  " format for nested items
-    let result = parse_and_serialize_with_options(
-        "1. First\n    1. Nested first\n    2. Nested second",
-        &options,
+fn test_unicode_in_heading() {
+    let input = "# 한글 제목";
+    let result = parse_and_serialize(input);
+    assert!(
+        result.contains("한글 제목"),
+        "Unicode heading should be preserved, got:\n{}",
+        result
     );
-    assert!(result.contains("1.  Nested first"), "got: {}", result);
-    assert!(result.contains("2.  Nested second"), "got: {}", result);
 }
 
 #[test]
-fn test_ordered_list_alternating_markers() {
-    let options = Options::default();
-    // Level 1 uses '.', level 2 uses ')'
-    // trailing_spaces=2, so "N.  " for level 1, "N)  " for level 2
-    let result = parse_and_serialize_with_options(
-        "1. First\n    1. Nested first\n    2. Nested second",
-        &options,
+fn test_unicode_in_link_text() {
+    let input = "[한글 링크](https://example.com)";
+    let result = parse_and_serialize(input);
+    assert!(
+        result.contains("한글 링크"),
+        "Unicode in link text should be preserved, got:\n{}",
+        result
     );
-    assert!(result.contains("1.  First"), "got: {}", result);
-    assert!(result.contains("1)  Nested first"), "got: {}", result);
-    assert!(result.contains("2)  Nested second"), "got: {}", result);
 }
 
-use crate::{FenceChar, IndentWidth, LeadingSpaces, MinFenceLength, TrailingSpaces};
-
 #[test]
-fn test_code_block_fence_char_backtick() {
-    let options = Options {
-        fence_char: FenceChar::Backtick,
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
-    assert!(result.starts_with("````"), "got: {}", result);
-    assert!(result.contains("rust"), "got: {}", result);
+fn test_footnote_with_multiple_paragraphs() {
+    let input = "Text[^1]\n\n[^1]: First paragraph of footnote";
+    let result = parse_and_serialize(input);
+    assert!(result.contains("[^1]"));
 }
 
 #[test]
-fn test_code_block_fence_char_default() {
-    let options = Options::default();
-    let result = parse_and_serialize_with_options("``` rust\nfn main() {}\n```", &options);
-    assert!(result.starts_with("~~~~"), "got: {}", result);
+fn test_nested_emphasis() {
+    let input = "***bold and italic***";
+    let result = parse_and_serialize(input);
+    // Should preserve some form of emphasis
+    assert!(result.contains("*"));
 }
 
 #[test]
-fn test_code_block_min_fence_length_three() {
-    let options = Options {
-        min_fence_length: MinFenceLength::new(3).unwrap(),
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
-    assert!(result.starts_with("~~~"), "got: {}", result);
-    assert!(!result.starts_with("~~~~"), "got: {}", result);
+fn test_code_block_with_blank_lines() {
+    let input = "~~~~ text\nline 1\n\nline 3\n~~~~";
+    let result = parse_and_serialize(input);
+    assert!(result.contains("line 1"));
+    assert!(result.contains("line 3"));
 }
 
 #[test]
-fn test_code_block_min_fence_length_six() {
-    let options = Options {
-        min_fence_length: MinFenceLength::new(6).unwrap(),
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
-    assert!(result.starts_with("~~~~~~"), "got: {}", result);
+fn test_gfm_task_list_checked() {
+    let input = " - [x] Completed task";
+    let result = parse_and_serialize(input);
+    assert_eq!(result, " -  [x] Completed task\n");
 }
 
 #[test]
-fn test_code_block_space_after_fence_false() {
-    let options = Options {
-        space_after_fence: false,
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
-    assert!(result.contains("~~~~rust"), "got: {}", result);
+fn test_gfm_task_list_unchecked() {
+    let input = " - [ ] Pending task";
+    let result = parse_and_serialize(input);
+    assert_eq!(result, " -  [ ] Pending task\n");
 }
 
 #[test]
-fn test_code_block_space_after_fence_true() {
-    let options = Options {
-        space_after_fence: true,
-        ..Options::default()
-    };
-    let result = parse_and_serialize_with_options("~~~~rust\nfn main() {}\n~~~~", &options);
-    assert!(result.contains("~~~~ rust"), "got: {}", result);
+fn test_gfm_task_list_mixed() {
+    let input = " - [x] Done\n - [ ] Todo\n - [x] Also done";
+    let result = parse_and_serialize(input);
+    assert!(result.contains("[x] Done"));
+    assert!(result.contains("[ ] Todo"));
+    assert!(result.contains("[x] Also done"));
 }
 
 #[test]
-fn test_ordered_list_long_list() {
-    // For a list with 10+ items, marker width stays fixed at 4
-    // Single-digit: "N.  " (2 trailing), double-digit: "NN. " (1 trailing)
-    let input =
-        "1. One\n2. Two\n3. Three\n4. Four\n5. Five\n6. Six\n7. Seven\n8. Eight\n9. Nine\n10. Ten";
+fn test_gfm_task_list_nested() {
+    let input = " - [x] Parent task\n    - [ ] Child task";
     let result = parse_and_serialize(input);
-    // Single-digit numbers have 2 trailing spaces
-    assert!(result.contains("1.  One"), "got:\n{}", result);
-    assert!(result.contains("9.  Nine"), "got:\n{}", result);
-    // Double-digit numbers have 1 trailing space to maintain 4-char marker width
-    assert!(result.contains("10. Ten"), "got:\n{}", result);
+    assert!(result.contains("[x] Parent task"));
+    assert!(result.contains("[ ] Child task"));
 }
 
 #[test]
-fn test_ordered_list_pad_small_list() {
-    // For lists with only single-digit items, no extra padding is needed
-    let input = "1. One\n2. Two\n3. Three";
+fn test_definition_list_no_extra_blank_line() {
+    let input = "Term\n:   Definition here";
     let result = parse_and_serialize(input);
-    // No extra padding since max number is single-digit
-    assert!(result.contains("1.  One"), "got:\n{}", result);
-    assert!(result.contains("2.  Two"), "got:\n{}", result);
-    assert!(result.contains("3.  Three"), "got:\n{}", result);
+    assert_eq!(result, "Term\n:   Definition here\n");
 }
 
 #[test]
-fn test_ordered_list_nested_long() {
-    // Nested ordered lists maintain fixed 4-char marker width
-    let input = "1. Parent one\n2. Parent two\n    1. Child one\n    2. Child two\n    3. Child three\n    4. Child four\n    5. Child five\n    6. Child six\n    7. Child seven\n    8. Child eight\n    9. Child nine\n    10. Child ten";
+fn test_definition_list_multiple_items() {
+    let input = "Term1\n:   Definition1\n\nTerm2\n:   Definition2";
     let result = parse_and_serialize(input);
-    // Parent list: "N.  " format
-    assert!(result.contains("1.  Parent one"), "got:\n{}", result);
-    assert!(result.contains("2.  Parent two"), "got:\n{}", result);
-    // Child list has 10 items, nested with 4-space indent
-    // Single-digit: 4 spaces + "N)  " (4 chars) = 8 total indent
-    // Double-digit: 4 spaces + "NN) " (4 chars) = 8 total indent
-    assert!(result.contains("    1)  Child one"), "got:\n{}", result);
-    assert!(result.contains("    9)  Child nine"), "got:\n{}", result);
-    assert!(result.contains("    10) Child ten"), "got:\n{}", result);
+    assert!(result.contains("Term1\n:   Definition1"));
+    assert!(result.contains("Term2\n:   Definition2"));
+    // Should have blank line between items, but not between term and definition
+    assert!(!result.contains("Term1\n\n:"));
 }
 
-// Tests for undefined reference warnings
-
 #[test]
-fn test_undefined_reference_warning() {
-    // When a reference link is used but not defined, a warning should be emitted
-    let input = "See [undefined reference] for details.";
-    let result = parse_and_serialize_with_warnings(input);
-    assert_eq!(result.warnings.len(), 1);
-    assert!(result.warnings[0].message.contains("undefined reference"));
-    assert!(
-        result.warnings[0]
-            .message
-            .contains("undefined reference link")
-    );
+fn test_abbreviation_definition_preserved() {
+    let input = "*[JSX]: JavaScript XML";
+    let result = parse_and_serialize_with_source(input);
+    assert_eq!(result, "*[JSX]: JavaScript XML\n");
 }
 
 #[test]
-fn test_defined_reference_no_warning() {
-    // When a reference link is properly defined, no warning should be emitted
-    let input = "See [defined reference] for details.\n\n[defined reference]: https://example.com";
-    let result = parse_and_serialize_with_warnings(input);
-    assert_eq!(
-        result.warnings.len(),
-        0,
-        "Expected no warnings but got: {:?}",
-        result.warnings
-    );
+fn test_abbreviation_definition_multiple() {
+    let input = "*[HTML]: HyperText Markup Language\n\n*[CSS]: Cascading Style Sheets";
+    let result = parse_and_serialize_with_source(input);
+    assert!(result.contains("*[HTML]: HyperText Markup Language"));
+    assert!(result.contains("*[CSS]: Cascading Style Sheets"));
 }
 
 #[test]
-fn test_multiple_undefined_references_warning() {
-    // Multiple undefined references should each generate a warning
-    let input = "See [foo] and [bar] for details.\n\n[foo]: https://example.com";
-    let result = parse_and_serialize_with_warnings(input);
-    assert_eq!(
-        result.warnings.len(),
-        1,
-        "Expected 1 warning for [bar] but got: {:?}",
-        result.warnings
-    );
-    assert!(result.warnings[0].message.contains("bar"));
+fn test_definition_list_with_list_as_first_child() {
+    let input = "Pros\n:    -  First item\n     -  Second item";
+    let result = parse_and_serialize(input);
+    // The list should be on the same line as the colon to ensure idempotent formatting
+    // Format: `:    -  First item` (colon + 4 spaces + list marker)
+    assert!(result.contains("Pros\n:    -  First item"));
+    assert!(result.contains("     -  Second item"));
+}
+
+#[test]
+fn test_reference_used_in_multiple_sections_not_duplicated() {
+    let input = r#"Section
+-------
+
+### First
+
+Text with [link].
+
+### Second
+
+More text with [link].
+
+[link]: https://example.com
+"#;
+    let result = parse_and_serialize_with_source(input);
+    // The reference should appear only once, after first use
+    let count = result.matches("[link]: https://example.com").count();
+    assert_eq!(
+        count, 1,
+        "Reference should appear exactly once, but found {} times in:\n{}",
+        count, result
+    );
+}
+
+#[test]
+fn test_heading_with_reference_link() {
+    let input = r#"[BotKit] by Fedify
+==================
+
+[BotKit]: https://botkit.fedify.dev/
+"#;
+    let result = parse_and_serialize_with_source(input);
+    // The reference link in the heading should be preserved
+    assert!(
+        result.contains("[BotKit] by Fedify"),
+        "Heading should contain reference link syntax, got:\n{}",
+        result
+    );
+    assert!(
+        result.contains("[BotKit]: https://botkit.fedify.dev/"),
+        "Reference definition should be preserved, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_footnote_at_section_end_before_subheading() {
+    let input = r#"Section
+-------
+
+Text with footnote[^1].
+
+### Subsection
+
+More text here.
+
+[^1]: This is a footnote.
+"#;
+    let result = parse_and_serialize_with_source(input);
+    // Footnote should appear before the subsection, not at document end
+    let footnote_pos = result.find("[^1]: This is a footnote.").unwrap();
+    let subsection_pos = result.find("### Subsection").unwrap();
+    assert!(
+        footnote_pos < subsection_pos,
+        "Footnote should appear before subsection, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_footnote_definition_wrapped_at_80_chars() {
+    let input = r#"Text[^1].
+
+[^1]: This is a very long footnote definition that definitely exceeds eighty characters and should be wrapped.
+"#;
+    let result = parse_and_serialize_with_source(input);
+    // Check that no line exceeds 80 characters
+    for line in result.lines() {
+        assert!(
+            line.len() <= 80,
+            "Line exceeds 80 characters: '{}' (len={})",
+            line,
+            line.len()
+        );
+    }
+    // Should still contain the footnote content
+    assert!(
+        result.contains("This is a very long footnote"),
+        "Footnote content should be preserved, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_footnote_continuation_indent_matches_prefix() {
+    let input = r#"Text[^note].
+
+[^note]: This is a long footnote with a longer name that should wrap with proper indentation.
+"#;
+    let result = parse_and_serialize_with_source(input);
+    // The continuation line should be indented to align with content after "[^note]: "
+    // "[^note]: " is 9 characters, so continuation should have 9 spaces
+    assert!(
+        result.contains("\n         "), // 9 spaces
+        "Continuation should be indented with 9 spaces to match '[^note]: ', got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_table_warns_on_unescaped_pipe_in_cell() {
+    use crate::format_with_warnings;
+
+    let input = r#"| Property | Type | Required |
+|----------|------|----------|
+| `strategy` | `"a" | "b"` | Yes |"#;
+    let result = format_with_warnings(input, &crate::Options::default()).unwrap();
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].message.contains("unescaped"));
+    assert_eq!(result.warnings[0].line, 3);
+}
+
+#[test]
+fn test_heading_setext_h1_disabled() {
+    let options = Options {
+        setext_h1: false,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("# Document Title", &options);
+    assert_eq!(result, "# Document Title\n");
+}
+
+#[test]
+fn test_heading_setext_h1_enabled() {
+    let options = Options {
+        setext_h1: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("# Document Title", &options);
+    assert_eq!(result, "Document Title\n==============\n");
+}
+
+#[test]
+fn test_heading_setext_h2_disabled() {
+    let options = Options {
+        setext_h2: false,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("## Section Title", &options);
+    assert_eq!(result, "## Section Title\n");
+}
+
+#[test]
+fn test_heading_setext_h2_enabled() {
+    let options = Options {
+        setext_h2: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("## Section Title", &options);
+    assert_eq!(result, "Section Title\n-------------\n");
+}
+
+#[test]
+fn test_list_unordered_marker_asterisk() {
+    let options = Options {
+        unordered_marker: crate::UnorderedMarker::Asterisk,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
+    assert_eq!(result, " *  Item one\n *  Item two\n");
+}
+
+#[test]
+fn test_list_unordered_marker_plus() {
+    let options = Options {
+        unordered_marker: crate::UnorderedMarker::Plus,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
+    assert_eq!(result, " +  Item one\n +  Item two\n");
+}
+
+#[test]
+fn test_list_unordered_marker_default() {
+    let options = Options::default();
+    let result = parse_and_serialize_with_options(" *  Item one\n *  Item two", &options);
+    assert_eq!(result, " -  Item one\n -  Item two\n");
+}
+
+#[test]
+fn test_list_leading_spaces_zero() {
+    let options = Options {
+        leading_spaces: LeadingSpaces::new(0).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
+    assert_eq!(result, "-  Item one\n-  Item two\n");
+}
+
+#[test]
+fn test_list_leading_spaces_two() {
+    let options = Options {
+        leading_spaces: LeadingSpaces::new(2).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
+    assert_eq!(result, "  -  Item one\n  -  Item two\n");
+}
+
+#[test]
+fn test_list_trailing_spaces_one() {
+    let options = Options {
+        trailing_spaces: TrailingSpaces::new(1).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
+    assert_eq!(result, " - Item one\n - Item two\n");
+}
+
+#[test]
+fn test_list_trailing_spaces_three() {
+    let options = Options {
+        trailing_spaces: TrailingSpaces::new(3).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n -  Item two", &options);
+    assert_eq!(result, " -   Item one\n -   Item two\n");
+}
+
+#[test]
+fn test_list_indent_width_two() {
+    // indent_width=2: nested list has 2 spaces indent before " -  " prefix
+    // Result: 2 spaces + " -  " = "   -  " (3 spaces before marker)
+    let options = Options {
+        indent_width: IndentWidth::new(2).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(" -  Item one\n     -  Nested", &options);
+    assert_eq!(result, " -  Item one\n   -  Nested\n");
+}
+
+#[test]
+fn test_list_indent_width_default() {
+    // indent_width=4 (default): nested list has 4 spaces indent before " -  " prefix
+    // Result: 4 spaces + " -  " = "     -  " (5 spaces before marker)
+    let options = Options::default();
+    let result = parse_and_serialize_with_options(" -  Item one\n     -  Nested", &options);
+    assert_eq!(result, " -  Item one\n     -  Nested\n");
+}
+
+#[test]
+fn test_ordered_list_odd_level_marker() {
+    let options = Options {
+        odd_level_marker: crate::OrderedMarker::Parenthesis,
+        ..Options::default()
+    };
+    // trailing_spaces=2, so "N)  " format
+    let result = parse_and_serialize_with_options("1. First\n2. Second", &options);
+    assert_eq!(result, "1)  First\n2)  Second\n");
+}
+
+#[test]
+fn test_ordered_list_even_level_marker() {
+    let options = Options {
+        even_level_marker: crate::OrderedMarker::Period,
+        ..Options::default()
+    };
+    // Nested ordered list (level 2)
+    // trailing_spaces=2, so "N.  " format for nested items
+    let result = parse_and_serialize_with_options(
+        "1. First\n    1. Nested first\n    2. Nested second",
+        &options,
+    );
+    assert!(result.contains("1.  Nested first"), "got: {}", result);
+    assert!(result.contains("2.  Nested second"), "got: {}", result);
+}
+
+#[test]
+fn test_ordered_list_alternating_markers() {
+    let options = Options::default();
+    // Level 1 uses '.', level 2 uses ')'
+    // trailing_spaces=2, so "N.  " for level 1, "N)  " for level 2
+    let result = parse_and_serialize_with_options(
+        "1. First\n    1. Nested first\n    2. Nested second",
+        &options,
+    );
+    assert!(result.contains("1.  First"), "got: {}", result);
+    assert!(result.contains("1)  Nested first"), "got: {}", result);
+    assert!(result.contains("2)  Nested second"), "got: {}", result);
+}
+
+use crate::{FenceChar, IndentWidth, LeadingSpaces, MinFenceLength, TrailingSpaces};
+
+#[test]
+fn test_code_block_fence_char_backtick() {
+    let options = Options {
+        fence_char: FenceChar::Backtick,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
+    assert!(result.starts_with("````"), "got: {}", result);
+    assert!(result.contains("rust"), "got: {}", result);
+}
+
+#[test]
+fn test_code_block_fence_char_default() {
+    let options = Options::default();
+    let result = parse_and_serialize_with_options("``` rust\nfn main() {}\n```", &options);
+    assert!(result.starts_with("~~~~"), "got: {}", result);
+}
+
+#[test]
+fn test_code_block_min_fence_length_three() {
+    let options = Options {
+        min_fence_length: MinFenceLength::new(3).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
+    assert!(result.starts_with("~~~"), "got: {}", result);
+    assert!(!result.starts_with("~~~~"), "got: {}", result);
+}
+
+#[test]
+fn test_code_block_min_fence_length_six() {
+    let options = Options {
+        min_fence_length: MinFenceLength::new(6).unwrap(),
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
+    assert!(result.starts_with("~~~~~~"), "got: {}", result);
+}
+
+#[test]
+fn test_code_block_space_after_fence_false() {
+    let options = Options {
+        space_after_fence: false,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("~~~~ rust\nfn main() {}\n~~~~", &options);
+    assert!(result.contains("~~~~rust"), "got: {}", result);
+}
+
+#[test]
+fn test_code_block_space_after_fence_true() {
+    let options = Options {
+        space_after_fence: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("~~~~rust\nfn main() {}\n~~~~", &options);
+    assert!(result.contains("~~~~ rust"), "got: {}", result);
+}
+
+#[test]
+fn test_ordered_list_long_list() {
+    // For a list with 10+ items, marker width stays fixed at 4
+    // Single-digit: "N.  " (2 trailing), double-digit: "NN. " (1 trailing)
+    let input =
+        "1. One\n2. Two\n3. Three\n4. Four\n5. Five\n6. Six\n7. Seven\n8. Eight\n9. Nine\n10. Ten";
+    let result = parse_and_serialize(input);
+    // Single-digit numbers have 2 trailing spaces
+    assert!(result.contains("1.  One"), "got:\n{}", result);
+    assert!(result.contains("9.  Nine"), "got:\n{}", result);
+    // Double-digit numbers have 1 trailing space to maintain 4-char marker width
+    assert!(result.contains("10. Ten"), "got:\n{}", result);
+}
+
+#[test]
+fn test_ordered_list_pad_small_list() {
+    // For lists with only single-digit items, no extra padding is needed
+    let input = "1. One\n2. Two\n3. Three";
+    let result = parse_and_serialize(input);
+    // No extra padding since max number is single-digit
+    assert!(result.contains("1.  One"), "got:\n{}", result);
+    assert!(result.contains("2.  Two"), "got:\n{}", result);
+    assert!(result.contains("3.  Three"), "got:\n{}", result);
+}
+
+#[test]
+fn test_ordered_list_nested_long() {
+    // Nested ordered lists maintain fixed 4-char marker width
+    let input = "1. Parent one\n2. Parent two\n    1. Child one\n    2. Child two\n    3. Child three\n    4. Child four\n    5. Child five\n    6. Child six\n    7. Child seven\n    8. Child eight\n    9. Child nine\n    10. Child ten";
+    let result = parse_and_serialize(input);
+    // Parent list: "N.  " format
+    assert!(result.contains("1.  Parent one"), "got:\n{}", result);
+    assert!(result.contains("2.  Parent two"), "got:\n{}", result);
+    // Child list has 10 items, nested with 4-space indent
+    // Single-digit: 4 spaces + "N)  " (4 chars) = 8 total indent
+    // Double-digit: 4 spaces + "NN) " (4 chars) = 8 total indent
+    assert!(result.contains("    1)  Child one"), "got:\n{}", result);
+    assert!(result.contains("    9)  Child nine"), "got:\n{}", result);
+    assert!(result.contains("    10) Child ten"), "got:\n{}", result);
+}
+
+// Tests for undefined reference warnings
+
+#[test]
+fn test_undefined_reference_warning() {
+    // When a reference link is used but not defined, a warning should be emitted
+    let input = "See [undefined reference] for details.";
+    let result = parse_and_serialize_with_warnings(input);
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].message.contains("undefined reference"));
+    assert!(
+        result.warnings[0]
+            .message
+            .contains("undefined reference link")
+    );
+}
+
+#[test]
+fn test_defined_reference_no_warning() {
+    // When a reference link is properly defined, no warning should be emitted
+    let input = "See [defined reference] for details.\n\n[defined reference]: https://example.com";
+    let result = parse_and_serialize_with_warnings(input);
+    assert_eq!(
+        result.warnings.len(),
+        0,
+        "Expected no warnings but got: {:?}",
+        result.warnings
+    );
+}
+
+#[test]
+fn test_multiple_undefined_references_warning() {
+    // Multiple undefined references should each generate a warning
+    let input = "See [foo] and [bar] for details.\n\n[foo]: https://example.com";
+    let result = parse_and_serialize_with_warnings(input);
+    assert_eq!(
+        result.warnings.len(),
+        1,
+        "Expected 1 warning for [bar] but got: {:?}",
+        result.warnings
+    );
+    assert!(result.warnings[0].message.contains("bar"));
 }
 
 #[test]
@@ -3291,8 +3880,10 @@ fn test_punctuation_curly_double_quotes_in_paragraph() {
 
 #[test]
 fn test_punctuation_curly_double_quotes_disabled() {
-    let mut options = Options::default();
-    options.curly_double_quotes = false;
+    let options = Options {
+        curly_double_quotes: false,
+        ..Options::default()
+    };
     let input = "He said \"hello\" to her.";
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "He said \"hello\" to her.\n");
@@ -3312,8 +3903,10 @@ fn test_punctuation_curly_single_quotes_in_paragraph() {
 
 #[test]
 fn test_punctuation_curly_single_quotes_disabled() {
-    let mut options = Options::default();
-    options.curly_single_quotes = false;
+    let options = Options {
+        curly_single_quotes: false,
+        ..Options::default()
+    };
     let input = "She said 'hello' to him.";
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "She said 'hello' to him.\n");
@@ -3330,8 +3923,10 @@ fn test_punctuation_ellipsis_in_paragraph() {
 
 #[test]
 fn test_punctuation_ellipsis_disabled() {
-    let mut options = Options::default();
-    options.ellipsis = false;
+    let options = Options {
+        ellipsis: false,
+        ..Options::default()
+    };
     let input = "Wait for it...";
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "Wait for it...\n");
@@ -3348,8 +3943,10 @@ fn test_punctuation_em_dash_default() {
 
 #[test]
 fn test_punctuation_em_dash_disabled() {
-    let mut options = Options::default();
-    options.em_dash = crate::DashSetting::Disabled;
+    let options = Options {
+        em_dash: crate::DashSetting::Disabled,
+        ..Options::default()
+    };
     let input = "Hello--world";
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "Hello--world\n");
@@ -3357,9 +3954,10 @@ fn test_punctuation_em_dash_disabled() {
 
 #[test]
 fn test_punctuation_em_dash_triple_hyphen() {
-    let mut options = Options::default();
-    options.em_dash =
-        crate::DashSetting::Pattern(crate::DashPattern::new("---".to_string()).unwrap());
+    let options = Options {
+        em_dash: crate::DashSetting::Pattern(crate::DashPattern::new("---".to_string()).unwrap()),
+        ..Options::default()
+    };
     let input = "Hello---world";
     let result = parse_and_serialize_with_options(input, &options);
     let expected = format!("Hello{}world\n", EM_DASH);
@@ -3378,11 +3976,11 @@ fn test_punctuation_en_dash_disabled_by_default() {
 
 #[test]
 fn test_punctuation_en_dash_enabled() {
-    let mut options = Options::default();
-    options.em_dash =
-        crate::DashSetting::Pattern(crate::DashPattern::new("---".to_string()).unwrap());
-    options.en_dash =
-        crate::DashSetting::Pattern(crate::DashPattern::new("--".to_string()).unwrap());
+    let options = Options {
+        em_dash: crate::DashSetting::Pattern(crate::DashPattern::new("---".to_string()).unwrap()),
+        en_dash: crate::DashSetting::Pattern(crate::DashPattern::new("--".to_string()).unwrap()),
+        ..Options::default()
+    };
     let input = "Pages 10--20 and a long---dash";
     let result = parse_and_serialize_with_options(input, &options);
     let expected = format!("Pages 10{}20 and a long{}dash\n", EN_DASH, EM_DASH);
@@ -3399,8 +3997,10 @@ fn test_punctuation_apostrophes_disabled_by_default() {
 
 #[test]
 fn test_punctuation_apostrophes_enabled() {
-    let mut options = Options::default();
-    options.curly_apostrophes = true;
+    let options = Options {
+        curly_apostrophes: true,
+        ..Options::default()
+    };
     let input = "It's a test";
     let result = parse_and_serialize_with_options(input, &options);
     let expected = format!("It{}s a test\n", RIGHT_SINGLE_QUOTE);
@@ -3486,8 +4086,10 @@ fn test_punctuation_preserve_existing_curly_quotes() {
 fn test_punctuation_all_transforms_combined() {
     // Test multiple punctuation transforms in one paragraph
     let input = "He said \"It's... amazing--isn't it?\"";
-    let mut options = Options::default();
-    options.curly_apostrophes = true;
+    let options = Options {
+        curly_apostrophes: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
 
     // Should have curly double quotes
@@ -3518,13 +4120,15 @@ fn test_punctuation_all_transforms_combined() {
 
 #[test]
 fn test_punctuation_all_disabled() {
-    let mut options = Options::default();
-    options.curly_double_quotes = false;
-    options.curly_single_quotes = false;
-    options.curly_apostrophes = false;
-    options.ellipsis = false;
-    options.em_dash = crate::DashSetting::Disabled;
-    options.en_dash = crate::DashSetting::Disabled;
+    let options = Options {
+        curly_double_quotes: false,
+        curly_single_quotes: false,
+        curly_apostrophes: false,
+        ellipsis: false,
+        em_dash: crate::DashSetting::Disabled,
+        en_dash: crate::DashSetting::Disabled,
+        ..Options::default()
+    };
 
     let input = "He said \"It's... amazing--isn't it?\"";
     let result = parse_and_serialize_with_options(input, &options);
@@ -3542,98 +4146,387 @@ fn test_punctuation_bracket_possessive_stays_straight() {
     // The apostrophe should remain straight
     assert_eq!(
         result,
-        "This package provides [Fedify]'s API.\n\n[Fedify]: https://fedify.dev/\n"
+        "This package provides [Fedify]'s API.\n\n[Fedify]: https://fedify.dev/\n"
+    );
+}
+
+#[test]
+fn test_punctuation_bracket_possessive_curly_when_enabled() {
+    // Possessive apostrophe after closing bracket should become curly
+    // when curly_apostrophes is enabled
+    let options = Options {
+        curly_apostrophes: true,
+        ..Options::default()
+    };
+
+    let input = "This package provides [Fedify]'s API.\n\n[Fedify]: https://fedify.dev/\n";
+    let result = parse_and_serialize_with_options(input, &options);
+    let expected = format!(
+        "This package provides [Fedify]{}s API.\n\n[Fedify]: https://fedify.dev/\n",
+        RIGHT_SINGLE_QUOTE
+    );
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_punctuation_decade_abbreviation() {
+    // '80s style decade abbreviations
+    let input = "The '80s were great.";
+    let result = parse_and_serialize(input);
+    // The apostrophe before the decade should become right single quote
+    let expected = format!("The {}80s were great.\n", RIGHT_SINGLE_QUOTE);
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_punctuation_single_hyphen_em_dash_with_spaces() {
+    // Single hyphen with spaces should transform when em_dash = "-"
+    let options = Options {
+        em_dash: crate::DashSetting::Pattern(crate::DashPattern::new("-".to_string()).unwrap()),
+        ..Options::default()
+    };
+    let input = "word - word";
+    let result = parse_and_serialize_with_options(input, &options);
+    let expected = format!("word {} word\n", EM_DASH);
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_punctuation_single_hyphen_em_dash_without_spaces() {
+    // Single hyphen without spaces should NOT transform when em_dash = "-"
+    let options = Options {
+        em_dash: crate::DashSetting::Pattern(crate::DashPattern::new("-".to_string()).unwrap()),
+        ..Options::default()
+    };
+    let input = "word-word";
+    let result = parse_and_serialize_with_options(input, &options);
+    // Hyphen should remain because it's not surrounded by spaces
+    assert_eq!(result, "word-word\n");
+}
+
+#[test]
+fn test_nested_list_followed_by_paragraph_no_extra_blank_line() {
+    // Regression test: When a list item contains a nested list followed by a paragraph,
+    // there should be exactly one blank line between them, not two.
+    let input = " -  Foo bar.
+
+     -  Baz.
+     -  Qux.
+
+    Quux.
+
+ -  Another item.
+";
+    let result = parse_and_serialize(input);
+    // There should be only one blank line between the nested list and "Quux."
+    // (i.e., "\n\n    Quux", not "\n\n\n    Quux")
+    assert!(
+        !result.contains("\n\n\n"),
+        "Should not have double blank lines between nested list and following paragraph.\nGot:\n{}",
+        result
+    );
+    assert_eq!(result, input, "Output should match input exactly");
+}
+
+#[test]
+fn test_heading_sentence_case_basic() {
+    let input = "# Hello World";
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(result, "Hello world\n===========\n");
+}
+
+#[test]
+fn test_heading_title_case() {
+    let input = "# a guide to the `foo` API\n\n### working with files in rust {#files}\n";
+    let options = Options {
+        heading_case: crate::HeadingCase::Title,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "A Guide to the `foo` API\n========================\n\n\
+         ### Working with Files in Rust {#files}\n"
+    );
+}
+
+#[test]
+fn test_heading_title_case_keeps_versions_and_link_labels() {
+    let input = "# changelog\n\n## [v0.1.1] - 2019-01-01\n\n## [unreleased][] changes\n\n\
+                 [v0.1.1]: https://example.com/v0.1.1\n[unreleased]: https://example.com/compare\n";
+    let options = Options {
+        heading_case: crate::HeadingCase::Title,
+        ..Options::default()
+    };
+    let result = crate::format(input, &options).unwrap();
+    assert!(result.contains("\n[v0.1.1] - 2019-01-01\n"));
+    assert!(result.contains("\n[unreleased][] Changes\n"));
+    assert!(result.contains("\n[v0.1.1]: https://example.com/v0.1.1\n"));
+    assert_eq!(crate::format(&result, &options).unwrap(), result);
+}
+
+#[test]
+fn test_heading_case_overrides_sentence_case() {
+    let input = "## the end of the road\n";
+    let options = Options {
+        heading_sentence_case: true,
+        heading_case: crate::HeadingCase::Title,
+        heading_title_case_style: crate::TitleCaseStyle::Ap,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(result, "The End of the Road\n-------------------\n");
+}
+
+#[test]
+fn test_heading_levels_normalized() {
+    let input = "### Title\n\nText.\n\n##### Section\n\n#### Other\n";
+    let options = Options {
+        heading_promote_first: true,
+        heading_fix_skipped_levels: true,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "Title\n=====\n\nText.\n\n\nSection\n-------\n\nOther\n-----\n"
+    );
+    let warnings: Vec<_> = result
+        .warnings
+        .iter()
+        .map(|w| (w.line, w.message.as_str()))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (1, "heading level changed from 3 to 1"),
+            (5, "heading level changed from 5 to 2"),
+            (7, "heading level changed from 4 to 2"),
+        ]
+    );
+}
+
+#[test]
+fn test_heading_top_level() {
+    let input = "# Title\n\n## Section\n";
+    let options = Options {
+        heading_top_level: 2,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(result, "Title\n-----\n\n### Section\n");
+    assert_eq!(parse_and_serialize_with_options(&result, &options), result);
+}
+
+#[test]
+fn test_heading_top_level_in_quotes_and_lists() {
+    let input = "## Section\n\n> ### Quoted\n\n -  #### Listed\n";
+    let options = Options {
+        heading_top_level: 1,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Section\n=======\n\n> ## Quoted\n\n -  ### Listed\n"
+    );
+}
+
+#[test]
+fn test_heading_in_block_quote_and_list_item() {
+    let input = "Para\n\n> ## Quoted\n>\n> Text.\n\n - ## Listed\n\n   Text.\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert_eq!(
+        result,
+        "Para\n\n> ## Quoted\n>\n> Text.\n\n -  ## Listed\n\n    Text.\n"
+    );
+    assert_eq!(crate::format(&result, &Options::default()).unwrap(), result);
+}
+
+#[test]
+fn test_heading_top_level_clamped() {
+    let input = "# Title\n\n##### Deep\n\n###### Deepest\n";
+    let options = Options {
+        heading_top_level: 3,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "### Title\n\n###### Deep\n\n###### Deepest\n"
+    );
+    let warnings: Vec<_> = result
+        .warnings
+        .iter()
+        .map(|w| (w.line, w.message.as_str()))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (1, "heading level changed from 1 to 3"),
+            (
+                3,
+                "heading level 5 would be shifted to 7, which is out of range; using 6 instead"
+            ),
+            (
+                5,
+                "heading level 6 would be shifted to 8, which is out of range; using 6 instead"
+            ),
+        ]
+    );
+    let again = crate::format_with_warnings(&result.output, &options).unwrap();
+    assert_eq!(again.output, result.output);
+    assert!(again.warnings.is_empty());
+}
+
+#[test]
+fn test_heading_numbering() {
+    let input = "# Spec\n\n## 2. the intro\n\n### Goals\n\n### Notes {-}\n\n\
+                 ### 1.1. non-goals\n\n## 2.3. Transport\n";
+    let options = Options {
+        heading_sentence_case: true,
+        heading_numbering: true,
+        heading_numbering_max_level: 3,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Spec\n====\n\n1 The intro\n-----------\n\n### 1.1 Goals\n\n### Notes {-}\n\n\
+         ### 1.2 Non-goals\n\n2 Transport\n-----------\n"
+    );
+}
+
+#[test]
+fn test_heading_numbering_keeps_years_and_versions() {
+    let input = "## 2023 Results\n\n## 1.0 Release\n\n## 3 Plans\n";
+    let options = Options {
+        heading_numbering: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "1 2023 Results\n--------------\n\n2 1.0 Release\n-------------\n\n\
+         3 Plans\n-------\n"
     );
+    assert_eq!(parse_and_serialize_with_options(&result, &options), result);
 }
 
 #[test]
-fn test_punctuation_bracket_possessive_curly_when_enabled() {
-    // Possessive apostrophe after closing bracket should become curly
-    // when curly_apostrophes is enabled
-    let mut options = Options::default();
-    options.curly_apostrophes = true;
-
-    let input = "This package provides [Fedify]'s API.\n\n[Fedify]: https://fedify.dev/\n";
+fn test_heading_numbering_without_headings_at_min_level() {
+    let input = "# T\n\n### A\n\n#### B\n\n## C\n\n#### D\n\n### E\n";
+    let options = Options {
+        heading_numbering: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
-    let expected = format!(
-        "This package provides [Fedify]{}s API.\n\n[Fedify]: https://fedify.dev/\n",
-        RIGHT_SINGLE_QUOTE
+    assert_eq!(
+        result,
+        "T\n=\n\n### 1 A\n\n#### 1.1 B\n\n2 C\n---\n\n#### 2.1 D\n\n### 2.2 E\n"
     );
-    assert_eq!(result, expected);
+    assert_eq!(parse_and_serialize_with_options(&result, &options), result);
 }
 
 #[test]
-fn test_punctuation_decade_abbreviation() {
-    // '80s style decade abbreviations
-    let input = "The '80s were great.";
-    let result = parse_and_serialize(input);
-    // The apostrophe before the decade should become right single quote
-    let expected = format!("The {}80s were great.\n", RIGHT_SINGLE_QUOTE);
-    assert_eq!(result, expected);
+fn test_heading_numbering_replaces_numbers_of_any_depth() {
+    let input = "# Spec\n\n## Intro\n\n### Detail\n";
+    let options = Options {
+        heading_numbering: true,
+        heading_numbering_min_level: 1,
+        ..Options::default()
+    };
+    let numbered = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        numbered,
+        "1 Spec\n======\n\n1.1 Intro\n---------\n\n### 1.1.1 Detail\n"
+    );
+    let options = Options {
+        heading_numbering: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(&numbered, &options);
+    // Headings out of the range are left as they are
+    assert_eq!(
+        result,
+        "1 Spec\n======\n\n1 Intro\n-------\n\n### 1.1 Detail\n"
+    );
+    assert_eq!(parse_and_serialize_with_options(&result, &options), result);
 }
 
 #[test]
-fn test_punctuation_single_hyphen_em_dash_with_spaces() {
-    // Single hyphen with spaces should transform when em_dash = "-"
-    let mut options = Options::default();
-    options.em_dash =
-        crate::DashSetting::Pattern(crate::DashPattern::new("-".to_string()).unwrap());
-    let input = "word - word";
+fn test_heading_numbering_plain_numbers() {
+    let input = "## 3 Ways to Win\n\n## Intro\n\n## 3 Setup\n";
+    let options = Options {
+        heading_numbering: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
-    let expected = format!("word {} word\n", EM_DASH);
-    assert_eq!(result, expected);
+    assert_eq!(
+        result,
+        "1 3 Ways to Win\n---------------\n\n2 Intro\n-------\n\n3 Setup\n-------\n"
+    );
+    assert_eq!(parse_and_serialize_with_options(&result, &options), result);
+
+    let options = Options {
+        heading_numbering: true,
+        heading_numbering_replace_plain: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options("## 2 B\n\n## 1 A\n", &options);
+    assert_eq!(result, "1 B\n---\n\n2 A\n---\n");
 }
 
 #[test]
-fn test_punctuation_single_hyphen_em_dash_without_spaces() {
-    // Single hyphen without spaces should NOT transform when em_dash = "-"
-    let mut options = Options::default();
-    options.em_dash =
-        crate::DashSetting::Pattern(crate::DashPattern::new("-".to_string()).unwrap());
-    let input = "word-word";
-    let result = parse_and_serialize_with_options(input, &options);
-    // Hyphen should remain because it's not surrounded by spaces
-    assert_eq!(result, "word-word\n");
+fn test_heading_numbering_toc_and_anchors() {
+    let input =
+        "<!-- hongdown-toc -->\n<!-- /hongdown-toc -->\n\n## Usage\n\nSee [usage](#1-usage).\n";
+    let options = Options {
+        heading_numbering: true,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "<!-- hongdown-toc -->\n\n -  [1 Usage](#1-usage)\n\n<!-- /hongdown-toc -->\n\n\n\
+         1 Usage\n-------\n\nSee [usage](#1-usage).\n"
+    );
+    assert!(result.warnings.is_empty());
 }
 
 #[test]
-fn test_nested_list_followed_by_paragraph_no_extra_blank_line() {
-    // Regression test: When a list item contains a nested list followed by a paragraph,
-    // there should be exactly one blank line between them, not two.
-    let input = " -  Foo bar.
-
-     -  Baz.
-     -  Qux.
-
-    Quux.
-
- -  Another item.
-";
-    let result = parse_and_serialize(input);
-    // There should be only one blank line between the nested list and "Quux."
-    // (i.e., "\n\n    Quux", not "\n\n\n    Quux")
-    assert!(
-        !result.contains("\n\n\n"),
-        "Should not have double blank lines between nested list and following paragraph.\nGot:\n{}",
-        result
+fn test_heading_attributes_excluded_from_sentence_case() {
+    let input = "## Some Title {.Foo   #my_id key=\"A B\"}\n\n## Plain Title {-}\n";
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Some title {#my_id .Foo key=\"A B\"}\n----------------------------------\n\n\
+         Plain title {-}\n---------------\n"
     );
-    assert_eq!(result, input, "Output should match input exactly");
 }
 
 #[test]
-fn test_heading_sentence_case_basic() {
-    let input = "# Hello World";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
-    let result = parse_and_serialize_with_options(input, &options);
-    assert_eq!(result, "Hello world\n===========\n");
+fn test_heading_attribute_lookalikes_left_alone() {
+    let input = "### Set {x}\n\n### {#alone}\n";
+    let result = parse_and_serialize(input);
+    assert_eq!(result, "### Set {x}\n\n### {#alone}\n");
 }
 
 #[test]
 fn test_heading_sentence_case_with_acronyms() {
     let input = "# Working With HTTP APIs";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "Working with HTTP APIs\n======================\n");
 }
@@ -3641,8 +4534,10 @@ fn test_heading_sentence_case_with_acronyms() {
 #[test]
 fn test_heading_sentence_case_with_proper_nouns() {
     let input = "# Introduction To JavaScript";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(
         result,
@@ -3653,9 +4548,11 @@ fn test_heading_sentence_case_with_proper_nouns() {
 #[test]
 fn test_heading_sentence_case_with_user_proper_nouns() {
     let input = "# Getting Started With MyAPI";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
-    options.heading_proper_nouns = vec!["MyAPI".to_string()];
+    let options = Options {
+        heading_sentence_case: true,
+        heading_proper_nouns: vec!["MyAPI".to_string()],
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(
         result,
@@ -3663,11 +4560,80 @@ fn test_heading_sentence_case_with_user_proper_nouns() {
     );
 }
 
+#[test]
+fn test_heading_sentence_case_with_disabled_categories() {
+    let input = "# Travel In France With Python";
+    let options = Options {
+        heading_sentence_case: true,
+        heading_disabled_categories: vec!["countries".to_string()],
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Travel in france with Python\n============================\n"
+    );
+}
+
+#[test]
+fn test_heading_sentence_case_disabled_category_with_user_proper_nouns() {
+    let input = "# Travel In France And Chad";
+    let options = Options {
+        heading_sentence_case: true,
+        heading_proper_nouns: vec!["Chad".to_string()],
+        heading_disabled_categories: vec!["countries".to_string()],
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Travel in france and Chad\n=========================\n"
+    );
+}
+
+#[test]
+fn test_heading_sentence_case_report() {
+    let input = "# Deploying Fedify To Kubernetes\n\n## Getting started\n";
+    let options = Options {
+        heading_sentence_case_report: true,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "Deploying Fedify To Kubernetes\n==============================\n\n\
+         Getting started\n---------------\n"
+    );
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 1);
+    assert_eq!(
+        result.warnings[0].message,
+        "sentence case would change heading to \"Deploying fedify to Kubernetes\"; \
+         lowercased words: Fedify, To"
+    );
+    assert_eq!(result.unknown_words, vec!["Fedify"]);
+}
+
+#[test]
+fn test_heading_sentence_case_report_with_proper_nouns() {
+    let input = "# Deploying Fedify\n";
+    let options = Options {
+        heading_sentence_case_report: true,
+        heading_proper_nouns: vec!["Fedify".to_string()],
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert!(result.warnings.is_empty());
+    assert!(result.unknown_words.is_empty());
+}
+
 #[test]
 fn test_heading_sentence_case_with_code_spans() {
     let input = "# Using `MyClass` In Your Code";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(
         result,
@@ -3686,8 +4652,10 @@ fn test_heading_sentence_case_disabled() {
 #[test]
 fn test_heading_sentence_case_atx_style() {
     let input = "### Working With APIs";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "### Working with APIs\n");
 }
@@ -3695,8 +4663,10 @@ fn test_heading_sentence_case_atx_style() {
 #[test]
 fn test_heading_sentence_case_with_quotes() {
     let input = "# Smart Suggestion: \"Did You Mean?\"";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(
         result,
@@ -3707,8 +4677,10 @@ fn test_heading_sentence_case_with_quotes() {
 #[test]
 fn test_heading_sentence_case_non_latin() {
     let input = "# \u{D55C}\u{AE00} \u{C81C}\u{BAA9} With English";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(
         result,
@@ -3722,8 +4694,10 @@ fn test_heading_sentence_case_starting_with_code_span() {
     // following the code span should NOT be capitalized (the code span itself
     // counts as the first word).
     let input = "# `Foo` object";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "`Foo` object\n============\n");
 }
@@ -3734,12 +4708,112 @@ fn test_heading_sentence_case_proper_noun_in_parentheses() {
     // Even though "Deno" is in the built-in proper nouns list, it was being
     // lowercased because find_proper_noun() didn't strip leading punctuation.
     let input = "# Test (Deno only)";
-    let mut options = Options::default();
-    options.heading_sentence_case = true;
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
     let result = parse_and_serialize_with_options(input, &options);
     assert_eq!(result, "Test (Deno only)\n================\n");
 }
 
+#[test]
+fn test_heading_sentence_case_preserves_link_destinations() {
+    let input = "# Read The [Setup Guide](./Docs/Setup.md)";
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Read the [setup guide](./Docs/Setup.md)\n=======================================\n"
+    );
+}
+
+#[test]
+fn test_heading_sentence_case_links_disabled() {
+    let input = "# Read The [Setup Guide](./Docs/Setup.md)";
+    let options = Options {
+        heading_sentence_case: true,
+        heading_sentence_case_links: false,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "Read the [Setup Guide](./Docs/Setup.md)\n=======================================\n"
+    );
+}
+
+#[test]
+fn test_heading_sentence_case_keeps_link_labels() {
+    let input = "# Changelog\n\n## [Unreleased]\n\n## [Version One][] Notes\n\n\
+                 ## [Full Text][Label] Notes\n\n[Unreleased]: https://example.com/compare\n\
+                 [Version One]: https://example.com/v1\n[Label]: https://example.com/label\n";
+    let options = Options {
+        heading_sentence_case: true,
+        ..Options::default()
+    };
+    let result = crate::format(input, &options).unwrap();
+    assert!(result.contains("\n[Unreleased]\n------------\n"));
+    assert!(result.contains("\n[Version One][] notes\n"));
+    assert!(result.contains("\n[full text][Label] notes\n"));
+    assert!(result.contains("\n[Unreleased]: https://example.com/compare\n"));
+    assert_eq!(crate::format(&result, &options).unwrap(), result);
+}
+
+#[test]
+fn test_sentence_case_table_headers() {
+    let input = "| Package Name | Supported Runtimes |\n| --- | --- |\n| Foo Bar | Deno |";
+    let options = Options {
+        heading_sentence_case_table_headers: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert!(
+        result.starts_with("| Package name | Supported runtimes |\n"),
+        "got: {}",
+        result
+    );
+    assert!(result.contains("| Foo Bar      | Deno"), "got: {}", result);
+}
+
+#[test]
+fn test_sentence_case_terms() {
+    let input = "Config File Format\n\n: The Format Of The File.";
+    let options = Options {
+        heading_sentence_case_terms: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert!(
+        result.starts_with("Config file format\n"),
+        "got: {}",
+        result
+    );
+    assert!(
+        result.contains("The Format Of The File."),
+        "got: {}",
+        result
+    );
+}
+
+#[test]
+fn test_sentence_case_table_headers_with_directives() {
+    let input = "<!-- hongdown-proper-nouns: Acme Cloud -->\n<!-- hongdown-common-nouns: Go -->\n\n\
+                 | Acme Cloud Region | Go Version |\n| --- | --- |\n| X | Y |";
+    let options = Options {
+        heading_sentence_case_table_headers: true,
+        ..Options::default()
+    };
+    let result = parse_and_serialize_with_options(input, &options);
+    assert!(
+        result.contains("| Acme Cloud region | Go version |"),
+        "got: {}",
+        result
+    );
+}
+
 // ============================================================================
 // Code block formatter tests
 // ============================================================================
@@ -3794,6 +4868,28 @@ fn test_code_block_formatter_transforms_content() {
     assert_eq!(result, "~~~~ upper\nHELLO WORLD\n~~~~\n");
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn test_code_block_attributes_language() {
+    use crate::CodeFormatter;
+
+    let mut options = Options::default();
+    options.code_formatters.insert(
+        "upper".to_string(),
+        CodeFormatter {
+            command: vec!["tr".to_string(), "a-z".to_string(), "A-Z".to_string()],
+            timeout_secs: 5,
+        },
+    );
+
+    let input = "```  {  .upper #code   .numberLines  }\nhello world\n```\n";
+    let result = parse_and_serialize_with_options(input, &options);
+    assert_eq!(
+        result,
+        "~~~~ {#code .upper .numberLines}\nHELLO WORLD\n~~~~\n"
+    );
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[test]
 fn test_code_block_formatter_failure_preserves_original() {
@@ -3882,8 +4978,10 @@ fn test_code_block_formatter_timeout() {
 fn test_code_block_formatter_with_default_language() {
     use crate::CodeFormatter;
 
-    let mut options = Options::default();
-    options.default_language = "text".to_string();
+    let mut options = Options {
+        default_language: "text".to_string(),
+        ..Options::default()
+    };
     options.code_formatters.insert(
         "text".to_string(),
         CodeFormatter {
@@ -4315,8 +5413,10 @@ fn test_possessive_apostrophe_after_digit_stays_straight() {
 fn test_possessive_apostrophe_after_digit_curly_when_enabled() {
     // Possessive apostrophe after a digit should become curly
     // when curly_apostrophes is enabled
-    let mut options = Options::default();
-    options.curly_apostrophes = true;
+    let options = Options {
+        curly_apostrophes: true,
+        ..Options::default()
+    };
     let input = "Version 1.2.3's highlight.";
     let result = parse_and_serialize_with_options(input, &options);
     let expected = format!("Version 1.2.3{}s highlight.\n", RIGHT_SINGLE_QUOTE);
@@ -4338,8 +5438,8 @@ fn test_serialize_windows_path_in_italic() {
 fn test_serialize_windows_path_idempotent() {
     let input = r"*C:\Users\Alice\Documents*";
     let first_pass = parse_and_serialize_with_source(input);
-    let second_pass = parse_and_serialize_with_source(&first_pass.trim_end());
-    let third_pass = parse_and_serialize_with_source(&second_pass.trim_end());
+    let second_pass = parse_and_serialize_with_source(first_pass.trim_end());
+    let third_pass = parse_and_serialize_with_source(second_pass.trim_end());
 
     assert_eq!(
         first_pass, second_pass,
@@ -4366,7 +5466,7 @@ fn test_serialize_multiple_backslashes() {
     assert_eq!(result, "*path\\\\to\\\\file*\n");
 
     // Test idempotency
-    let second_pass = parse_and_serialize_with_source(&result.trim_end());
+    let second_pass = parse_and_serialize_with_source(result.trim_end());
     assert_eq!(result, second_pass);
 }
 
@@ -4557,180 +5657,683 @@ fn test_footnote_with_mixed_blocks() {
         "Blockquote should be preserved.\nGot:\n{}",
         result
     );
-    assert!(
-        result.contains("print(\"code\")"),
-        "Code block should be preserved.\nGot:\n{}",
-        result
+    assert!(
+        result.contains("print(\"code\")"),
+        "Code block should be preserved.\nGot:\n{}",
+        result
+    );
+    assert!(
+        result.contains(" -  A list item"),
+        "List should be preserved.\nGot:\n{}",
+        result
+    );
+}
+
+// Regression tests for footnote wrapping with custom line_width
+// Bug: footnotes were hardcoded to wrap at 80 chars instead of respecting line_width option
+
+#[test]
+fn test_footnote_respects_custom_line_width_simple() {
+    let options = Options {
+        line_width: crate::LineWidth::new(60).unwrap(),
+        ..Options::default()
+    };
+    let input = r#"Text[^1].
+
+[^1]: This is a very long footnote definition that exceeds sixty characters and should be wrapped according to the custom line width setting.
+"#;
+    let result = parse_and_serialize_with_options(input, &options);
+
+    // All lines should respect the 60-char limit
+    for line in result.lines() {
+        assert!(
+            line.len() <= 60,
+            "Line exceeds 60 characters: '{}' (len={})",
+            line,
+            line.len()
+        );
+    }
+
+    // Footnote content should be preserved
+    assert!(
+        result.contains("This is a very long footnote"),
+        "Footnote content should be preserved, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_footnote_respects_custom_line_width_with_long_paragraph() {
+    let options = Options {
+        line_width: crate::LineWidth::new(60).unwrap(),
+        ..Options::default()
+    };
+    let input = r#"Text[^1].
+
+[^1]: First paragraph.
+
+      This is a very long second paragraph in the footnote that should be wrapped according to the custom line width of sixty characters for proper formatting.
+"#;
+    let result = parse_and_serialize_with_footnotes_and_options(input, &options);
+
+    // For footnotes with block elements, the continuation indent ("[^1]: " = 6 chars)
+    // plus the block indent (6 spaces) means content starts at column 12.
+    // So the actual content should wrap at 60 - 12 = 48 chars per line.
+    // We verify that no line of actual content (excluding indentation) exceeds reasonable bounds.
+
+    // Content should be preserved
+    assert!(
+        result.contains("First paragraph."),
+        "First paragraph should be preserved, got:\n{}",
+        result
+    );
+    assert!(
+        result.contains("This is a very long second paragraph"),
+        "Second paragraph should be preserved, got:\n{}",
+        result
+    );
+
+    // Check that text is wrapped (not all on one line)
+    let lines: Vec<&str> = result.lines().collect();
+    let long_lines: Vec<&str> = lines
+        .iter()
+        .filter(|line| line.contains("This is a very long"))
+        .copied()
+        .collect();
+
+    // The long paragraph should be split across multiple lines
+    assert!(
+        long_lines.len() < 2,
+        "Long paragraph should not be all on one line, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_footnote_respects_custom_line_width_with_list() {
+    let options = Options {
+        line_width: crate::LineWidth::new(60).unwrap(),
+        ..Options::default()
+    };
+    let input = r#"Text[^1].
+
+[^1]: A list in footnote:
+
+       -  This is a very long list item that exceeds sixty characters and should wrap properly with continuation indent.
+       -  Second item also very long to test wrapping behavior for list items inside footnotes.
+"#;
+    let result = parse_and_serialize_with_footnotes_and_options(input, &options);
+
+    // List content should be preserved
+    assert!(
+        result.contains("This is a very long list item"),
+        "List item content should be preserved, got:\n{}",
+        result
+    );
+    assert!(
+        result.contains("Second item also very long"),
+        "Second list item should be preserved, got:\n{}",
+        result
+    );
+
+    // Check that long list items are wrapped (split across multiple lines)
+    let lines: Vec<&str> = result.lines().collect();
+    let first_item_lines: Vec<&str> = lines
+        .iter()
+        .filter(|line| line.contains("very long list item"))
+        .copied()
+        .collect();
+
+    // Should not have the entire long text on a single line
+    assert!(
+        first_item_lines.is_empty() || !first_item_lines[0].contains("and should wrap"),
+        "Long list item should be wrapped, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_footnote_respects_custom_line_width_with_ordered_list() {
+    let options = Options {
+        line_width: crate::LineWidth::new(60).unwrap(),
+        ..Options::default()
+    };
+    let input = r#"Text[^1].
+
+[^1]: An ordered list in footnote:
+
+      1.  This is the first item with a very long description that exceeds sixty characters.
+      2.  This is the second item also with long text to test wrapping for ordered lists.
+"#;
+    let result = parse_and_serialize_with_footnotes_and_options(input, &options);
+
+    // Ordered list content should be preserved
+    assert!(
+        result.contains("This is the first item"),
+        "First ordered item should be preserved, got:\n{}",
+        result
+    );
+    assert!(
+        result.contains("This is the second item"),
+        "Second ordered item should be preserved, got:\n{}",
+        result
+    );
+
+    // Check that long ordered list items are wrapped
+    let lines: Vec<&str> = result.lines().collect();
+    let first_item_lines: Vec<&str> = lines
+        .iter()
+        .filter(|line| line.contains("first item with"))
+        .copied()
+        .collect();
+
+    // Should not have the entire long text on a single line
+    assert!(
+        first_item_lines.is_empty() || !first_item_lines[0].contains("exceeds sixty"),
+        "Long ordered list item should be wrapped, got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_toc_regenerates_region() {
+    let input = "Title\n=====\n\n<!-- hongdown-toc -->\n\n -  [Stale](#stale)\n\n<!-- /hongdown-toc -->\n\nInstallation\n------------\n\n### From source\n\nUsage\n-----\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert_eq!(
+        result,
+        "Title\n=====\n\n<!-- hongdown-toc -->\n\n -  [Installation](#installation)\n     -  [From source](#from-source)\n -  [Usage](#usage)\n\n<!-- /hongdown-toc -->\n\n\nInstallation\n------------\n\n### From source\n\nUsage\n-----\n"
+    );
+    assert_eq!(crate::format(&result, &Options::default()).unwrap(), result);
+}
+
+#[test]
+fn test_toc_inserts_end_marker() {
+    let input = "# Title\n\n<!-- hongdown-toc -->\n\n## Usage\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert!(
+        result.contains("<!-- hongdown-toc -->\n\n -  [Usage](#usage)\n\n<!-- /hongdown-toc -->\n"),
+        "got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_toc_end_marker_after_heading() {
+    let input = "# Title\n\n<!-- hongdown-toc -->\n\n## Usage\n\n -  [Stale](#usage)\n\n<!-- /hongdown-toc -->\n";
+    let result = crate::format_with_warnings(input, &Options::default()).unwrap();
+    assert_eq!(result.output.matches("<!-- /hongdown-toc -->").count(), 1);
+    assert!(
+        result.output.contains(" -  [Stale](#usage)"),
+        "got:\n{}",
+        result.output
+    );
+    assert!(!result.output.contains("[Usage](#usage)"));
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 3);
+    assert!(result.warnings[0].message.contains("end marker"));
+}
+
+#[test]
+fn test_toc_level_range() {
+    let input = "<!-- hongdown-toc: min-level=3, max-level=3 -->\n\n## A\n\n### B\n\n#### C\n\n## D\n\n### B\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert!(
+        result.starts_with(
+            "<!-- hongdown-toc: min-level=3, max-level=3 -->\n\n -  [B](#b)\n -  [B](#b-1)\n\n<!-- /hongdown-toc -->\n"
+        ),
+        "got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_toc_invalid_arguments() {
+    let input = "# Title\n\n<!-- hongdown-toc: depth=2 -->\n\n## Usage\n";
+    let result = crate::format_with_warnings(input, &Options::default()).unwrap();
+    assert!(!result.output.contains("<!-- /hongdown-toc -->"));
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 3);
+    assert_eq!(
+        result.warnings[0].message,
+        "invalid table of contents directive: unknown argument `depth`; leaving it as a comment"
+    );
+}
+
+#[test]
+fn test_toc_link_text_and_slugs() {
+    let input = "<!-- hongdown-toc -->\n\n## The `Options` *type* [docs](https://example.com)\n\n## What's [new]?\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert!(
+        result.contains(" -  [The `Options` type docs](#the-options-type-docs)\n"),
+        "got:\n{}",
+        result
+    );
+    assert!(
+        result.contains(" -  [What's \\[new\\]?](#whats-new)\n"),
+        "got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_toc_follows_list_options() {
+    let options = Options {
+        unordered_marker: crate::UnorderedMarker::Asterisk,
+        leading_spaces: crate::LeadingSpaces::new(0).unwrap(),
+        trailing_spaces: crate::TrailingSpaces::new(1).unwrap(),
+        indent_width: crate::IndentWidth::new(2).unwrap(),
+        ..Options::default()
+    };
+    let input = "<!-- hongdown-toc -->\n\n## A\n\n### B\n";
+    let result = crate::format(input, &options).unwrap();
+    assert!(
+        result.contains(
+            "<!-- hongdown-toc -->\n\n* [A](#a)\n  * [B](#b)\n\n<!-- /hongdown-toc -->\n"
+        ),
+        "got:\n{}",
+        result
+    );
+}
+
+#[test]
+fn test_toc_left_alone_when_disabled() {
+    let input = "<!-- hongdown-disable -->\n\n<!-- hongdown-toc -->\n\n- [Stale](#stale)\n\n<!-- /hongdown-toc -->\n\n<!-- hongdown-enable -->\n\n## A\n";
+    let result = crate::format(input, &Options::default()).unwrap();
+    assert!(result.contains("- [Stale](#stale)"), "got:\n{}", result);
+    assert!(!result.contains("[A](#a)"), "got:\n{}", result);
+}
+
+fn anchor_warnings(input: &str) -> Vec<(usize, String)> {
+    crate::format_with_warnings(input, &Options::default())
+        .unwrap()
+        .warnings
+        .into_iter()
+        .filter(|w| w.message.starts_with("undefined anchor link"))
+        .map(|w| (w.line, w.message))
+        .collect()
+}
+
+#[test]
+fn test_anchor_links_to_headings() {
+    let input = "Usage\n-----\n\nSee [usage](#usage) and [options](#configuration-options).\n\n### Configuration options\n";
+    assert!(anchor_warnings(input).is_empty());
+}
+
+#[test]
+fn test_anchor_links_duplicate_headings() {
+    let input = "## Usage\n\n## Usage\n\nSee [second](#usage-1) and [third](#usage-2).\n";
+    assert_eq!(
+        anchor_warnings(input),
+        vec![(5, "undefined anchor link: #usage-2".to_string())]
+    );
+}
+
+#[test]
+fn test_anchor_links_html_ids() {
+    let input = "<a id=\"custom\"></a>\n\nJump to [custom](#custom) or [name](#legacy).\n\n<a name='legacy'></a>\n";
+    assert!(anchor_warnings(input).is_empty());
+}
+
+#[test]
+fn test_anchor_links_reference_definitions() {
+    let input = "## Usage\n\nSee [usage] and [other].\n\n[usage]: #usage\n[other]: #other\n";
+    assert_eq!(
+        anchor_warnings(input),
+        vec![(6, "undefined anchor link: #other".to_string())]
+    );
+}
+
+#[test]
+fn test_anchor_links_suggest_slug() {
+    let input = "## Usage\n\nSee [usage](#Usage).\n";
+    assert_eq!(
+        anchor_warnings(input),
+        vec![(
+            3,
+            "undefined anchor link: #Usage (did you mean #usage?)".to_string()
+        )]
     );
-    assert!(
-        result.contains(" -  A list item"),
-        "List should be preserved.\nGot:\n{}",
-        result
+}
+
+#[test]
+fn test_anchor_links_ignore_code_and_disabled_regions() {
+    let input = "~~~~\n[x]: #in-code\n~~~~\n\n<!-- hongdown-disable-next-line -->\nSee [x](#disabled).\n\nSee [y](#percent%2Dencoded).\n\n## Percent-encoded\n";
+    assert!(anchor_warnings(input).is_empty());
+}
+
+#[test]
+fn test_anchor_links_to_heading_ids() {
+    let input = "## Installation {#install}\n\n## Install\n\n\
+                 See [install](#install), [again](#install-1), and [not](#installation).\n";
+    assert_eq!(
+        anchor_warnings(input),
+        vec![(5, "undefined anchor link: #installation".to_string())]
     );
 }
 
-// Regression tests for footnote wrapping with custom line_width
-// Bug: footnotes were hardcoded to wrap at 80 chars instead of respecting line_width option
+#[test]
+fn test_unused_references_removed_by_default() {
+    let input =
+        "See [used].\n\n[used]: https://example.com/used\n[unused]: https://example.com/unused\n";
+    let result = crate::format_with_warnings(input, &Options::default()).unwrap();
+    assert_eq!(
+        result.output,
+        "See [used].\n\n[used]: https://example.com/used\n"
+    );
+    assert!(result.warnings.is_empty());
+}
 
 #[test]
-fn test_footnote_respects_custom_line_width_simple() {
+fn test_unused_references_warn_keeps_in_section() {
     let options = Options {
-        line_width: crate::LineWidth::new(60).unwrap(),
+        link_unused_references: crate::UnusedReferences::Warn,
         ..Options::default()
     };
-    let input = r#"Text[^1].
+    let input = "See [used].\n\n[used]: https://example.com/used\n[unused]: https://example.com/unused \"Title\"\n\nNext\n----\n\nText.\n\n[later]: <https://example.com/later>\n";
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "See [used].\n\n[used]: https://example.com/used\n[unused]: https://example.com/unused \"Title\"\n\n\nNext\n----\n\nText.\n\n[later]: https://example.com/later\n"
+    );
+    let warnings: Vec<_> = result
+        .warnings
+        .iter()
+        .map(|w| (w.line, w.message.as_str()))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (4, "unused reference definition: [unused]"),
+            (11, "unused reference definition: [later]"),
+        ]
+    );
+}
 
-[^1]: This is a very long footnote definition that exceeds sixty characters and should be wrapped according to the custom line width setting.
-"#;
-    let result = parse_and_serialize_with_options(input, &options);
+#[test]
+fn test_conflicting_reference_labels() {
+    let input = "See [a].\n\n[a]: https://example.com/one\n[A]: https://example.com/two\n";
+    let result = crate::format_with_warnings(input, &Options::default()).unwrap();
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 4);
+    assert_eq!(
+        result.warnings[0].message,
+        "conflicting reference definition: [A] is already defined on line 3 with a different URL"
+    );
 
-    // All lines should respect the 60-char limit
-    for line in result.lines() {
-        assert!(
-            line.len() <= 60,
-            "Line exceeds 60 characters: '{}' (len={})",
-            line,
-            line.len()
-        );
-    }
+    let options = Options {
+        link_conflicting_labels: crate::ConflictingLabels::Ignore,
+        ..Options::default()
+    };
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert!(result.warnings.is_empty());
+}
 
-    // Footnote content should be preserved
-    assert!(
-        result.contains("This is a very long footnote"),
-        "Footnote content should be preserved, got:\n{}",
-        result
+#[test]
+fn test_duplicate_reference_urls_warn() {
+    let options = Options {
+        link_duplicate_urls: crate::DuplicateUrls::Warn,
+        ..Options::default()
+    };
+    let input = "See [a] and [b].\n\n[a]: https://example.com/\n[b]: https://example.com/\n";
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(result.output, input);
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 4);
+    assert_eq!(
+        result.warnings[0].message,
+        "duplicate reference URL: [b] points to the same URL as [a] on line 3"
     );
 }
 
 #[test]
-fn test_footnote_respects_custom_line_width_with_long_paragraph() {
+fn test_duplicate_reference_urls_merge() {
     let options = Options {
-        line_width: crate::LineWidth::new(60).unwrap(),
+        link_duplicate_urls: crate::DuplicateUrls::Merge,
         ..Options::default()
     };
-    let input = r#"Text[^1].
+    let input = "See [a], [b], [text][b], and [![badge][img]][b].\n\n[a]: https://example.com/\n[b]: https://example.com/\n[img]: https://example.com/badge.svg\n";
+    let result = crate::format(input, &options).unwrap();
+    assert_eq!(
+        result,
+        "See [a], [b][a], [text][a], and [![badge][img]][a].\n\n[a]: https://example.com/\n[img]: https://example.com/badge.svg\n"
+    );
+    assert_eq!(crate::format(&result, &options).unwrap(), result);
+}
 
-[^1]: First paragraph.
+#[test]
+fn test_duplicate_reference_urls_warn_on_external_link() {
+    let options = Options {
+        link_duplicate_urls: crate::DuplicateUrls::Warn,
+        ..Options::default()
+    };
+    let input = "See [Hyperscan][hs].  Also [Hyperscan on GitHub](https://github.com/intel/hyperscan).\n\n[hs]: https://github.com/intel/hyperscan\n";
+    let result = crate::format_with_warnings(input, &options).unwrap();
+    assert_eq!(
+        result.output,
+        "See [Hyperscan][hs].  Also [Hyperscan on GitHub].\n\n[hs]: https://github.com/intel/hyperscan\n[Hyperscan on GitHub]: https://github.com/intel/hyperscan\n"
+    );
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 1);
+    assert_eq!(
+        result.warnings[0].message,
+        "duplicate reference URL: [Hyperscan on GitHub] points to the same URL as [hs] on line 3"
+    );
+}
 
-      This is a very long second paragraph in the footnote that should be wrapped according to the custom line width of sixty characters for proper formatting.
-"#;
-    let result = parse_and_serialize_with_footnotes_and_options(input, &options);
+#[test]
+fn test_duplicate_reference_urls_merge_external_links() {
+    let options = Options {
+        link_duplicate_urls: crate::DuplicateUrls::Merge,
+        ..Options::default()
+    };
+    let input = "See [Hyperscan][hs].  Also [Hyperscan on GitHub](https://github.com/intel/hyperscan),\n[one](https://example.com/), and [two](https://example.com/).\n\n[hs]: https://github.com/intel/hyperscan\n";
+    let result = crate::format(input, &options).unwrap();
+    assert_eq!(
+        result,
+        "See [Hyperscan][hs].  Also [Hyperscan on GitHub][hs],\n[one], and [two][one].\n\n[hs]: https://github.com/intel/hyperscan\n[one]: https://example.com/\n"
+    );
+    assert_eq!(crate::format(&result, &options).unwrap(), result);
+}
 
-    // For footnotes with block elements, the continuation indent ("[^1]: " = 6 chars)
-    // plus the block indent (6 spaces) means content starts at column 12.
-    // So the actual content should wrap at 60 - 12 = 48 chars per line.
-    // We verify that no line of actual content (excluding indentation) exceeds reasonable bounds.
+fn format_with_placement(input: &str, placement: crate::ReferencePlacement) -> String {
+    let options = Options {
+        link_reference_placement: placement,
+        ..Options::default()
+    };
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+    output
+}
 
-    // Content should be preserved
-    assert!(
-        result.contains("First paragraph."),
-        "First paragraph should be preserved, got:\n{}",
-        result
+const PLACEMENT_INPUT: &str = "Intro [A](https://a.example/).\n\nSection\n-------\n\nText [B](https://b.example/).[^1]\n\n### Sub\n\nText [C](https://c.example/).\n\n[^1]: Note.\n";
+
+#[test]
+fn test_reference_placement_section_h2() {
+    let output = format_with_placement(PLACEMENT_INPUT, crate::ReferencePlacement::SectionH2);
+    assert_eq!(
+        output,
+        "Intro [A].\n\n[A]: https://a.example/\n\n\nSection\n-------\n\nText [B].[^1]\n\n### Sub\n\nText [C].\n\n[^1]: Note.\n\n[B]: https://b.example/\n[C]: https://c.example/\n"
     );
-    assert!(
-        result.contains("This is a very long second paragraph"),
-        "Second paragraph should be preserved, got:\n{}",
-        result
+}
+
+#[test]
+fn test_reference_placement_document() {
+    let output = format_with_placement(PLACEMENT_INPUT, crate::ReferencePlacement::Document);
+    assert_eq!(
+        output,
+        "Intro [A].\n\n\nSection\n-------\n\nText [B].[^1]\n\n### Sub\n\nText [C].\n\n[^1]: Note.\n\n[A]: https://a.example/\n[B]: https://b.example/\n[C]: https://c.example/\n"
     );
+}
 
-    // Check that text is wrapped (not all on one line)
-    let lines: Vec<&str> = result.lines().collect();
-    let long_lines: Vec<&str> = lines
-        .iter()
-        .filter(|line| line.contains("This is a very long"))
-        .copied()
-        .collect();
+#[test]
+fn test_reference_placement_block() {
+    let output = format_with_placement(PLACEMENT_INPUT, crate::ReferencePlacement::Block);
+    assert_eq!(
+        output,
+        "Intro [A].\n\n[A]: https://a.example/\n\n\nSection\n-------\n\nText [B].[^1]\n\n[^1]: Note.\n\n[B]: https://b.example/\n\n### Sub\n\nText [C].\n\n[C]: https://c.example/\n"
+    );
+}
 
-    // The long paragraph should be split across multiple lines
-    assert!(
-        long_lines.len() < 2,
-        "Long paragraph should not be all on one line, got:\n{}",
-        result
+#[test]
+fn test_reference_placement_document_ignores_disable_directive() {
+    let input = "Text [A](https://a.example/).\n\n<!-- hongdown-disable -->\n\nKeep   *as*   is.\n\n<!-- hongdown-enable -->\n\nMore [B](https://b.example/).\n";
+    let output = format_with_placement(input, crate::ReferencePlacement::Document);
+    assert_eq!(
+        output,
+        "Text [A].\n\n<!-- hongdown-disable -->\n\nKeep   *as*   is.\n\n<!-- hongdown-enable -->\n\nMore [B].\n\n[A]: https://a.example/\n[B]: https://b.example/\n"
     );
 }
 
 #[test]
-fn test_footnote_respects_custom_line_width_with_list() {
+fn test_external_links_kept_inline() {
     let options = Options {
-        line_width: crate::LineWidth::new(60).unwrap(),
+        link_external_links: crate::ExternalLinks::Inline,
         ..Options::default()
     };
-    let input = r#"Text[^1].
-
-[^1]: A list in footnote:
-
-       -  This is a very long list item that exceeds sixty characters and should wrap properly with continuation indent.
-       -  Second item also very long to test wrapping behavior for list items inside footnotes.
-"#;
-    let result = parse_and_serialize_with_footnotes_and_options(input, &options);
+    let input = "See [Rust](https://www.rust-lang.org/).\n";
+    assert_eq!(crate::format(input, &options).unwrap(), input);
+}
 
-    // List content should be preserved
-    assert!(
-        result.contains("This is a very long list item"),
-        "List item content should be preserved, got:\n{}",
-        result
-    );
-    assert!(
-        result.contains("Second item also very long"),
-        "Second list item should be preserved, got:\n{}",
-        result
+#[test]
+fn test_external_links_min_url_width() {
+    let options = Options {
+        link_min_url_width: 20,
+        ..Options::default()
+    };
+    let input = "See [short](https://a.io) and [long](https://example.com/long/path).\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "See [short](https://a.io) and [long].\n\n[long]: https://example.com/long/path\n"
     );
+}
 
-    // Check that long list items are wrapped (split across multiple lines)
-    let lines: Vec<&str> = result.lines().collect();
-    let first_item_lines: Vec<&str> = lines
-        .iter()
-        .filter(|line| line.contains("very long list item"))
-        .copied()
-        .collect();
-
-    // Should not have the entire long text on a single line
-    assert!(
-        first_item_lines.is_empty() || !first_item_lines[0].contains("and should wrap"),
-        "Long list item should be wrapped, got:\n{}",
-        result
+#[test]
+fn test_external_schemes_and_domains() {
+    let options = Options {
+        link_external_schemes: vec!["mailto".to_string(), "ipfs:".to_string()],
+        link_external_domains: vec!["example.com".to_string()],
+        ..Options::default()
+    };
+    let input = "Mail [me](mailto:me@example.org), see [IPFS](ipfs://bafy/x), [CDN](//cdn.example.com/a.js), [FTP](ftp://ftp.example.com/), and [other](ftp://other.org/).\n";
+    assert_eq!(
+        crate::format(input, &options).unwrap(),
+        "Mail [me], see [IPFS], [CDN], [FTP], and [other](ftp://other.org/).\n\n[me]: mailto:me@example.org\n[IPFS]: ipfs://bafy/x\n[CDN]: //cdn.example.com/a.js\n[FTP]: ftp://ftp.example.com/\n"
     );
 }
 
 #[test]
-fn test_footnote_respects_custom_line_width_with_ordered_list() {
+fn test_reference_label_numeric() {
     let options = Options {
-        line_width: crate::LineWidth::new(60).unwrap(),
+        link_reference_label: crate::ReferenceLabel::Numeric,
         ..Options::default()
     };
-    let input = r#"Text[^1].
+    let input = "Read [A](https://a.example/), [B](https://b.example/), and [A again](https://a.example/) or [old][1].\n\n[1]: https://old.example/\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "Read [A][2], [B][3], and [A again][2] or [old][1].\n\n[1]: https://old.example/\n[2]: https://a.example/\n[3]: https://b.example/\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+}
 
-[^1]: An ordered list in footnote:
+#[test]
+fn test_reference_label_domain() {
+    let options = Options {
+        link_reference_label: crate::ReferenceLabel::Domain,
+        ..Options::default()
+    };
+    let input = "See [the book](https://www.rust-lang.org/learn), [std](https://www.rust-lang.org/std), and [example.com](https://example.com/).\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "See [the book][rust-lang.org], [std][rust-lang.org-2], and [example.com].\n\n[rust-lang.org]: https://www.rust-lang.org/learn\n[rust-lang.org-2]: https://www.rust-lang.org/std\n[example.com]: https://example.com/\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+}
 
-      1.  This is the first item with a very long description that exceeds sixty characters.
-      2.  This is the second item also with long text to test wrapping for ordered lists.
-"#;
-    let result = parse_and_serialize_with_footnotes_and_options(input, &options);
+#[test]
+fn test_reference_label_text_with_different_urls() {
+    let input = "See [one](https://a.example/), [one](https://b.example/), and [one](https://a.example/).\n\n\
+                 Also [two][] and [two](https://c.example/).\n\n[two]: https://d.example/\n";
+    let output = crate::format(input, &Options::default()).unwrap();
+    assert_eq!(
+        output,
+        "See [one], [one][one-2], and [one].\n\nAlso [two][] and [two][two-2].\n\n\
+         [one]: https://a.example/\n[one-2]: https://b.example/\n[two]: https://d.example/\n\
+         [two-2]: https://c.example/\n"
+    );
+    assert_eq!(crate::format(&output, &Options::default()).unwrap(), output);
+}
 
-    // Ordered list content should be preserved
-    assert!(
-        result.contains("This is the first item"),
-        "First ordered item should be preserved, got:\n{}",
-        result
+#[test]
+fn test_reference_order() {
+    let input = "[b], [2], [A], [1].\n\n[b]: https://b.example/\n[2]: https://two.example/\n[A]: https://a.example/\n[1]: https://one.example/\n";
+    let format_with_order = |order| {
+        let options = Options {
+            link_reference_order: order,
+            ..Options::default()
+        };
+        crate::format(input, &options).unwrap()
+    };
+    assert_eq!(
+        format_with_order(crate::ReferenceOrder::Auto),
+        "[b], [2], [A], [1].\n\n[b]: https://b.example/\n[A]: https://a.example/\n[1]: https://one.example/\n[2]: https://two.example/\n"
     );
-    assert!(
-        result.contains("This is the second item"),
-        "Second ordered item should be preserved, got:\n{}",
-        result
+    assert_eq!(
+        format_with_order(crate::ReferenceOrder::Appearance),
+        "[b], [2], [A], [1].\n\n[b]: https://b.example/\n[2]: https://two.example/\n[A]: https://a.example/\n[1]: https://one.example/\n"
+    );
+    assert_eq!(
+        format_with_order(crate::ReferenceOrder::Alphabetical),
+        "[b], [2], [A], [1].\n\n[1]: https://one.example/\n[2]: https://two.example/\n[A]: https://a.example/\n[b]: https://b.example/\n"
+    );
+    assert_eq!(
+        format_with_order(crate::ReferenceOrder::NumericThenAlpha),
+        "[b], [2], [A], [1].\n\n[1]: https://one.example/\n[2]: https://two.example/\n[A]: https://a.example/\n[b]: https://b.example/\n"
+    );
+}
+
+#[test]
+fn test_renumber_references() {
+    let options = Options {
+        link_renumber_references: true,
+        ..Options::default()
+    };
+    let input = "See [B][2], [3], [C][3], and [A][1][].\n\n[1]: https://a.example/\n[2]: https://b.example/\n[3]: https://c.example/\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "See [B][1], [2], [C][2], and [A][3][].\n\n[1]: https://b.example/\n[2]: https://c.example/\n[3]: https://a.example/\n"
     );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+}
 
-    // Check that long ordered list items are wrapped
-    let lines: Vec<&str> = result.lines().collect();
-    let first_item_lines: Vec<&str> = lines
-        .iter()
-        .filter(|line| line.contains("first item with"))
-        .copied()
-        .collect();
+#[test]
+fn test_renumber_references_with_numeric_labels() {
+    let options = Options {
+        link_renumber_references: true,
+        link_reference_label: crate::ReferenceLabel::Numeric,
+        ..Options::default()
+    };
+    let input = "New [X](https://x.example/), old [A][5], and [again](https://a.example/).\n\n[5]: https://a.example/\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "New [X][1], old [A][2], and [again][2].\n\n[1]: https://x.example/\n[2]: https://a.example/\n"
+    );
+    assert_eq!(crate::format(&output, &options).unwrap(), output);
+}
 
-    // Should not have the entire long text on a single line
-    assert!(
-        first_item_lines.is_empty() || !first_item_lines[0].contains("exceeds sixty"),
-        "Long ordered list item should be wrapped, got:\n{}",
-        result
+#[test]
+fn test_renumber_references_keeps_disabled_numbers() {
+    let options = Options {
+        link_renumber_references: true,
+        ..Options::default()
+    };
+    let input = "See [B][3] and [A][2].\n\n[2]: https://a.example/\n[3]: https://b.example/\n\n<!-- hongdown-disable -->\n\nOld [Z][1].\n\n<!-- hongdown-enable -->\n\n[1]: https://z.example/\n";
+    let output = crate::format(input, &options).unwrap();
+    assert_eq!(
+        output,
+        "See [B][2] and [A][3].\n\n[2]: https://b.example/\n[3]: https://a.example/\n\n<!-- hongdown-disable -->\n\nOld [Z][1].\n\n<!-- hongdown-enable -->\n"
     );
 }
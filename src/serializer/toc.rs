@@ -0,0 +1,220 @@
+//! Table of contents generation.
+
+use comrak::nodes::{AstNode, NodeValue};
+
+use super::Serializer;
+use super::attribute;
+use super::escape;
+use super::inline::{case_placeholder, unmask_case};
+use super::punctuation;
+use super::slug::Slugger;
+
+/// The marker that closes a generated table of contents.
+pub const TOC_END_MARKER: &str = "<!-- /hongdown-toc -->";
+
+impl<'a> Serializer<'a> {
+    /// Build the table of contents for the document as a nested list of
+    /// links to its headings.
+    ///
+    /// Only top-level headings between `min_level` and `max_level` are
+    /// listed, but every heading is counted so that the slugs of duplicate
+    /// headings get the same suffixes as on GitHub.  The list follows the
+    /// configured unordered list marker and indentation.
+    pub(super) fn generate_toc<'b>(
+        &self,
+        document: &'b AstNode<'b>,
+        min_level: u8,
+        max_level: u8,
+    ) -> String {
+        let mut slugger = Slugger::new();
+        let mut output = String::new();
+        // Levels of the headings enclosing the current entry
+        let mut levels: Vec<u8> = Vec::new();
+
+        for node in document.descendants() {
+            let level = match &node.data.borrow().value {
+                NodeValue::Heading(heading) => heading.level,
+                _ => continue,
+            };
+            let text = self.toc_heading_text(node);
+            let slug = match attribute::heading_id(node) {
+                Some(id) => slugger.explicit(&id),
+                None => slugger.slug(&toc_plain_text(&text)),
+            };
+
+            let is_top_level = node
+                .parent()
+                .is_some_and(|parent| matches!(parent.data.borrow().value, NodeValue::Document));
+            if !is_top_level || level < min_level || level > max_level {
+                continue;
+            }
+
+            // Skipped levels (e.g., h2 followed by h4) nest only one step
+            while levels.last().is_some_and(|&l| l >= level) {
+                levels.pop();
+            }
+            let depth = levels.len();
+            levels.push(level);
+
+            output.push_str(&" ".repeat(self.options.indent_width.get() * depth));
+            output.push_str(&" ".repeat(self.options.leading_spaces.get()));
+            output.push(self.options.unordered_marker.as_char());
+            output.push_str(&" ".repeat(self.options.trailing_spaces.get()));
+            output.push('[');
+            output.push_str(&text);
+            output.push_str("](#");
+            output.push_str(&slug);
+            output.push_str(")\n");
+        }
+
+        output
+    }
+
+    /// Compute the anchor of every heading in the document, paired with the
+    /// anchor it would have without the configured heading case applied.
+    pub(super) fn heading_anchors<'b>(&self, document: &'b AstNode<'b>) -> Vec<(String, String)> {
+        let mut slugger = Slugger::new();
+        let mut original_slugger = Slugger::new();
+        let mut anchors = Vec::new();
+        for node in document.descendants() {
+            if !matches!(node.data.borrow().value, NodeValue::Heading(_)) {
+                continue;
+            }
+            if let Some(id) = attribute::heading_id(node) {
+                original_slugger.explicit(&id);
+                anchors.push((slugger.explicit(&id), id));
+                continue;
+            }
+            let mut original = String::new();
+            let mut masks = Vec::new();
+            self.collect_toc_text(node, &mut original, &mut masks);
+            let text = unmask_case(&self.format_heading_text(node, original.trim()), &masks);
+            let original = unmask_case(&original, &masks);
+            anchors.push((
+                slugger.slug(&toc_plain_text(&text)),
+                original_slugger.slug(&toc_plain_text(&original)),
+            ));
+        }
+        anchors
+    }
+
+    /// Render the text of a heading for use as link text: formatting and
+    /// links are dropped, code spans are kept, and the configured heading
+    /// case and section number are applied.
+    fn toc_heading_text<'b>(&self, node: &'b AstNode<'b>) -> String {
+        let mut text = String::new();
+        let mut masks = Vec::new();
+        self.collect_toc_text(node, &mut text, &mut masks);
+        unmask_case(&self.format_heading_text(node, text.trim()), &masks)
+    }
+
+    /// Collect the text of a heading for use as link text.  Unless the text
+    /// of links is converted along with the heading, it is masked as in
+    /// [`Serializer::collect_with_case_conversion`].
+    fn collect_toc_text<'b>(
+        &self,
+        node: &'b AstNode<'b>,
+        text: &mut String,
+        masks: &mut Vec<String>,
+    ) {
+        match &node.data.borrow().value {
+            NodeValue::Text(t) => {
+                let t = attribute::split_heading_attributes(node, t).map_or(&t[..], |(t, _)| t);
+                let transformed = punctuation::transform_punctuation(t, self.options);
+                text.push_str(&escape_link_text(&transformed));
+            }
+            NodeValue::Code(code) => {
+                text.push_str(&escape::format_code_span(&code.literal));
+            }
+            NodeValue::SoftBreak | NodeValue::LineBreak => {
+                text.push(' ');
+            }
+            NodeValue::HtmlInline(_) | NodeValue::FootnoteReference(_) => {}
+            NodeValue::Link(_) if !self.options.heading_sentence_case_links => {
+                let mut link_text = String::new();
+                for child in node.children() {
+                    self.collect_toc_text(child, &mut link_text, masks);
+                }
+                text.push_str(&case_placeholder(masks.len()));
+                masks.push(link_text);
+            }
+            _ => {
+                for child in node.children() {
+                    self.collect_toc_text(child, text, masks);
+                }
+            }
+        }
+    }
+}
+
+/// Escape characters that would otherwise be interpreted as Markdown
+/// inside link text.
+fn escape_link_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '[' | ']' | '*' | '_' | '`' | '<') {
+            result.push('\\');
+        }
+        result.push(ch);
+    }
+    result
+}
+
+/// Recover the plain text of link text produced by [`escape_link_text`]
+/// and [`escape::format_code_span`], as a browser would render it.
+fn toc_plain_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '\\' && rest.len() > 1 {
+            let escaped = rest[1..].chars().next().unwrap();
+            result.push(escaped);
+            rest = &rest[1 + escaped.len_utf8()..];
+        } else if ch == '`' {
+            let fence_len = rest.len() - rest.trim_start_matches('`').len();
+            let fence = &rest[..fence_len];
+            let after = &rest[fence_len..];
+            match after.find(fence) {
+                Some(end) => {
+                    let code = &after[..end];
+                    let code = if code.len() >= 2
+                        && code.starts_with(' ')
+                        && code.ends_with(' ')
+                        && !code.trim().is_empty()
+                    {
+                        &code[1..code.len() - 1]
+                    } else {
+                        code
+                    };
+                    result.push_str(code);
+                    rest = &after[end + fence_len..];
+                }
+                None => {
+                    result.push_str(fence);
+                    rest = after;
+                }
+            }
+        } else {
+            result.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_link_text, toc_plain_text};
+
+    #[test]
+    fn test_escape_link_text() {
+        assert_eq!(escape_link_text("a [b] *c*"), "a \\[b\\] \\*c\\*");
+    }
+
+    #[test]
+    fn test_toc_plain_text() {
+        assert_eq!(toc_plain_text("a \\[b\\] \\*c\\*"), "a [b] *c*");
+        assert_eq!(toc_plain_text("The `Options` type"), "The Options type");
+        assert_eq!(toc_plain_text("`` `tick` ``"), "`tick`");
+    }
+}
@@ -2,6 +2,8 @@
 
 use unicode_width::UnicodeWidthStr;
 
+use super::escape::math_span_end;
+
 /// Wrap text at the specified line width.
 ///
 /// This function handles soft break markers (`\x00`) which represent where
@@ -10,14 +12,19 @@ use unicode_width::UnicodeWidthStr;
 ///
 /// Hard line breaks (`\n`) are preserved with two trailing spaces before the
 /// newline, and the prefix is added to the continuation line.
-pub fn wrap_text(text: &str, prefix: &str, line_width: usize) -> String {
+pub fn wrap_text(
+    text: &str,
+    prefix: &str,
+    line_width: usize,
+    templates: &[(String, String)],
+) -> String {
     // First, split by hard line breaks (actual newlines)
     // These must be preserved with two trailing spaces
     let hard_break_segments: Vec<&str> = text.split('\n').collect();
 
     if hard_break_segments.len() == 1 {
         // No hard line breaks, process normally with soft breaks
-        return wrap_text_segment(text, prefix, line_width);
+        return wrap_text_segment(text, prefix, line_width, templates);
     }
 
     // Process each segment separated by hard line breaks
@@ -29,7 +36,7 @@ pub fn wrap_text(text: &str, prefix: &str, line_width: usize) -> String {
         }
         // First segment uses the normal prefix, subsequent segments also need prefix
         // (wrap_text_segment handles adding the prefix to the first line)
-        let wrapped = wrap_text_segment(segment, prefix, line_width);
+        let wrapped = wrap_text_segment(segment, prefix, line_width, templates);
         result.push_str(&wrapped);
     }
 
@@ -37,14 +44,19 @@ pub fn wrap_text(text: &str, prefix: &str, line_width: usize) -> String {
 }
 
 /// Wrap a single segment of text (between hard line breaks).
-fn wrap_text_segment(text: &str, prefix: &str, line_width: usize) -> String {
+fn wrap_text_segment(
+    text: &str,
+    prefix: &str,
+    line_width: usize,
+    templates: &[(String, String)],
+) -> String {
     // Split by soft break markers (original line breaks)
     // \x00 represents where the original document had line breaks
     let original_lines: Vec<&str> = text.split('\x00').collect();
 
     if original_lines.len() == 1 {
         // No original line breaks, just wrap normally
-        return wrap_single_segment(text, prefix, prefix, line_width);
+        return wrap_single_segment(text, prefix, prefix, line_width, templates);
     }
 
     // Process lines: keep short lines as-is until we hit a long line,
@@ -76,7 +88,7 @@ fn wrap_text_segment(text: &str, prefix: &str, line_width: usize) -> String {
             }
 
             // Wrap the merged content
-            let wrapped = wrap_single_segment(&merged, prefix, prefix, line_width);
+            let wrapped = wrap_single_segment(&merged, prefix, prefix, line_width, templates);
 
             if !result.is_empty() {
                 result.push('\n');
@@ -100,6 +112,7 @@ pub fn wrap_text_first_line(
     first_prefix: &str,
     continuation_prefix: &str,
     line_width: usize,
+    templates: &[(String, String)],
 ) -> String {
     // First, split by hard line breaks (actual newlines)
     // These must be preserved with two trailing spaces
@@ -107,7 +120,13 @@ pub fn wrap_text_first_line(
 
     if hard_break_segments.len() == 1 {
         // No hard line breaks, process normally with soft breaks
-        return wrap_text_first_line_segment(text, first_prefix, continuation_prefix, line_width);
+        return wrap_text_first_line_segment(
+            text,
+            first_prefix,
+            continuation_prefix,
+            line_width,
+            templates,
+        );
     }
 
     // Process each segment separated by hard line breaks
@@ -124,8 +143,13 @@ pub fn wrap_text_first_line(
         } else {
             ("", continuation_prefix)
         };
-        let wrapped =
-            wrap_text_first_line_segment(segment, current_first, current_cont, line_width);
+        let wrapped = wrap_text_first_line_segment(
+            segment,
+            current_first,
+            current_cont,
+            line_width,
+            templates,
+        );
         result.push_str(&wrapped);
         is_first_segment = false;
     }
@@ -139,13 +163,20 @@ fn wrap_text_first_line_segment(
     first_prefix: &str,
     continuation_prefix: &str,
     line_width: usize,
+    templates: &[(String, String)],
 ) -> String {
     // Split by soft break markers (original line breaks)
     let original_lines: Vec<&str> = text.split('\x00').collect();
 
     if original_lines.len() == 1 {
         // No original line breaks, just wrap normally
-        return wrap_single_segment(text, first_prefix, continuation_prefix, line_width);
+        return wrap_single_segment(
+            text,
+            first_prefix,
+            continuation_prefix,
+            line_width,
+            templates,
+        );
     }
 
     // Process lines: keep short lines as-is until we hit a long line,
@@ -184,8 +215,13 @@ fn wrap_text_first_line_segment(
             }
 
             // Wrap the merged content
-            let wrapped =
-                wrap_single_segment(&merged, current_prefix, continuation_prefix, line_width);
+            let wrapped = wrap_single_segment(
+                &merged,
+                current_prefix,
+                continuation_prefix,
+                line_width,
+                templates,
+            );
 
             if !result.is_empty() {
                 result.push('\n');
@@ -200,13 +236,16 @@ fn wrap_text_first_line_segment(
 
 /// Wrap a single segment of text (no original line break markers).
 ///
-/// Handles special tokens like backtick-delimited code spans and bracketed
-/// content (links) as unbreakable units.
+/// Handles special tokens like backtick-delimited code spans, `$`-delimited
+/// math spans, brace-delimited content (e.g., MDX expressions), bracketed
+/// content (links), and template tags delimited by any of the `templates`
+/// as unbreakable units.
 pub fn wrap_single_segment(
     text: &str,
     first_prefix: &str,
     prefix: &str,
     line_width: usize,
+    templates: &[(String, String)],
 ) -> String {
     let mut result = String::new();
     let mut current_line = String::new();
@@ -219,16 +258,52 @@ pub fn wrap_single_segment(
     // Split into "tokens" where each token is either:
     // - A word (non-space characters) followed by optional spaces
     // - Content inside backticks (treated as a single unbreakable unit)
+    // - Content inside dollar signs (treated as a single unbreakable unit for math)
+    // - Content inside braces (treated as a single unbreakable unit for expressions)
     // - Content inside brackets (treated as a single unbreakable unit for links)
     // We preserve double spaces after periods.
-    let chars = text.chars();
+    let chars: Vec<char> = text.chars().collect();
     let mut current_token = String::new();
     let mut trailing_spaces = String::new();
     let mut in_backticks = false;
+    let mut span_end = None;
     let mut bracket_depth = 0;
 
-    for ch in chars {
-        if ch == '`' && bracket_depth == 0 {
+    for (i, &ch) in chars.iter().enumerate() {
+        if let Some(end) = span_end {
+            // Inside a math span, braces, or a template tag, everything is
+            // part of the token
+            current_token.push(ch);
+            if i == end {
+                span_end = None;
+            }
+        } else if !in_backticks
+            && bracket_depth == 0
+            && let Some(end) = template_end(&chars, i, templates).or_else(|| match ch {
+                '$' => math_span_end(&chars, i),
+                '{' => brace_end(&chars, i),
+                _ => None,
+            })
+        {
+            // Start of math span, braces, or template tag - output the
+            // previous word first
+            if !current_token.is_empty() && !trailing_spaces.is_empty() {
+                add_token_to_line_with_prefix(
+                    &mut result,
+                    &mut current_line,
+                    &current_token,
+                    &trailing_spaces,
+                    first_prefix_width,
+                    prefix,
+                    line_width,
+                    &mut is_first_line,
+                );
+                current_token.clear();
+                trailing_spaces.clear();
+            }
+            current_token.push(ch);
+            span_end = Some(end);
+        } else if ch == '`' && bracket_depth == 0 {
             if in_backticks {
                 // End of backtick region
                 current_token.push(ch);
@@ -328,6 +403,42 @@ pub fn wrap_single_segment(
     result
 }
 
+/// Find the brace that closes the one at `start`, if any.
+fn brace_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (j, &c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(j);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Find the last character of the template tag that opens at `start`, if
+/// any.
+fn template_end(chars: &[char], start: usize, templates: &[(String, String)]) -> Option<usize> {
+    templates
+        .iter()
+        .filter(|(open, close)| !open.is_empty() && !close.is_empty())
+        .find_map(|(open, close)| {
+            let open: Vec<char> = open.chars().collect();
+            let close: Vec<char> = close.chars().collect();
+            if !chars[start..].starts_with(&open) {
+                return None;
+            }
+            (start + open.len()..=chars.len().saturating_sub(close.len()))
+                .find(|&j| chars[j..].starts_with(&close))
+                .map(|j| j + close.len() - 1)
+        })
+}
+
 #[allow(clippy::too_many_arguments)]
 fn add_token_to_line_with_prefix(
     result: &mut String,
@@ -366,3 +477,39 @@ fn add_token_to_line_with_prefix(
         *is_first_line = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_keeps_math_span_together() {
+        let text = "The identity $e^{i \\pi} + 1 = 0$ relates five constants.";
+        assert_eq!(
+            wrap_single_segment(text, "", "", 20, &[]),
+            "The identity\n$e^{i \\pi} + 1 = 0$\nrelates five\nconstants."
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_braces_together() {
+        assert_eq!(
+            wrap_single_segment(
+                "Hello {user.first + user.last} and { unclosed",
+                "",
+                "",
+                16,
+                &[]
+            ),
+            "Hello\n{user.first + user.last}\nand { unclosed"
+        );
+    }
+
+    #[test]
+    fn test_wrap_breaks_between_prices() {
+        assert_eq!(
+            wrap_single_segment("It costs $5 or $10 today", "", "", 12, &[]),
+            "It costs $5\nor $10 today"
+        );
+    }
+}
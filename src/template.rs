@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Templating syntax.
+//!
+//! Jekyll, Hugo, and other static site generators expand template tags such
+//! as `{% include foo.html %}` and `{{< figure src="x" >}}` before rendering
+//! the Markdown, so the tags have to survive formatting untouched.  They are
+//! masked before parsing, in a way that keeps the source positions of
+//! everything else intact:
+//!
+//!  -  Lines that consist of template tags only become HTML comments that
+//!     span the same lines.  The serializer recognizes them by [`MARKER`]
+//!     and emits the original lines verbatim.
+//!  -  Other template tags become code spans of the same length.  The
+//!     serializer emits their original text instead of a code span.
+
+use crate::mdx::skip_code_span;
+use crate::scan::{self, Line};
+
+/// The start of the HTML comment that stands for lines of template tags.
+pub(crate) const MARKER: &str = "<!--template";
+
+/// Check if an HTML block literal stands for masked lines of template tags.
+pub(crate) fn is_masked_block(literal: &str) -> bool {
+    literal.starts_with(MARKER)
+}
+
+/// Mask the template tags of `input` delimited by any of the `delimiters`,
+/// leaving lines that are written back verbatim alone.
+pub(crate) fn mask(input: &str, delimiters: &[(String, String)]) -> String {
+    let delimiters: Vec<(Vec<char>, Vec<char>)> = delimiters
+        .iter()
+        .filter(|(open, close)| !open.is_empty() && !close.is_empty())
+        .map(|(open, close)| (open.chars().collect(), close.chars().collect()))
+        .collect();
+    if delimiters.is_empty() {
+        return input.to_string();
+    }
+    let lines = scan::scan(input);
+    let mut result = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        if line.is_verbatim() {
+            result.push_str(line.text);
+            i += 1;
+            continue;
+        }
+        if line.can_start_block
+            && let Some(end) = tag_lines_end(&lines, i, &delimiters)
+        {
+            for (j, line) in lines.iter().enumerate().take(end + 1).skip(i) {
+                let newline = &line.text[line.content.len()..];
+                if j == i {
+                    result.push_str(MARKER);
+                }
+                if j == end {
+                    result.push_str("-->");
+                }
+                result.push_str(newline);
+            }
+            i = end + 1;
+            continue;
+        }
+        result.push_str(&mask_tags(line.content, &delimiters));
+        result.push_str(&line.text[line.content.len()..]);
+        i += 1;
+    }
+    result
+}
+
+/// If the line at `start` begins with a template tag, and it and the tags
+/// that follow it take up whole lines, find the last of those lines.
+fn tag_lines_end(
+    lines: &[Line<'_>],
+    start: usize,
+    delimiters: &[(Vec<char>, Vec<char>)],
+) -> Option<usize> {
+    let first: Vec<char> = lines[start].text.chars().collect();
+    delimiters
+        .iter()
+        .find(|(open, _)| first.starts_with(open))?;
+    let chars: Vec<char> = lines[start..]
+        .iter()
+        .flat_map(|line| line.text.chars())
+        .collect();
+    let mut pos = 0;
+    loop {
+        pos = tag_end(&chars, pos, delimiters)?;
+        while chars.get(pos).is_some_and(|&c| c == ' ' || c == '\t') {
+            pos += 1;
+        }
+        if chars.get(pos).is_none_or(|&c| c == '\r' || c == '\n') {
+            let newlines = chars[..pos].iter().filter(|&&c| c == '\n').count();
+            return Some(start + newlines);
+        }
+    }
+}
+
+/// If a template tag opens at `start`, find the index right after its
+/// closing delimiter.
+fn tag_end(chars: &[char], start: usize, delimiters: &[(Vec<char>, Vec<char>)]) -> Option<usize> {
+    let (open, close) = delimiters
+        .iter()
+        .find(|(open, _)| chars[start..].starts_with(open))?;
+    (start + open.len()..=chars.len().saturating_sub(close.len()))
+        .find(|&j| chars[j..].starts_with(close))
+        .map(|j| j + close.len())
+}
+
+/// Turn the template tags of a line into code spans of the same length,
+/// leaving code spans alone.
+fn mask_tags(line: &str, delimiters: &[(Vec<char>, Vec<char>)]) -> String {
+    let mut chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '`' => i = skip_code_span(&chars, i),
+            _ => match tag_end(&chars, i, delimiters) {
+                Some(end)
+                    if !chars[i..end].contains(&'`')
+                        && (i == 0 || chars[i - 1] != '`')
+                        && chars.get(end).is_none_or(|&c| c != '`') =>
+                {
+                    chars[i] = '`';
+                    chars[end - 1] = '`';
+                    i = end;
+                }
+                _ => i += 1,
+            },
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn liquid() -> Vec<(String, String)> {
+        vec![
+            ("{{".to_string(), "}}".to_string()),
+            ("{%".to_string(), "%}".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_mask_lines() {
+        let input = "{% if x %}\nText\n{{< figure\n  src=\"x\" >}} {% end %}\n";
+        assert_eq!(
+            mask(input, &liquid()),
+            "<!--template-->\nText\n<!--template\n-->\n"
+        );
+    }
+
+    #[test]
+    fn test_mask_inline_tags() {
+        assert_eq!(
+            mask("Hi {{ page.title }}, `{{ x }}` {% y\n", &liquid()),
+            "Hi `{ page.title }`, `{{ x }}` {% y\n"
+        );
+    }
+
+    #[test]
+    fn test_mask_without_delimiters() {
+        let input = "{{ x }}\n";
+        assert_eq!(mask(input, &[]), input);
+        let input = "~~~\n{% raw %}\n~~~\n";
+        assert_eq!(mask(input, &liquid()), input);
+    }
+
+    #[test]
+    fn test_mask_ignores_verbatim_lines() {
+        let input = "<div>\n  {{ page.title }}\n</div>\n";
+        assert_eq!(mask(input, &liquid()), input);
+        let input = "Para\n\n    {{ a }} and {% b %}\n";
+        assert_eq!(mask(input, &liquid()), input);
+        let input = "> ```\n> {{ x }}\n> ```\n\n - ```\n   {% y %}\n   ```\n";
+        assert_eq!(mask(input, &liquid()), input);
+        let input = "---\ntitle: \"{{ x }}\"\n---\n";
+        assert_eq!(mask(input, &liquid()), input);
+    }
+
+    #[test]
+    fn test_mask_lines_only_where_blocks_start() {
+        assert_eq!(
+            mask("Para\n    {% if x %}\n", &liquid()),
+            "Para\n    `% if x %`\n"
+        );
+    }
+}
@@ -7,8 +7,11 @@ use wasm_bindgen::prelude::*;
 
 use crate::Options;
 use crate::config::{
-    DashPattern, DashSetting, FenceChar, IndentWidth, LeadingSpaces, LineWidth, MinFenceLength,
-    OrderedListPad, OrderedMarker, ThematicBreakStyle, TrailingSpaces, UnorderedMarker,
+    CalloutTarget, CalloutTypeCase, ConflictingLabels, ContainerStyle, DashPattern, DashSetting,
+    DuplicateUrls, ExternalLinks, FenceChar, FrontMatterQuoteStyle, HeadingCase, IndentWidth,
+    LeadingSpaces, LineWidth, MathDelimiters, MinFenceLength, OrderedListPad, OrderedMarker,
+    ProperNounLint, ReferenceLabel, ReferenceOrder, ReferencePlacement, ThematicBreakStyle,
+    TitleCaseStyle, TrailingSpaces, UnorderedMarker, UnusedReferences,
 };
 
 /// JavaScript-friendly options struct.
@@ -29,6 +32,49 @@ pub struct JsOptions {
     /// Convert headings to sentence case (default: false).
     pub heading_sentence_case: Option<bool>,
 
+    /// Warn about the headings sentence case would change instead of
+    /// converting them (default: false).
+    pub heading_sentence_case_report: Option<bool>,
+
+    /// Convert the text of links in headings too (default: true).
+    pub heading_sentence_case_links: Option<bool>,
+
+    /// Convert the header cells of tables to sentence case (default: false).
+    pub heading_sentence_case_table_headers: Option<bool>,
+
+    /// Convert the terms of description lists to sentence case
+    /// (default: false).
+    pub heading_sentence_case_terms: Option<bool>,
+
+    /// How the case of headings is converted: "sentence", "title", or
+    /// "preserve" (default: "preserve").
+    pub heading_case: Option<String>,
+
+    /// The rules title case follows: "chicago" or "ap" (default: "chicago").
+    pub heading_title_case_style: Option<String>,
+
+    /// Level to shift the shallowest heading to, or 0 to leave the levels
+    /// alone (default: 0).
+    pub heading_top_level: Option<u8>,
+
+    /// Shift headings so that the first one becomes an h1 (default: false).
+    pub heading_promote_first: Option<bool>,
+
+    /// Close gaps in the heading hierarchy (default: false).
+    pub heading_fix_skipped_levels: Option<bool>,
+
+    /// Prefix headings with hierarchical section numbers (default: false).
+    pub heading_numbering: Option<bool>,
+
+    /// Highest heading level to number (default: 2).
+    pub heading_numbering_min_level: Option<u8>,
+
+    /// Lowest heading level to number (default: 6).
+    pub heading_numbering_max_level: Option<u8>,
+
+    /// Also replace plain numbers that headings start with (default: false).
+    pub heading_numbering_replace_plain: Option<bool>,
+
     /// Additional proper nouns to preserve in sentence case.
     /// These are merged with built-in proper nouns.
     pub heading_proper_nouns: Option<Vec<String>>,
@@ -37,6 +83,9 @@ pub struct JsOptions {
     /// These are excluded from built-in proper nouns.
     pub heading_common_nouns: Option<Vec<String>>,
 
+    /// Categories of built-in proper nouns to leave out in sentence case.
+    pub heading_disabled_categories: Option<Vec<String>>,
+
     /// Marker for unordered lists: "-", "*", or "+" (default: "-").
     pub unordered_marker: Option<String>,
 
@@ -96,6 +145,118 @@ pub struct JsOptions {
 
     /// Em-dash setting: false to disable, or a string pattern (default: "--").
     pub em_dash: Option<JsDashSetting>,
+
+    /// Normalize YAML and TOML front matter (default: false).
+    pub front_matter_canonicalize: Option<bool>,
+
+    /// Indent width for canonicalized front matter (default: 2).
+    pub front_matter_indent_width: Option<usize>,
+
+    /// Quoting style for front matter strings: "preserve", "double", or
+    /// "single" (default: "preserve").
+    pub front_matter_quote_style: Option<String>,
+
+    /// Top-level front matter keys to move to the beginning (default: []).
+    pub front_matter_key_order: Option<Vec<String>>,
+
+    /// Unused reference definitions: "remove", "keep", or "warn"
+    /// (default: "remove").
+    pub link_unused_references: Option<String>,
+
+    /// Labels pointing to the same URL: "keep", "warn", or "merge"
+    /// (default: "keep").
+    pub link_duplicate_urls: Option<String>,
+
+    /// Labels defined twice with different URLs: "warn" or "ignore"
+    /// (default: "warn").
+    pub link_conflicting_labels: Option<String>,
+
+    /// Where reference and footnote definitions are placed: "section",
+    /// "section-h2", "document", or "block" (default: "section").
+    pub link_reference_placement: Option<String>,
+
+    /// Whether external links are converted: "reference" or "inline"
+    /// (default: "reference").
+    pub link_external_links: Option<String>,
+
+    /// Convert only links whose URL is wider than this (default: 0).
+    pub link_min_url_width: Option<usize>,
+
+    /// Additional URL schemes treated as external (default: []).
+    pub link_external_schemes: Option<Vec<String>>,
+
+    /// Domains whose URLs are treated as external (default: []).
+    pub link_external_domains: Option<Vec<String>>,
+
+    /// Labels of converted links: "text", "numeric", or "domain"
+    /// (default: "text").
+    pub link_reference_label: Option<String>,
+
+    /// Order of reference definitions: "auto", "appearance", "alphabetical",
+    /// or "numeric-then-alpha" (default: "auto").
+    pub link_reference_order: Option<String>,
+
+    /// Renumber numeric reference labels in order of first use
+    /// (default: false).
+    pub link_renumber_references: Option<bool>,
+
+    /// Recognize `~~strikethrough~~` (default: false).
+    pub extension_strikethrough: Option<bool>,
+
+    /// Recognize `^superscript^` (default: false).
+    pub extension_superscript: Option<bool>,
+
+    /// Recognize `~subscript~` (default: false).
+    pub extension_subscript: Option<bool>,
+
+    /// Recognize `$inline$` and `$$display$$` math (default: false).
+    pub extension_math: Option<bool>,
+
+    /// How display math blocks are delimited: "dollar" or "fence"
+    /// (default: "dollar").
+    pub extension_math_delimiters: Option<String>,
+
+    /// Recognize bare URLs, `www.` links, and email addresses (default: false).
+    pub extension_autolink: Option<bool>,
+
+    /// Recognize `__underline__` instead of `__strong__` (default: false).
+    pub extension_underline: Option<bool>,
+
+    /// Recognize `||spoiler||` (default: false).
+    pub extension_spoiler: Option<bool>,
+
+    /// Recognize `:emoji:` shortcodes (default: false).
+    pub extension_shortcodes: Option<bool>,
+
+    /// Recognize `[[wikilinks]]` and `![[embeds]]` (default: false).
+    pub extension_wikilinks: Option<bool>,
+
+    /// Treat the document as MDX (default: false).
+    pub extension_mdx: Option<bool>,
+
+    /// Recognize `:::type` containers and fenced divs (default: false).
+    pub extension_containers: Option<bool>,
+
+    /// Convert between alert containers and alerts: "preserve", "alert", or
+    /// "container" (default: "preserve").
+    pub extension_container_style: Option<String>,
+
+    /// Recognize Obsidian-style callouts of any type (default: false).
+    pub extension_callouts: Option<bool>,
+
+    /// Callout type case: "upper", "lower", or "preserve" (default: "upper").
+    pub extension_callout_type_case: Option<String>,
+
+    /// Callout target: "any" or "github" (default: "any").
+    pub extension_callout_target: Option<String>,
+
+    /// Pairs of template tag delimiters, e.g., `[["{{", "}}"]]`
+    /// (default: []).
+    pub template_delimiters: Option<Vec<(String, String)>>,
+
+    /// What to do about proper nouns written in the wrong case in the body
+    /// text: "off", "warn", or "fix" (default: "off").
+    pub lint_proper_nouns: Option<String>,
 }
 
 /// JavaScript-friendly dash setting.
@@ -141,12 +302,61 @@ impl JsOptions {
         if let Some(v) = self.heading_sentence_case {
             opts.heading_sentence_case = v;
         }
+        if let Some(v) = self.heading_sentence_case_report {
+            opts.heading_sentence_case_report = v;
+        }
+        if let Some(v) = self.heading_sentence_case_links {
+            opts.heading_sentence_case_links = v;
+        }
+        if let Some(v) = self.heading_sentence_case_table_headers {
+            opts.heading_sentence_case_table_headers = v;
+        }
+        if let Some(v) = self.heading_sentence_case_terms {
+            opts.heading_sentence_case_terms = v;
+        }
+        if let Some(ref v) = self.heading_case {
+            opts.heading_case = match v.as_str() {
+                "sentence" => HeadingCase::Sentence,
+                "title" => HeadingCase::Title,
+                _ => HeadingCase::Preserve,
+            };
+        }
+        if let Some(ref v) = self.heading_title_case_style {
+            opts.heading_title_case_style = match v.as_str() {
+                "ap" => TitleCaseStyle::Ap,
+                _ => TitleCaseStyle::Chicago,
+            };
+        }
+        if let Some(v) = self.heading_top_level {
+            opts.heading_top_level = v;
+        }
+        if let Some(v) = self.heading_promote_first {
+            opts.heading_promote_first = v;
+        }
+        if let Some(v) = self.heading_fix_skipped_levels {
+            opts.heading_fix_skipped_levels = v;
+        }
+        if let Some(v) = self.heading_numbering {
+            opts.heading_numbering = v;
+        }
+        if let Some(v) = self.heading_numbering_min_level {
+            opts.heading_numbering_min_level = v;
+        }
+        if let Some(v) = self.heading_numbering_max_level {
+            opts.heading_numbering_max_level = v;
+        }
+        if let Some(v) = self.heading_numbering_replace_plain {
+            opts.heading_numbering_replace_plain = v;
+        }
         if let Some(ref v) = self.heading_proper_nouns {
             opts.heading_proper_nouns = v.clone();
         }
         if let Some(ref v) = self.heading_common_nouns {
             opts.heading_common_nouns = v.clone();
         }
+        if let Some(ref v) = self.heading_disabled_categories {
+            opts.heading_disabled_categories = v.clone();
+        }
         if let Some(ref v) = self.unordered_marker {
             opts.unordered_marker = match v.as_str() {
                 "*" => UnorderedMarker::Asterisk,
@@ -237,6 +447,157 @@ impl JsOptions {
         if let Some(ref v) = self.em_dash {
             opts.em_dash = v.to_dash_setting();
         }
+        if let Some(v) = self.front_matter_canonicalize {
+            opts.front_matter_canonicalize = v;
+        }
+        if let Some(v) = self.front_matter_indent_width {
+            if let Ok(width) = IndentWidth::new(v) {
+                opts.front_matter_indent_width = width;
+            }
+        }
+        if let Some(ref v) = self.front_matter_quote_style {
+            opts.front_matter_quote_style = match v.as_str() {
+                "double" => FrontMatterQuoteStyle::Double,
+                "single" => FrontMatterQuoteStyle::Single,
+                _ => FrontMatterQuoteStyle::Preserve,
+            };
+        }
+        if let Some(ref v) = self.front_matter_key_order {
+            opts.front_matter_key_order = v.clone();
+        }
+        if let Some(ref v) = self.link_unused_references {
+            opts.link_unused_references = match v.as_str() {
+                "keep" => UnusedReferences::Keep,
+                "warn" => UnusedReferences::Warn,
+                _ => UnusedReferences::Remove,
+            };
+        }
+        if let Some(ref v) = self.link_duplicate_urls {
+            opts.link_duplicate_urls = match v.as_str() {
+                "warn" => DuplicateUrls::Warn,
+                "merge" => DuplicateUrls::Merge,
+                _ => DuplicateUrls::Keep,
+            };
+        }
+        if let Some(ref v) = self.link_conflicting_labels {
+            opts.link_conflicting_labels = match v.as_str() {
+                "ignore" => ConflictingLabels::Ignore,
+                _ => ConflictingLabels::Warn,
+            };
+        }
+        if let Some(ref v) = self.link_reference_placement {
+            opts.link_reference_placement = match v.as_str() {
+                "section-h2" => ReferencePlacement::SectionH2,
+                "document" => ReferencePlacement::Document,
+                "block" => ReferencePlacement::Block,
+                _ => ReferencePlacement::Section,
+            };
+        }
+        if let Some(ref v) = self.link_external_links {
+            opts.link_external_links = match v.as_str() {
+                "inline" => ExternalLinks::Inline,
+                _ => ExternalLinks::Reference,
+            };
+        }
+        if let Some(v) = self.link_min_url_width {
+            opts.link_min_url_width = v;
+        }
+        if let Some(ref v) = self.link_external_schemes {
+            opts.link_external_schemes = v.clone();
+        }
+        if let Some(ref v) = self.link_external_domains {
+            opts.link_external_domains = v.clone();
+        }
+        if let Some(ref v) = self.link_reference_label {
+            opts.link_reference_label = match v.as_str() {
+                "numeric" => ReferenceLabel::Numeric,
+                "domain" => ReferenceLabel::Domain,
+                _ => ReferenceLabel::Text,
+            };
+        }
+        if let Some(ref v) = self.link_reference_order {
+            opts.link_reference_order = match v.as_str() {
+                "appearance" => ReferenceOrder::Appearance,
+                "alphabetical" => ReferenceOrder::Alphabetical,
+                "numeric-then-alpha" => ReferenceOrder::NumericThenAlpha,
+                _ => ReferenceOrder::Auto,
+            };
+        }
+        if let Some(v) = self.link_renumber_references {
+            opts.link_renumber_references = v;
+        }
+        if let Some(v) = self.extension_strikethrough {
+            opts.extension_strikethrough = v;
+        }
+        if let Some(v) = self.extension_superscript {
+            opts.extension_superscript = v;
+        }
+        if let Some(v) = self.extension_subscript {
+            opts.extension_subscript = v;
+        }
+        if let Some(v) = self.extension_math {
+            opts.extension_math = v;
+        }
+        if let Some(ref v) = self.extension_math_delimiters {
+            opts.extension_math_delimiters = match v.as_str() {
+                "fence" => MathDelimiters::Fence,
+                _ => MathDelimiters::Dollar,
+            };
+        }
+        if let Some(v) = self.extension_autolink {
+            opts.extension_autolink = v;
+        }
+        if let Some(v) = self.extension_underline {
+            opts.extension_underline = v;
+        }
+        if let Some(v) = self.extension_spoiler {
+            opts.extension_spoiler = v;
+        }
+        if let Some(v) = self.extension_shortcodes {
+            opts.extension_shortcodes = v;
+        }
+        if let Some(v) = self.extension_wikilinks {
+            opts.extension_wikilinks = v;
+        }
+        if let Some(v) = self.extension_mdx {
+            opts.extension_mdx = v;
+        }
+        if let Some(v) = self.extension_containers {
+            opts.extension_containers = v;
+        }
+        if let Some(ref v) = self.extension_container_style {
+            opts.extension_container_style = match v.as_str() {
+                "alert" => ContainerStyle::Alert,
+                "container" => ContainerStyle::Container,
+                _ => ContainerStyle::Preserve,
+            };
+        }
+        if let Some(v) = self.extension_callouts {
+            opts.extension_callouts = v;
+        }
+        if let Some(ref v) = self.extension_callout_type_case {
+            opts.extension_callout_type_case = match v.as_str() {
+                "lower" => CalloutTypeCase::Lower,
+                "preserve" => CalloutTypeCase::Preserve,
+                _ => CalloutTypeCase::Upper,
+            };
+        }
+        if let Some(ref v) = self.extension_callout_target {
+            opts.extension_callout_target = match v.as_str() {
+                "github" => CalloutTarget::Github,
+                _ => CalloutTarget::Any,
+            };
+        }
+        if let Some(ref v) = self.template_delimiters {
+            opts.template_delimiters = v.clone();
+        }
+        if let Some(ref v) = self.lint_proper_nouns {
+            opts.lint_proper_nouns = match v.as_str() {
+                "warn" => ProperNounLint::Warn,
+                "fix" => ProperNounLint::Fix,
+                _ => ProperNounLint::Off,
+            };
+        }
 
         opts
     }
@@ -280,6 +641,7 @@ pub fn format(input: &str, options: JsValue) -> Result<String, JsError> {
     };
 
     let opts = js_opts.to_options();
+    crate::validate_proper_noun_categories(&opts).map_err(|e| JsError::new(&e.to_string()))?;
     crate::format(input, &opts).map_err(|e| JsError::new(&e.to_string()))
 }
 
@@ -302,6 +664,7 @@ pub fn format_with_warnings(input: &str, options: JsValue) -> Result<JsValue, Js
     };
 
     let opts = js_opts.to_options();
+    crate::validate_proper_noun_categories(&opts).map_err(|e| JsError::new(&e.to_string()))?;
     let result =
         crate::format_with_warnings(input, &opts).map_err(|e| JsError::new(&e.to_string()))?;
 
@@ -338,7 +701,7 @@ pub fn format_with_code_formatter(
     options: JsValue,
     code_formatter: Option<js_sys::Function>,
 ) -> Result<JsValue, JsError> {
-    use comrak::{Arena, Options as ComrakOptions, parse_document};
+    use comrak::Arena;
 
     let js_opts: JsOptions = if options.is_undefined() || options.is_null() {
         JsOptions::default()
@@ -347,6 +710,7 @@ pub fn format_with_code_formatter(
     };
 
     let opts = js_opts.to_options();
+    crate::validate_proper_noun_categories(&opts).map_err(|e| JsError::new(&e.to_string()))?;
 
     if input.is_empty() {
         let js_result = JsFormatResult {
@@ -356,16 +720,10 @@ pub fn format_with_code_formatter(
         return serde_wasm_bindgen::to_value(&js_result).map_err(|e| JsError::new(&e.to_string()));
     }
 
-    let arena = Arena::new();
-    let mut comrak_options = ComrakOptions::default();
-    comrak_options.extension.front_matter_delimiter = Some("---".to_string());
-    comrak_options.extension.table = true;
-    comrak_options.extension.description_lists = true;
-    comrak_options.extension.alerts = true;
-    comrak_options.extension.footnotes = true;
-    comrak_options.extension.tasklist = true;
+    let (opts, mut warnings) = crate::front_matter::apply_overrides(input, &opts);
 
-    let root = parse_document(&arena, input, &comrak_options);
+    let arena = Arena::new();
+    let root = crate::parse_document(&arena, input, &opts);
 
     // Create callback closure if provided
     let callback: crate::serializer::CodeFormatterCallback = code_formatter.map(|func| {
@@ -389,11 +747,11 @@ pub fn format_with_code_formatter(
 
     let result =
         crate::serializer::serialize_with_code_formatter(root, &opts, Some(input), callback);
+    warnings.extend(result.warnings);
 
     let js_result = JsFormatResult {
         output: result.output,
-        warnings: result
-            .warnings
+        warnings: warnings
             .into_iter()
             .map(|w| JsWarning {
                 line: w.line,
@@ -456,6 +814,72 @@ mod tests {
         assert!(opts.heading_sentence_case);
     }
 
+    #[test]
+    fn test_js_options_heading_sentence_case_report() {
+        let js_opts = JsOptions {
+            heading_sentence_case_report: Some(true),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert!(opts.heading_sentence_case_report);
+    }
+
+    #[test]
+    fn test_js_options_heading_sentence_case_extents() {
+        let js_opts = JsOptions {
+            heading_sentence_case_links: Some(false),
+            heading_sentence_case_table_headers: Some(true),
+            heading_sentence_case_terms: Some(true),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert!(!opts.heading_sentence_case_links);
+        assert!(opts.heading_sentence_case_table_headers);
+        assert!(opts.heading_sentence_case_terms);
+    }
+
+    #[test]
+    fn test_js_options_heading_case() {
+        let js_opts = JsOptions {
+            heading_case: Some("title".to_string()),
+            heading_title_case_style: Some("ap".to_string()),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert_eq!(opts.heading_case, HeadingCase::Title);
+        assert_eq!(opts.heading_title_case_style, TitleCaseStyle::Ap);
+    }
+
+    #[test]
+    fn test_js_options_heading_levels() {
+        let js_opts = JsOptions {
+            heading_top_level: Some(2),
+            heading_promote_first: Some(true),
+            heading_fix_skipped_levels: Some(true),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert_eq!(opts.heading_top_level, 2);
+        assert!(opts.heading_promote_first);
+        assert!(opts.heading_fix_skipped_levels);
+    }
+
+    #[test]
+    fn test_js_options_heading_numbering() {
+        let js_opts = JsOptions {
+            heading_numbering: Some(true),
+            heading_numbering_min_level: Some(1),
+            heading_numbering_max_level: Some(3),
+            heading_numbering_replace_plain: Some(true),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert!(opts.heading_numbering);
+        assert_eq!(opts.heading_numbering_min_level, 1);
+        assert_eq!(opts.heading_numbering_max_level, 3);
+        assert!(opts.heading_numbering_replace_plain);
+    }
+
     #[test]
     fn test_js_options_heading_proper_nouns() {
         let js_opts = JsOptions {
@@ -476,6 +900,16 @@ mod tests {
         assert_eq!(opts.heading_common_nouns, vec!["react"]);
     }
 
+    #[test]
+    fn test_js_options_heading_disabled_categories() {
+        let js_opts = JsOptions {
+            heading_disabled_categories: Some(vec!["countries".to_string()]),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert_eq!(opts.heading_disabled_categories, vec!["countries"]);
+    }
+
     #[test]
     fn test_js_options_heading_all() {
         let js_opts = JsOptions {
@@ -489,4 +923,104 @@ mod tests {
         assert_eq!(opts.heading_proper_nouns, vec!["Fedify"]);
         assert_eq!(opts.heading_common_nouns, vec!["api"]);
     }
+
+    #[test]
+    fn test_js_options_front_matter() {
+        let js_opts = JsOptions {
+            front_matter_canonicalize: Some(true),
+            front_matter_indent_width: Some(4),
+            front_matter_quote_style: Some("single".to_string()),
+            front_matter_key_order: Some(vec!["title".to_string()]),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert!(opts.front_matter_canonicalize);
+        assert_eq!(opts.front_matter_indent_width.get(), 4);
+        assert_eq!(opts.front_matter_quote_style, FrontMatterQuoteStyle::Single);
+        assert_eq!(opts.front_matter_key_order, vec!["title"]);
+    }
+
+    #[test]
+    fn test_js_options_templates() {
+        let js_opts = JsOptions {
+            template_delimiters: Some(vec![("{%".to_string(), "%}".to_string())]),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert_eq!(
+            opts.template_delimiters,
+            vec![("{%".to_string(), "%}".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_js_options_lint_proper_nouns() {
+        let js_opts = JsOptions {
+            lint_proper_nouns: Some("fix".to_string()),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert_eq!(opts.lint_proper_nouns, ProperNounLint::Fix);
+    }
+
+    #[test]
+    fn test_js_options_extensions() {
+        let js_opts = JsOptions {
+            extension_strikethrough: Some(true),
+            extension_math: Some(true),
+            extension_math_delimiters: Some("fence".to_string()),
+            extension_autolink: Some(true),
+            extension_wikilinks: Some(true),
+            extension_mdx: Some(true),
+            extension_containers: Some(true),
+            extension_container_style: Some("alert".to_string()),
+            extension_callouts: Some(true),
+            extension_callout_type_case: Some("lower".to_string()),
+            extension_callout_target: Some("github".to_string()),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert!(opts.extension_strikethrough);
+        assert!(opts.extension_math);
+        assert_eq!(opts.extension_math_delimiters, MathDelimiters::Fence);
+        assert!(opts.extension_autolink);
+        assert!(opts.extension_wikilinks);
+        assert!(opts.extension_mdx);
+        assert!(opts.extension_containers);
+        assert_eq!(opts.extension_container_style, ContainerStyle::Alert);
+        assert!(opts.extension_callouts);
+        assert_eq!(opts.extension_callout_type_case, CalloutTypeCase::Lower);
+        assert_eq!(opts.extension_callout_target, CalloutTarget::Github);
+        assert!(!opts.extension_underline);
+    }
+
+    #[test]
+    fn test_js_options_link() {
+        let js_opts = JsOptions {
+            link_unused_references: Some("warn".to_string()),
+            link_duplicate_urls: Some("merge".to_string()),
+            link_conflicting_labels: Some("ignore".to_string()),
+            link_reference_placement: Some("section-h2".to_string()),
+            link_external_links: Some("inline".to_string()),
+            link_min_url_width: Some(30),
+            link_external_schemes: Some(vec!["mailto".to_string()]),
+            link_external_domains: Some(vec!["example.com".to_string()]),
+            link_reference_label: Some("domain".to_string()),
+            link_reference_order: Some("numeric-then-alpha".to_string()),
+            link_renumber_references: Some(true),
+            ..Default::default()
+        };
+        let opts = js_opts.to_options();
+        assert_eq!(opts.link_unused_references, UnusedReferences::Warn);
+        assert_eq!(opts.link_duplicate_urls, DuplicateUrls::Merge);
+        assert_eq!(opts.link_conflicting_labels, ConflictingLabels::Ignore);
+        assert_eq!(opts.link_reference_placement, ReferencePlacement::SectionH2);
+        assert_eq!(opts.link_external_links, ExternalLinks::Inline);
+        assert_eq!(opts.link_min_url_width, 30);
+        assert_eq!(opts.link_external_schemes, vec!["mailto"]);
+        assert_eq!(opts.link_external_domains, vec!["example.com"]);
+        assert_eq!(opts.link_reference_label, ReferenceLabel::Domain);
+        assert_eq!(opts.link_reference_order, ReferenceOrder::NumericThenAlpha);
+        assert!(opts.link_renumber_references);
+    }
 }
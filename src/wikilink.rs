@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2025 Hong Minhee <https://hongminhee.org/>
+// SPDX-License-Identifier: GPL-3.0-or-later
+//! Wikilink embeds.
+//!
+//! Comrak parses `[[Page]]` wikilinks, but not Obsidian's `![[image.png]]`
+//! embeds: the `!` opens an image instead.  So each `![[` is turned into
+//! `[[!` before parsing, which makes the embed a wikilink spanning the same
+//! columns.  The serializer writes wikilinks back from the source.
+
+use crate::mdx::skip_code_span;
+use crate::scan;
+
+/// Turn the wikilink embeds of `input` into wikilinks whose target starts
+/// with `!`, leaving code spans and lines that are written back verbatim
+/// alone.
+pub(crate) fn mask(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    for line in scan::scan(input) {
+        if line.is_verbatim() {
+            result.push_str(line.text);
+            continue;
+        }
+        result.push_str(&mask_embeds(line.content));
+        result.push_str(&line.text[line.content.len()..]);
+    }
+    result
+}
+
+/// Turn the `![[` of the embeds in a line into `[[!`.
+fn mask_embeds(line: &str) -> String {
+    let mut chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '`' => i = skip_code_span(&chars, i),
+            '!' if chars[i + 1..].starts_with(&['[', '[']) => {
+                chars[i..i + 3].copy_from_slice(&['[', '[', '!']);
+                i += 3;
+            }
+            _ => i += 1,
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(
+            mask("See ![[a_b.png]] and [[Page]], not `![[x]]` or \\![[y]].\n"),
+            "See [[!a_b.png]] and [[Page]], not `![[x]]` or \\![[y]].\n"
+        );
+        let input = "~~~\n![[x]]\n~~~\n";
+        assert_eq!(mask(input), input);
+    }
+
+    #[test]
+    fn test_mask_ignores_verbatim_lines() {
+        let input = "<div>\n![[in html]]\n</div>\n\nPara\n\n    ![[in code]]\n";
+        assert_eq!(mask(input), input);
+        let input = "> ```\n> ![[in code]]\n> ```\n";
+        assert_eq!(mask(input), input);
+    }
+}
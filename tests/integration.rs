@@ -534,6 +534,150 @@ mod cli_tests {
         assert_eq!(exit_code, 0, "All files should pass check");
         assert!(stdout.is_empty());
     }
+
+    /// Test that .mdx files are formatted as MDX, and picked up from
+    /// directories only when MDX mode is enabled.
+    #[test]
+    fn test_mdx_files() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let docs_dir = temp_dir.path().join("docs");
+        fs::create_dir(&docs_dir).expect("Failed to create docs dir");
+        let page = docs_dir.join("page.mdx");
+        fs::write(
+            &page,
+            "import {Note} from './note'\n\n# Page\n\n<Note>\n</Note>\n",
+        )
+        .expect("Failed to write page.mdx");
+        let plain_config = temp_dir.path().join("plain.toml");
+        fs::write(&plain_config, "").expect("Failed to write plain.toml");
+        let mdx_config = temp_dir.path().join("mdx.toml");
+        fs::write(&mdx_config, "[extensions]\nmdx = true\n").expect("Failed to write mdx.toml");
+
+        let (stdout, _stderr, exit_code) = run_hongdown(
+            &[
+                "--config",
+                plain_config.to_str().unwrap(),
+                page.to_str().unwrap(),
+            ],
+            None,
+        );
+        assert_eq!(exit_code, 0);
+        assert_eq!(
+            stdout,
+            "import {Note} from './note'\n\nPage\n====\n\n<Note>\n</Note>\n"
+        );
+
+        let (_stdout, _stderr, exit_code) = run_hongdown(
+            &[
+                "--config",
+                plain_config.to_str().unwrap(),
+                "--check",
+                docs_dir.to_str().unwrap(),
+            ],
+            None,
+        );
+        assert_eq!(exit_code, 0, "MDX files should not be collected");
+
+        let (_stdout, _stderr, exit_code) = run_hongdown(
+            &[
+                "--config",
+                mdx_config.to_str().unwrap(),
+                "--check",
+                docs_dir.to_str().unwrap(),
+            ],
+            None,
+        );
+        assert_eq!(exit_code, 1, "MDX files should be collected and checked");
+    }
+
+    /// Test that --check-links reports broken relative links across files.
+    #[test]
+    fn test_check_links() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let docs_dir = temp_dir.path().join("docs");
+        fs::create_dir(&docs_dir).expect("Failed to create docs dir");
+        fs::write(
+            docs_dir.join("guide.md"),
+            "Guide\n=====\n\nSee the [index](../index.md#usage).\n",
+        )
+        .expect("Failed to write guide.md");
+        fs::write(
+            temp_dir.path().join("index.md"),
+            "Index\n=====\n\nRead the [guide](docs/guide.md), [setup](docs/guide.md#setup),\nand [missing](missing.md).\n\n\nUsage\n-----\n",
+        )
+        .expect("Failed to write index.md");
+
+        let (_stdout, stderr, exit_code) = run_hongdown(
+            &[
+                "--check",
+                "--check-links",
+                temp_dir.path().to_str().unwrap(),
+            ],
+            None,
+        );
+
+        assert_eq!(exit_code, 0, "stderr: {}", stderr);
+        assert!(
+            stderr.contains("index.md:4: warning: broken link: docs/guide.md#setup"),
+            "got stderr: {}",
+            stderr
+        );
+        assert!(
+            stderr.contains("index.md:5: warning: broken link: missing.md (file not found)"),
+            "got stderr: {}",
+            stderr
+        );
+        assert!(!stderr.contains("guide.md:4"), "got stderr: {}", stderr);
+    }
+
+    /// Test sentence case report mode warns without rewriting headings and
+    /// summarizes the unknown words of all files.
+    #[test]
+    fn test_sentence_case_report() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(&config_path, "[heading]\nsentence_case = \"report\"\n")
+            .expect("Failed to write config");
+        let first = "About Fedify\n============\n\n### Fedify On Deno\n";
+        fs::write(temp_dir.path().join("a.md"), first).expect("Failed to write a.md");
+        fs::write(temp_dir.path().join("b.md"), "Using Fedify\n============\n")
+            .expect("Failed to write b.md");
+
+        let (_stdout, stderr, exit_code) = run_hongdown(
+            &[
+                "--check",
+                "--config",
+                config_path.to_str().unwrap(),
+                temp_dir.path().to_str().unwrap(),
+            ],
+            None,
+        );
+
+        assert_eq!(exit_code, 0, "stderr: {}", stderr);
+        assert!(
+            stderr.contains(
+                "a.md:4: warning: sentence case would change heading to \"Fedify on Deno\"; \
+                 lowercased words: On"
+            ),
+            "got stderr: {}",
+            stderr
+        );
+        assert!(stderr.contains("  Fedify (2)\n"), "got stderr: {}", stderr);
+        assert!(!stderr.contains("  On ("), "got stderr: {}", stderr);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("a.md")).unwrap(),
+            first
+        );
+    }
 }
 
 /// Test proper nouns directive in sentence case.
@@ -606,6 +750,75 @@ Some content.
     );
 }
 
+/// Test options overridden by a `hongdown` key in YAML front matter.
+#[test]
+fn test_front_matter_yaml_overrides() {
+    let input = r#"---
+title: Release notes
+hongdown:
+  line_width: 40
+  heading:
+    sentence_case: true
+    proper_nouns: [Hongdown]
+---
+
+# Introducing Hongdown Release Notes
+
+This paragraph is long enough that it has to be wrapped at forty columns.
+"#;
+
+    let result = format(input, &Options::default()).unwrap();
+
+    assert!(
+        result.starts_with("---\ntitle: Release notes\nhongdown:\n"),
+        "Front matter should be preserved verbatim, got:\n{}",
+        result
+    );
+    assert!(
+        result.contains("Introducing Hongdown release notes\n"),
+        "Heading should be converted to sentence case, got:\n{}",
+        result
+    );
+    assert!(
+        result.contains(
+            "This paragraph is long enough that it\nhas to be wrapped at forty columns.\n"
+        ),
+        "Paragraph should be wrapped at 40 columns, got:\n{}",
+        result
+    );
+}
+
+/// Test that front matter overrides do not leak into other documents.
+#[test]
+fn test_front_matter_overrides_are_per_document() {
+    let options = Options {
+        line_width: LineWidth::new(40).unwrap(),
+        ..Options::default()
+    };
+    let with_override = "---\nhongdown:\n  line_width: 100\n---\n\nThis paragraph is long enough that it would be wrapped at forty columns.\n";
+    let without_override =
+        "This paragraph is long enough that it would be wrapped at forty columns.\n";
+
+    let result = format(with_override, &options).unwrap();
+    assert!(
+        result.ends_with(
+            "\nThis paragraph is long enough that it would be wrapped at forty columns.\n"
+        )
+    );
+    let result = format(without_override, &options).unwrap();
+    assert!(result.starts_with("This paragraph is long enough that it\n"));
+}
+
+/// Test that invalid front matter overrides produce a warning.
+#[test]
+fn test_front_matter_invalid_overrides_warning() {
+    let input = "---\ntitle: Test\nhongdown:\n  line_width: 4\n---\n\nText.\n";
+    let result = hongdown::format_with_warnings(input, &Options::default()).unwrap();
+    assert_eq!(result.output, input);
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.warnings[0].line, 3);
+}
+
 // ============================================================================
 // Code block formatter integration tests
 // ============================================================================